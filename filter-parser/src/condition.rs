@@ -3,6 +3,10 @@
 //! ```text
 //! condition      = value ("==" | ">" ...) value
 //! to             = value value TO value
+//! null           = value "IS" WS+ "NULL"
+//! empty          = value "IS" WS+ "EMPTY"
+//! starts_with    = value "STARTS" WS+ "WITH" WS+ value
+//! contains       = value "CONTAINS" WS+ value
 //! ```
 
 use nom::branch::alt;
@@ -12,6 +16,7 @@ use nom::combinator::cut;
 use nom::sequence::{terminated, tuple};
 use Condition::*;
 
+use crate::value::word_exact;
 use crate::{parse_value, FilterCondition, IResult, Span, Token};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,9 +26,13 @@ pub enum Condition<'a> {
     Equal(Token<'a>),
     NotEqual(Token<'a>),
     Exists,
+    Null,
+    Empty,
     LowerThan(Token<'a>),
     LowerThanOrEqual(Token<'a>),
     Between { from: Token<'a>, to: Token<'a> },
+    StartsWith(Token<'a>),
+    Contains(Token<'a>),
 }
 
 /// condition      = value ("==" | ">" ...) value
@@ -58,6 +67,42 @@ pub fn parse_not_exists(input: Span) -> IResult<FilterCondition> {
     Ok((input, FilterCondition::Not(Box::new(FilterCondition::Condition { fid: key, op: Exists }))))
 }
 
+/// null           = value "IS" WS+ "NULL"
+pub fn parse_is_null(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, _, _)) =
+        tuple((parse_value, tag("IS"), multispace1, word_exact("NULL")))(input)?;
+
+    Ok((input, FilterCondition::Condition { fid, op: Null }))
+}
+
+/// not_null       = value "IS" WS+ "NOT" WS+ "NULL"
+pub fn parse_is_not_null(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, _, _, _, _)) =
+        tuple((parse_value, tag("IS"), multispace1, tag("NOT"), multispace1, word_exact("NULL")))(
+            input,
+        )?;
+
+    Ok((input, FilterCondition::Not(Box::new(FilterCondition::Condition { fid, op: Null }))))
+}
+
+/// empty          = value "IS" WS+ "EMPTY"
+pub fn parse_is_empty(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, _, _)) =
+        tuple((parse_value, tag("IS"), multispace1, word_exact("EMPTY")))(input)?;
+
+    Ok((input, FilterCondition::Condition { fid, op: Empty }))
+}
+
+/// not_empty      = value "IS" WS+ "NOT" WS+ "EMPTY"
+pub fn parse_is_not_empty(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, _, _, _, _)) =
+        tuple((parse_value, tag("IS"), multispace1, tag("NOT"), multispace1, word_exact("EMPTY")))(
+            input,
+        )?;
+
+    Ok((input, FilterCondition::Not(Box::new(FilterCondition::Condition { fid, op: Empty }))))
+}
+
 /// to             = value value "TO" WS+ value
 pub fn parse_to(input: Span) -> IResult<FilterCondition> {
     let (input, (key, from, _, _, to)) =
@@ -65,3 +110,48 @@ pub fn parse_to(input: Span) -> IResult<FilterCondition> {
 
     Ok((input, FilterCondition::Condition { fid: key, op: Between { from, to } }))
 }
+
+/// starts_with    = value "STARTS" WS+ "WITH" WS+ value
+pub fn parse_starts_with(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, _, _, value)) =
+        tuple((parse_value, tag("STARTS"), multispace1, word_exact("WITH"), cut(parse_value)))(
+            input,
+        )?;
+
+    Ok((input, FilterCondition::Condition { fid, op: StartsWith(value) }))
+}
+
+/// not_starts_with = value "NOT" WS+ "STARTS" WS+ "WITH" WS+ value
+pub fn parse_not_starts_with(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, _, _, _, _, value)) = tuple((
+        parse_value,
+        tag("NOT"),
+        multispace1,
+        tag("STARTS"),
+        multispace1,
+        word_exact("WITH"),
+        cut(parse_value),
+    ))(input)?;
+
+    let condition = FilterCondition::Condition { fid, op: StartsWith(value) };
+    Ok((input, FilterCondition::Not(Box::new(condition))))
+}
+
+/// contains       = value "CONTAINS" WS+ value
+pub fn parse_contains(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, value)) =
+        tuple((parse_value, word_exact("CONTAINS"), cut(parse_value)))(input)?;
+
+    Ok((input, FilterCondition::Condition { fid, op: Contains(value) }))
+}
+
+/// not_contains   = value "NOT" WS+ "CONTAINS" WS+ value
+pub fn parse_not_contains(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, _, _, _, value)) =
+        tuple((parse_value, tag("NOT"), multispace1, word_exact("CONTAINS"), cut(parse_value)))(
+            input,
+        )?;
+
+    let condition = FilterCondition::Condition { fid, op: Contains(value) };
+    Ok((input, FilterCondition::Not(Box::new(condition))))
+}
@@ -6,18 +6,30 @@
 //! or             = and ("OR" WS+ and)*
 //! and            = not ("AND" WS+ not)*
 //! not            = ("NOT" WS+ not) | primary
-//! primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | in | condition | exists | not_exists | to
-//! in             = value "IN" WS* "[" value_list "]"
+//! primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | geoBoundingBox | geoPolygon | in | condition | exists | not_exists | null | not_null | empty | not_empty | to | starts_with | not_starts_with | contains | not_contains
+//! in             = value "IN" WS* ("[" value_list "]" | placeholder)
 //! condition      = value ("=" | "!=" | ">" | ">=" | "<" | "<=") value
 //! exists         = value "EXISTS"
 //! not_exists     = value "NOT" WS+ "EXISTS"
+//! null           = value "IS" WS+ "NULL"
+//! not_null       = value "IS" WS+ "NOT" WS+ "NULL"
+//! empty          = value "IS" WS+ "EMPTY"
+//! not_empty      = value "IS" WS+ "NOT" WS+ "EMPTY"
 //! to             = value value "TO" WS+ value
-//! value          = WS* ( word | singleQuoted | doubleQuoted) WS+
+//! starts_with    = value "STARTS" WS+ "WITH" WS+ value
+//! not_starts_with = value "NOT" WS+ "STARTS" WS+ "WITH" WS+ value
+//! contains       = value "CONTAINS" WS+ value
+//! not_contains   = value "NOT" WS+ "CONTAINS" WS+ value
+//! value          = WS* ( word | singleQuoted | doubleQuoted | placeholder) WS+
 //! value_list     = (value ("," value)* ","?)?
 //! singleQuoted   = "'" .* all but quotes "'"
 //! doubleQuoted   = "\"" .* all but double quotes "\""
-//! word           = (alphanumeric | _ | - | .)+
+//! word           = (alphanumeric | _ | - | .)+ | numberOrDate
+//! numberOrDate   = digit (alphanumeric | _ | - | . | : | +)*
+//! placeholder    = "?"
 //! geoRadius      = "_geoRadius(" WS* float WS* "," WS* float WS* "," float WS* ")"
+//! geoBoundingBox = "_geoBoundingBox(" WS* "[" WS* float WS* "," WS* float WS* "]" WS* "," WS* "[" WS* float WS* "," WS* float WS* "]" WS* ")"
+//! geoPolygon     = "_geoPolygon(" WS* "[" WS* float WS* "," WS* float WS* "]" (WS* "," WS* "[" WS* float WS* "," WS* float WS* "]")+ WS* ")"
 //! ```
 //!
 //! Other BNF grammar used to handle some specific errors:
@@ -33,20 +45,27 @@
 //! field < 12 AND _geoPoint(1, 2)
 //! ```
 //!
-//! - If a user try to use a geoRadius as a value we must throw an error.
+//! - If a user try to use a geoRadius, a geoBoundingBox or a geoPolygon as a value we must throw an error.
 //! ```text
 //! field = _geoRadius(12, 13, 14)
+//! field = _geoBoundingBox([12, 13], [14, 15])
+//! field = _geoPolygon([12, 13], [14, 15], [16, 17])
 //! ```
 //!
 
 mod condition;
 mod error;
+mod owned;
+mod parameter;
 mod value;
 
 use std::fmt::Debug;
 
 pub use condition::{parse_condition, parse_to, Condition};
-use condition::{parse_exists, parse_not_exists};
+use condition::{
+    parse_contains, parse_exists, parse_is_empty, parse_is_not_empty, parse_is_not_null,
+    parse_is_null, parse_not_contains, parse_not_exists, parse_not_starts_with, parse_starts_with,
+};
 use error::{cut_with_err, ExpectedValueKind, NomErrorExt};
 pub use error::{Error, ErrorKind};
 use nom::branch::alt;
@@ -58,8 +77,10 @@ use nom::number::complete::recognize_float;
 use nom::sequence::{delimited, preceded, terminated, tuple};
 use nom::Finish;
 use nom_locate::LocatedSpan;
+pub use owned::{OwnedCondition, OwnedFilterCondition, OwnedValue};
+pub use parameter::FilterValue;
 pub(crate) use value::parse_value;
-use value::word_exact;
+use value::{parse_placeholder, word_exact};
 
 pub type Span<'a> = LocatedSpan<&'a str, &'a str>;
 
@@ -74,17 +95,36 @@ pub struct Token<'a> {
     /// If you need to modify the original input you can use the `value` field
     /// to store your modified input.
     value: Option<String>,
+    /// Whether the token is a `?` placeholder, its `value` is the bound parameter.
+    placeholder: bool,
+    /// Whether the token is the bare placeholder of an `IN ?` condition, a list can be bound to it.
+    list_placeholder: bool,
+    /// Whether the value was written between quotes or bound to a string parameter.
+    quoted: bool,
 }
 
 impl<'a> PartialEq for Token<'a> {
     fn eq(&self, other: &Self) -> bool {
-        self.span.fragment() == other.span.fragment()
+        // the parameters bound to the placeholders can't be found in the original input
+        if self.placeholder || other.placeholder {
+            self.value() == other.value()
+        } else {
+            self.span.fragment() == other.span.fragment()
+        }
     }
 }
 
 impl<'a> Token<'a> {
     pub fn new(span: Span<'a>, value: Option<String>) -> Self {
-        Self { span, value }
+        Self { span, value, placeholder: false, list_placeholder: false, quoted: false }
+    }
+
+    pub(crate) fn quoted(span: Span<'a>, value: Option<String>) -> Self {
+        Self { span, value, placeholder: false, list_placeholder: false, quoted: true }
+    }
+
+    pub(crate) fn placeholder(span: Span<'a>) -> Self {
+        Self { span, value: None, placeholder: true, list_placeholder: false, quoted: false }
     }
 
     pub fn lexeme(&self) -> &str {
@@ -95,12 +135,18 @@ impl<'a> Token<'a> {
         self.value.as_ref().map_or(&self.span, |value| value)
     }
 
+    /// Whether the value was written between quotes, e.g. `"true"`, or bound to a string
+    /// parameter. A quoted value is always a string, even if it looks like a boolean.
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
     pub fn as_external_error(&self, error: impl std::error::Error) -> Error<'a> {
         Error::new_from_external(self.span, error)
     }
 
     pub fn parse_finite_float(&self) -> Result<f64, Error> {
-        let value: f64 = self.span.parse().map_err(|e| self.as_external_error(e))?;
+        let value: f64 = self.value().parse().map_err(|e| self.as_external_error(e))?;
         if value.is_finite() {
             Ok(value)
         } else {
@@ -111,7 +157,7 @@ impl<'a> Token<'a> {
 
 impl<'a> From<Span<'a>> for Token<'a> {
     fn from(span: Span<'a>) -> Self {
-        Self { span, value: None }
+        Self { span, value: None, placeholder: false, list_placeholder: false, quoted: false }
     }
 }
 
@@ -130,6 +176,8 @@ pub enum FilterCondition<'a> {
     Or(Vec<Self>),
     And(Vec<Self>),
     GeoLowerThan { point: [Token<'a>; 2], radius: Token<'a> },
+    GeoBoundingBox { top_right_point: [Token<'a>; 2], bottom_left_point: [Token<'a>; 2] },
+    GeoPolygon { points: Vec<[Token<'a>; 2]> },
 }
 
 impl<'a> FilterCondition<'a> {
@@ -156,16 +204,41 @@ impl<'a> FilterCondition<'a> {
                 None
             }
             FilterCondition::GeoLowerThan { point: [point, _], .. } if depth == 0 => Some(point),
+            FilterCondition::GeoBoundingBox { top_right_point: [point, _], .. } if depth == 0 => {
+                Some(point)
+            }
+            FilterCondition::GeoPolygon { points } if depth == 0 => {
+                points.first().map(|[point, _]| point)
+            }
             _ => None,
         }
     }
 
     pub fn parse(input: &'a str) -> Result<Option<Self>, Error> {
+        Self::parse_with_params(input, &[])
+    }
+
+    /// Parses a filter in which the `?` placeholders are replaced, in order, by the given parameters.
+    ///
+    /// The parameters are never interpreted as filter syntax, a list can only be bound to
+    /// the placeholder of an `IN ?` condition.
+    pub fn parse_with_params(
+        input: &'a str,
+        params: &[FilterValue],
+    ) -> Result<Option<Self>, Error<'a>> {
+        let span = Span::new_extra(input, input);
         if input.trim().is_empty() {
-            return Ok(None);
+            return match params.len() {
+                0 => Ok(None),
+                received => Err(Error::new_from_kind(
+                    span,
+                    ErrorKind::ParametersCount { expected: 0, received },
+                )),
+            };
         }
-        let span = Span::new_extra(input, input);
-        parse_filter(span).finish().map(|(_rem, output)| Some(output))
+        let mut filter = parse_filter(span).finish().map(|(_rem, output)| output)?;
+        filter.bind_params(span, params)?;
+        Ok(Some(filter))
     }
 }
 
@@ -192,10 +265,16 @@ fn parse_value_list(input: Span) -> IResult<Vec<Token>> {
     }
 }
 
-/// "IN" WS* "[" value_list "]"
+/// "IN" WS* ("[" value_list "]" | placeholder)
 fn parse_in_body(input: Span) -> IResult<Vec<Token>> {
     let (input, _) = ws(word_exact("IN"))(input)?;
 
+    // a bare placeholder can be bound to the whole list of values
+    if let Ok((input, mut placeholder)) = terminated(parse_placeholder, multispace0)(input) {
+        placeholder.list_placeholder = true;
+        return Ok((input, vec![placeholder]));
+    }
+
     // everything after `IN` can be a failure
     let (input, _) =
         cut_with_err(tag("["), |_| Error::new_from_kind(input, ErrorKind::InOpeningBracket))(
@@ -325,6 +404,59 @@ fn parse_geo_radius(input: Span) -> IResult<FilterCondition> {
     Ok((input, res))
 }
 
+/// Parses a geo shape made of a list of points: `name([float, float], [float, float], ...)`.
+/// Returns a failure if the name is followed by anything else than the list of points.
+fn parse_geo_shape_points<'a>(
+    input: Span<'a>,
+    name: &'static str,
+    kind: ErrorKind<'a>,
+) -> IResult<'a, Vec<Vec<Span<'a>>>> {
+    // we want to allow space BEFORE the name of the shape but not after
+    preceded(
+        tuple((multispace0, word_exact(name))),
+        // if we were able to parse the name and can't parse the rest of the input we return a failure
+        cut(delimited(
+            char('('),
+            separated_list1(
+                tag(","),
+                ws(delimited(char('['), separated_list1(tag(","), ws(recognize_float)), char(']'))),
+            ),
+            char(')'),
+        )),
+    )(input)
+    .map_err(|e| e.map(|_| Error::new_from_kind(input, kind)))
+}
+
+/// geoBoundingBox = WS* "_geoBoundingBox([float WS* "," WS* float WS* "], [float WS* "," WS* float WS* "]")
+/// If we parse `_geoBoundingBox` we MUST parse the rest of the expression.
+fn parse_geo_bounding_box(input: Span) -> IResult<FilterCondition> {
+    let (input, args) =
+        parse_geo_shape_points(input, "_geoBoundingBox", ErrorKind::GeoBoundingBox)?;
+
+    if args.len() != 2 || args[0].len() != 2 || args[1].len() != 2 {
+        return Err(nom::Err::Failure(Error::new_from_kind(input, ErrorKind::GeoBoundingBox)));
+    }
+
+    let res = FilterCondition::GeoBoundingBox {
+        top_right_point: [args[0][0].into(), args[0][1].into()],
+        bottom_left_point: [args[1][0].into(), args[1][1].into()],
+    };
+    Ok((input, res))
+}
+
+/// geoPolygon    = WS* "_geoPolygon([float WS* "," WS* float WS* "], [float WS* "," WS* float WS* "], [float WS* "," WS* float WS* "], ...)"
+/// If we parse `_geoPolygon` we MUST parse the rest of the expression.
+fn parse_geo_polygon(input: Span) -> IResult<FilterCondition> {
+    let (input, args) = parse_geo_shape_points(input, "_geoPolygon", ErrorKind::GeoPolygon)?;
+
+    if args.len() < 3 || args.iter().any(|point| point.len() != 2) {
+        return Err(nom::Err::Failure(Error::new_from_kind(input, ErrorKind::GeoPolygon)));
+    }
+
+    let points = args.into_iter().map(|point| [point[0].into(), point[1].into()]).collect();
+    Ok((input, FilterCondition::GeoPolygon { points }))
+}
+
 /// geoPoint      = WS* "_geoPoint(float WS* "," WS* float WS* "," WS* float)
 fn parse_geo_point(input: Span) -> IResult<FilterCondition> {
     // we want to forbid space BEFORE the _geoPoint but not after
@@ -352,7 +484,7 @@ fn parse_error_reserved_keyword(input: Span) -> IResult<FilterCondition> {
     }
 }
 
-/// primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | condition | exists | not_exists | to
+/// primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | geoBoundingBox | geoPolygon | condition | exists | not_exists | null | not_null | empty | not_empty | to | starts_with | not_starts_with | contains | not_contains
 fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
     if depth > MAX_FILTER_DEPTH {
         return Err(nom::Err::Error(Error::new_from_kind(input, ErrorKind::DepthLimitReached)));
@@ -367,12 +499,22 @@ fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
             }),
         ),
         parse_geo_radius,
+        parse_geo_bounding_box,
+        parse_geo_polygon,
         parse_in,
         parse_not_in,
         parse_condition,
         parse_exists,
         parse_not_exists,
+        parse_is_null,
+        parse_is_not_null,
+        parse_is_empty,
+        parse_is_not_empty,
         parse_to,
+        parse_starts_with,
+        parse_not_starts_with,
+        parse_contains,
+        parse_not_contains,
         // the next lines are only for error handling and are written at the end to have the less possible performance impact
         parse_geo_point,
         parse_error_reserved_keyword,
@@ -452,6 +594,11 @@ pub mod tests {
         insta::assert_display_snapshot!(p("subscribers <= 1000"), @"{subscribers} <= {1000}");
         insta::assert_display_snapshot!(p("subscribers 100 TO 1000"), @"{subscribers} {100} TO {1000}");
 
+        // Test dates
+        insta::assert_display_snapshot!(p("published_at > 2023-01-01T00:00:00Z"), @"{published_at} > {2023-01-01T00:00:00Z}");
+        insta::assert_display_snapshot!(p("published_at <= 2023-01-01T10:30:00.5+02:00"), @"{published_at} <= {2023-01-01T10:30:00.5+02:00}");
+        insta::assert_display_snapshot!(p("published_at 2022-01-01T00:00:00Z TO 2023-01-01T00:00:00Z"), @"{published_at} {2022-01-01T00:00:00Z} TO {2023-01-01T00:00:00Z}");
+
         // Test NOT + EXISTS
         insta::assert_display_snapshot!(p("subscribers EXISTS"), @"{subscribers} EXISTS");
         insta::assert_display_snapshot!(p("NOT subscribers < 1000"), @"NOT ({subscribers} < {1000})");
@@ -459,6 +606,32 @@ pub mod tests {
         insta::assert_display_snapshot!(p("subscribers NOT EXISTS"), @"NOT ({subscribers} EXISTS)");
         insta::assert_display_snapshot!(p("NOT subscribers NOT EXISTS"), @"{subscribers} EXISTS");
         insta::assert_display_snapshot!(p("subscribers NOT   EXISTS"), @"NOT ({subscribers} EXISTS)");
+
+        // Test IS NULL + IS EMPTY
+        insta::assert_display_snapshot!(p("subscribers IS NULL"), @"{subscribers} IS NULL");
+        insta::assert_display_snapshot!(p("subscribers IS NOT NULL"), @"NOT ({subscribers} IS NULL)");
+        insta::assert_display_snapshot!(p("NOT subscribers IS NULL"), @"NOT ({subscribers} IS NULL)");
+        insta::assert_display_snapshot!(p("NOT subscribers IS NOT NULL"), @"{subscribers} IS NULL");
+        insta::assert_display_snapshot!(p("subscribers IS   NOT   NULL"), @"NOT ({subscribers} IS NULL)");
+        insta::assert_display_snapshot!(p("subscribers IS EMPTY"), @"{subscribers} IS EMPTY");
+        insta::assert_display_snapshot!(p("subscribers IS NOT EMPTY"), @"NOT ({subscribers} IS EMPTY)");
+        insta::assert_display_snapshot!(p("NOT subscribers IS EMPTY"), @"NOT ({subscribers} IS EMPTY)");
+        insta::assert_display_snapshot!(p("NOT subscribers IS NOT EMPTY"), @"{subscribers} IS EMPTY");
+        insta::assert_display_snapshot!(p("subscribers IS NULL AND channel IS NOT EMPTY"), @"AND[{subscribers} IS NULL, NOT ({channel} IS EMPTY), ]");
+
+        // Test STARTS WITH + NOT STARTS WITH
+        insta::assert_display_snapshot!(p("sku STARTS WITH AB-"), @"{sku} STARTS WITH {AB-}");
+        insta::assert_display_snapshot!(p("sku STARTS WITH 'AB CD'"), @"{sku} STARTS WITH {AB CD}");
+        insta::assert_display_snapshot!(p("sku   STARTS   WITH   \"AB-\""), @"{sku} STARTS WITH {AB-}");
+        insta::assert_display_snapshot!(p("sku NOT STARTS WITH AB-"), @"NOT ({sku} STARTS WITH {AB-})");
+        insta::assert_display_snapshot!(p("NOT sku STARTS WITH AB-"), @"NOT ({sku} STARTS WITH {AB-})");
+
+        // Test CONTAINS + NOT CONTAINS
+        insta::assert_display_snapshot!(p("title CONTAINS dog"), @"{title} CONTAINS {dog}");
+        insta::assert_display_snapshot!(p("title CONTAINS 'a dog'"), @"{title} CONTAINS {a dog}");
+        insta::assert_display_snapshot!(p("title NOT CONTAINS dog"), @"NOT ({title} CONTAINS {dog})");
+        insta::assert_display_snapshot!(p("NOT title NOT CONTAINS dog"), @"{title} CONTAINS {dog}");
+        insta::assert_display_snapshot!(p("title CONTAINS dog AND sku STARTS WITH AB"), @"AND[{title} CONTAINS {dog}, {sku} STARTS WITH {AB}, ]");
         insta::assert_display_snapshot!(p("NOT subscribers 100 TO 1000"), @"NOT ({subscribers} {100} TO {1000})");
 
         // Test nested NOT
@@ -469,6 +642,16 @@ pub mod tests {
         insta::assert_display_snapshot!(p("_geoRadius(12, 13, 14)"), @"_geoRadius({12}, {13}, {14})");
         insta::assert_display_snapshot!(p("NOT _geoRadius(12, 13, 14)"), @"NOT (_geoRadius({12}, {13}, {14}))");
 
+        // Test geo bounding box
+        insta::assert_display_snapshot!(p("_geoBoundingBox([12, 13], [14, 15])"), @"_geoBoundingBox([{12}, {13}], [{14}, {15}])");
+        insta::assert_display_snapshot!(p("NOT _geoBoundingBox([12, 13], [14, 15])"), @"NOT (_geoBoundingBox([{12}, {13}], [{14}, {15}]))");
+        insta::assert_display_snapshot!(p("_geoBoundingBox( [ 12 , 13 ] , [ 14 , 15 ] )"), @"_geoBoundingBox([{12}, {13}], [{14}, {15}])");
+
+        // Test geo polygon
+        insta::assert_display_snapshot!(p("_geoPolygon([12, 13], [14, 15], [16, 17])"), @"_geoPolygon([{12}, {13}], [{14}, {15}], [{16}, {17}])");
+        insta::assert_display_snapshot!(p("_geoPolygon([12, 13], [14, 15], [16, 17], [-1.5, 13])"), @"_geoPolygon([{12}, {13}], [{14}, {15}], [{16}, {17}], [{-1.5}, {13}])");
+        insta::assert_display_snapshot!(p("NOT _geoPolygon([12, 13], [14, 15], [16, 17])"), @"NOT (_geoPolygon([{12}, {13}], [{14}, {15}], [{16}, {17}]))");
+
         // Test OR + AND
         insta::assert_display_snapshot!(p("channel = ponce AND 'dog race' != 'bernese mountain'"), @"AND[{channel} = {ponce}, {dog race} != {bernese mountain}, ]");
         insta::assert_display_snapshot!(p("channel = ponce OR 'dog race' != 'bernese mountain'"), @"OR[{channel} = {ponce}, {dog race} != {bernese mountain}, ]");
@@ -510,6 +693,12 @@ pub mod tests {
         17:21 channel = Ponce = 12
         "###);
 
+        // only the numbers and the dates can contain a `:` or a `+` without being quoted
+        insta::assert_display_snapshot!(p("channel = a:b"), @r###"
+        Found unexpected characters at the end of the filter: `:b`. You probably forgot an `OR` or an `AND` rule.
+        12:14 channel = a:b
+        "###);
+
         insta::assert_display_snapshot!(p("channel =    "), @r###"
         Was expecting a value but instead got nothing.
         14:14 channel =    
@@ -526,7 +715,7 @@ pub mod tests {
         "###);
 
         insta::assert_display_snapshot!(p("'OR'"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, or `_geoRadius` at `\'OR\'`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `STARTS WITH`, `NOT STARTS WITH`, `CONTAINS`, `NOT CONTAINS`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `\'OR\'`.
         1:5 'OR'
         "###);
 
@@ -536,12 +725,12 @@ pub mod tests {
         "###);
 
         insta::assert_display_snapshot!(p("channel Ponce"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, or `_geoRadius` at `channel Ponce`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `STARTS WITH`, `NOT STARTS WITH`, `CONTAINS`, `NOT CONTAINS`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `channel Ponce`.
         1:14 channel Ponce
         "###);
 
         insta::assert_display_snapshot!(p("channel = Ponce OR"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, or `_geoRadius` but instead got nothing.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `STARTS WITH`, `NOT STARTS WITH`, `CONTAINS`, `NOT CONTAINS`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` but instead got nothing.
         19:19 channel = Ponce OR
         "###);
 
@@ -555,13 +744,58 @@ pub mod tests {
         1:16 _geoRadius = 12
         "###);
 
+        insta::assert_display_snapshot!(p("_geoBoundingBox"), @r###"
+        The `_geoBoundingBox` filter expects two pairs of arguments: `_geoBoundingBox([latitude, longitude], [latitude, longitude])`.
+        1:16 _geoBoundingBox
+        "###);
+
+        insta::assert_display_snapshot!(p("_geoBoundingBox([12, 13])"), @r###"
+        The `_geoBoundingBox` filter expects two pairs of arguments: `_geoBoundingBox([latitude, longitude], [latitude, longitude])`.
+        26:26 _geoBoundingBox([12, 13])
+        "###);
+
+        insta::assert_display_snapshot!(p("_geoBoundingBox([12, 13, 14], [15, 16])"), @r###"
+        The `_geoBoundingBox` filter expects two pairs of arguments: `_geoBoundingBox([latitude, longitude], [latitude, longitude])`.
+        40:40 _geoBoundingBox([12, 13, 14], [15, 16])
+        "###);
+
+        insta::assert_display_snapshot!(p("position <= _geoBoundingBox([12, 13], [14, 15])"), @r###"
+        The `_geoBoundingBox` filter is an operation and can't be used as a value.
+        13:48 position <= _geoBoundingBox([12, 13], [14, 15])
+        "###);
+
+        insta::assert_display_snapshot!(p("_geoPolygon([12, 13], [14, 15])"), @r###"
+        The `_geoPolygon` filter expects at least three points: `_geoPolygon([latitude, longitude], [latitude, longitude], [latitude, longitude], ...)`.
+        32:32 _geoPolygon([12, 13], [14, 15])
+        "###);
+
+        insta::assert_display_snapshot!(p("_geoPolygon([12, 13], [14], [16, 17])"), @r###"
+        The `_geoPolygon` filter expects at least three points: `_geoPolygon([latitude, longitude], [latitude, longitude], [latitude, longitude], ...)`.
+        38:38 _geoPolygon([12, 13], [14], [16, 17])
+        "###);
+
+        insta::assert_display_snapshot!(p("position = _geoPolygon([12, 13], [14, 15], [16, 17])"), @r###"
+        The `_geoPolygon` filter is an operation and can't be used as a value.
+        12:53 position = _geoPolygon([12, 13], [14, 15], [16, 17])
+        "###);
+
+        insta::assert_display_snapshot!(p("sku STARTS WITH"), @r###"
+        Was expecting a value but instead got nothing.
+        16:16 sku STARTS WITH
+        "###);
+
+        insta::assert_display_snapshot!(p("title CONTAINS"), @r###"
+        Was expecting a value but instead got nothing.
+        15:15 title CONTAINS
+        "###);
+
         insta::assert_display_snapshot!(p("_geoPoint(12, 13, 14)"), @r###"
-        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance) built-in rule to filter on `_geo` coordinates.
+        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([latitude, longitude], ...)` built-in rules to filter on `_geo` coordinates.
         1:22 _geoPoint(12, 13, 14)
         "###);
 
         insta::assert_display_snapshot!(p("position <= _geoPoint(12, 13, 14)"), @r###"
-        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance) built-in rule to filter on `_geo` coordinates.
+        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([latitude, longitude], ...)` built-in rules to filter on `_geo` coordinates.
         13:34 position <= _geoPoint(12, 13, 14)
         "###);
 
@@ -591,12 +825,12 @@ pub mod tests {
         "###);
 
         insta::assert_display_snapshot!(p("colour NOT EXIST"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, or `_geoRadius` at `colour NOT EXIST`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `STARTS WITH`, `NOT STARTS WITH`, `CONTAINS`, `NOT CONTAINS`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `colour NOT EXIST`.
         1:17 colour NOT EXIST
         "###);
 
         insta::assert_display_snapshot!(p("subscribers 100 TO1000"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, or `_geoRadius` at `subscribers 100 TO1000`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `STARTS WITH`, `NOT STARTS WITH`, `CONTAINS`, `NOT CONTAINS`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `subscribers 100 TO1000`.
         1:23 subscribers 100 TO1000
         "###);
 
@@ -659,6 +893,67 @@ pub mod tests {
         "###);
     }
 
+    #[test]
+    fn parameters() {
+        use FilterCondition as Fc;
+
+        fn p<'a>(s: &'a str, params: &[FilterValue]) -> impl std::fmt::Display + 'a {
+            Fc::parse_with_params(s, params).unwrap().unwrap()
+        }
+
+        fn e<'a>(s: &'a str, params: &[FilterValue]) -> impl std::fmt::Display + 'a {
+            Fc::parse_with_params(s, params).unwrap_err()
+        }
+
+        insta::assert_display_snapshot!(p("price > ?", &[12.5.into()]), @"{price} > {12.5}");
+        insta::assert_display_snapshot!(p("brand = ?", &["O'Neil \"AND\" x = 1".into()]), @r###"{brand} = {O'Neil "AND" x = 1}"###);
+        insta::assert_display_snapshot!(p("? = ?", &["dog race".into(), true.into()]), @"{dog race} = {true}");
+        insta::assert_display_snapshot!(p("price ? TO ?", &[10.into(), 20.into()]), @"{price} {10} TO {20}");
+        insta::assert_display_snapshot!(p("price > ? AND brand IN ?", &[100.into(), vec!["nike", "adidas"].into()]), @"AND[{price} > {100}, {brand} IN[{nike}, {adidas}, ], ]");
+        insta::assert_display_snapshot!(p("brand NOT IN ? OR brand IN [?, ?]", &[vec!["a"].into(), "b".into(), "c".into()]), @"OR[NOT ({brand} IN[{a}, ]), {brand} IN[{b}, {c}, ], ]");
+        insta::assert_display_snapshot!(p("brand IN ?", &[Vec::<String>::new().into()]), @"{brand} IN[]");
+        insta::assert_display_snapshot!(p("brand IN ?", &["nike".into()]), @"{brand} IN[{nike}, ]");
+        insta::assert_display_snapshot!(p("brand = '?'", &[]), @"{brand} = {?}");
+
+        // the bound values can't be distinguished from the same values written in the filter
+        let bound = Fc::parse_with_params("price > ?", &[12.into()]).unwrap();
+        assert_eq!(bound, Fc::parse("price > 12").unwrap());
+        assert_ne!(bound, Fc::parse_with_params("price > ?", &[13.into()]).unwrap());
+
+        insta::assert_display_snapshot!(e("price > ?", &[]), @r###"
+        Expected 1 parameters to bind to the `?` placeholders of the filter but received 0.
+        1:10 price > ?
+        "###);
+        insta::assert_display_snapshot!(e("price > ?", &[1.into(), 2.into()]), @r###"
+        Expected 1 parameters to bind to the `?` placeholders of the filter but received 2.
+        1:10 price > ?
+        "###);
+        insta::assert_display_snapshot!(e("", &[1.into()]), @r###"
+        Expected 0 parameters to bind to the `?` placeholders of the filter but received 1.
+        1:1 
+        "###);
+        insta::assert_display_snapshot!(e("price > ?", &[vec![1, 2].into()]), @r###"
+        A list of values can only be bound to the `?` placeholder of an `IN ?` condition.
+        9:10 price > ?
+        "###);
+        insta::assert_display_snapshot!(e("price IN ?", &[vec![vec![1]].into()]), @r###"
+        A list of values can only be bound to the `?` placeholder of an `IN ?` condition.
+        10:11 price IN ?
+        "###);
+        insta::assert_display_snapshot!(e("price IN [?]", &[vec![1, 2].into()]), @r###"
+        A list of values can only be bound to the `?` placeholder of an `IN ?` condition.
+        11:12 price IN [?]
+        "###);
+        insta::assert_display_snapshot!(e("price > ?", &[f64::NAN.into()]), @r###"
+        Non finite floats are not supported
+        9:10 price > ?
+        "###);
+        insta::assert_display_snapshot!(e("price IN ?", &[vec![1.0, f64::INFINITY].into()]), @r###"
+        Non finite floats are not supported
+        10:11 price IN ?
+        "###);
+    }
+
     #[test]
     fn depth() {
         let filter = FilterCondition::parse("account_ids=1 OR account_ids=2 OR account_ids=3 OR account_ids=4 OR account_ids=5 OR account_ids=6").unwrap().unwrap();
@@ -715,6 +1010,24 @@ impl<'a> std::fmt::Display for FilterCondition<'a> {
             FilterCondition::GeoLowerThan { point, radius } => {
                 write!(f, "_geoRadius({}, {}, {})", point[0], point[1], radius)
             }
+            FilterCondition::GeoBoundingBox {
+                top_right_point: top_right,
+                bottom_left_point: bottom_left,
+            } => write!(
+                f,
+                "_geoBoundingBox([{}, {}], [{}, {}])",
+                top_right[0], top_right[1], bottom_left[0], bottom_left[1]
+            ),
+            FilterCondition::GeoPolygon { points } => {
+                write!(f, "_geoPolygon(")?;
+                for (i, [lat, lng]) in points.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "[{}, {}]", lat, lng)?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -726,9 +1039,13 @@ impl<'a> std::fmt::Display for Condition<'a> {
             Condition::Equal(token) => write!(f, "= {token}"),
             Condition::NotEqual(token) => write!(f, "!= {token}"),
             Condition::Exists => write!(f, "EXISTS"),
+            Condition::Null => write!(f, "IS NULL"),
+            Condition::Empty => write!(f, "IS EMPTY"),
             Condition::LowerThan(token) => write!(f, "< {token}"),
             Condition::LowerThanOrEqual(token) => write!(f, "<= {token}"),
             Condition::Between { from, to } => write!(f, "{from} TO {to}"),
+            Condition::StartsWith(token) => write!(f, "STARTS WITH {token}"),
+            Condition::Contains(token) => write!(f, "CONTAINS {token}"),
         }
     }
 }
@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Condition, FilterCondition, Span, Token};
+
+/// An owned version of a [`FilterCondition`] that isn't tied to the lifetime of the filter
+/// expression, it can be cached, sent across threads or serialized.
+///
+/// The tokens are stored as their values, their positions in the original expression are lost.
+/// The values compared to the facets also keep whether they were quoted, see [`OwnedValue`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OwnedFilterCondition {
+    Not(Box<Self>),
+    Condition { fid: String, op: OwnedCondition },
+    In { fid: String, els: Vec<OwnedValue> },
+    Or(Vec<Self>),
+    And(Vec<Self>),
+    GeoLowerThan { point: [String; 2], radius: String },
+    GeoBoundingBox { top_right_point: [String; 2], bottom_left_point: [String; 2] },
+    GeoPolygon { points: Vec<[String; 2]> },
+}
+
+/// An owned version of a [`Condition`], see [`OwnedFilterCondition`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OwnedCondition {
+    GreaterThan(OwnedValue),
+    GreaterThanOrEqual(OwnedValue),
+    Equal(OwnedValue),
+    NotEqual(OwnedValue),
+    Exists,
+    Null,
+    Empty,
+    LowerThan(OwnedValue),
+    LowerThanOrEqual(OwnedValue),
+    Between { from: OwnedValue, to: OwnedValue },
+    StartsWith(OwnedValue),
+    Contains(OwnedValue),
+}
+
+/// An owned version of a value [`Token`], a quoted `"true"` is a string
+/// while a bare `true` can also match the boolean facets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedValue {
+    pub value: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub quoted: bool,
+}
+
+impl<'a> From<&Token<'a>> for OwnedValue {
+    fn from(token: &Token<'a>) -> Self {
+        OwnedValue { value: token.value().to_owned(), quoted: token.is_quoted() }
+    }
+}
+
+impl<'a> From<&'a OwnedValue> for Token<'a> {
+    fn from(value: &'a OwnedValue) -> Self {
+        let span = Span::new_extra(value.value.as_str(), value.value.as_str());
+        if value.quoted {
+            Token::quoted(span, None)
+        } else {
+            Token::from(span)
+        }
+    }
+}
+
+fn owned_token(token: &Token) -> String {
+    token.value().to_owned()
+}
+
+fn owned_point([lat, lng]: &[Token; 2]) -> [String; 2] {
+    [owned_token(lat), owned_token(lng)]
+}
+
+fn borrowed_point([lat, lng]: &[String; 2]) -> [Token; 2] {
+    [Token::from(lat.as_str()), Token::from(lng.as_str())]
+}
+
+impl<'a> From<&Condition<'a>> for OwnedCondition {
+    fn from(condition: &Condition<'a>) -> Self {
+        match condition {
+            Condition::GreaterThan(token) => OwnedCondition::GreaterThan(token.into()),
+            Condition::GreaterThanOrEqual(token) => {
+                OwnedCondition::GreaterThanOrEqual(token.into())
+            }
+            Condition::Equal(token) => OwnedCondition::Equal(token.into()),
+            Condition::NotEqual(token) => OwnedCondition::NotEqual(token.into()),
+            Condition::Exists => OwnedCondition::Exists,
+            Condition::Null => OwnedCondition::Null,
+            Condition::Empty => OwnedCondition::Empty,
+            Condition::LowerThan(token) => OwnedCondition::LowerThan(token.into()),
+            Condition::LowerThanOrEqual(token) => OwnedCondition::LowerThanOrEqual(token.into()),
+            Condition::Between { from, to } => {
+                OwnedCondition::Between { from: from.into(), to: to.into() }
+            }
+            Condition::StartsWith(token) => OwnedCondition::StartsWith(token.into()),
+            Condition::Contains(token) => OwnedCondition::Contains(token.into()),
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedCondition> for Condition<'a> {
+    fn from(condition: &'a OwnedCondition) -> Self {
+        match condition {
+            OwnedCondition::GreaterThan(value) => Condition::GreaterThan(value.into()),
+            OwnedCondition::GreaterThanOrEqual(value) => {
+                Condition::GreaterThanOrEqual(value.into())
+            }
+            OwnedCondition::Equal(value) => Condition::Equal(value.into()),
+            OwnedCondition::NotEqual(value) => Condition::NotEqual(value.into()),
+            OwnedCondition::Exists => Condition::Exists,
+            OwnedCondition::Null => Condition::Null,
+            OwnedCondition::Empty => Condition::Empty,
+            OwnedCondition::LowerThan(value) => Condition::LowerThan(value.into()),
+            OwnedCondition::LowerThanOrEqual(value) => Condition::LowerThanOrEqual(value.into()),
+            OwnedCondition::Between { from, to } => {
+                Condition::Between { from: from.into(), to: to.into() }
+            }
+            OwnedCondition::StartsWith(value) => Condition::StartsWith(value.into()),
+            OwnedCondition::Contains(value) => Condition::Contains(value.into()),
+        }
+    }
+}
+
+impl<'a> From<&FilterCondition<'a>> for OwnedFilterCondition {
+    fn from(filter: &FilterCondition<'a>) -> Self {
+        match filter {
+            FilterCondition::Not(filter) => OwnedFilterCondition::Not(Box::new((&**filter).into())),
+            FilterCondition::Condition { fid, op } => {
+                OwnedFilterCondition::Condition { fid: owned_token(fid), op: op.into() }
+            }
+            FilterCondition::In { fid, els } => OwnedFilterCondition::In {
+                fid: owned_token(fid),
+                els: els.iter().map(Into::into).collect(),
+            },
+            FilterCondition::Or(filters) => {
+                OwnedFilterCondition::Or(filters.iter().map(Into::into).collect())
+            }
+            FilterCondition::And(filters) => {
+                OwnedFilterCondition::And(filters.iter().map(Into::into).collect())
+            }
+            FilterCondition::GeoLowerThan { point, radius } => OwnedFilterCondition::GeoLowerThan {
+                point: owned_point(point),
+                radius: owned_token(radius),
+            },
+            FilterCondition::GeoBoundingBox { top_right_point, bottom_left_point } => {
+                OwnedFilterCondition::GeoBoundingBox {
+                    top_right_point: owned_point(top_right_point),
+                    bottom_left_point: owned_point(bottom_left_point),
+                }
+            }
+            FilterCondition::GeoPolygon { points } => OwnedFilterCondition::GeoPolygon {
+                points: points.iter().map(owned_point).collect(),
+            },
+        }
+    }
+}
+
+impl<'a> From<FilterCondition<'a>> for OwnedFilterCondition {
+    fn from(filter: FilterCondition<'a>) -> Self {
+        (&filter).into()
+    }
+}
+
+impl<'a> From<&'a OwnedFilterCondition> for FilterCondition<'a> {
+    fn from(filter: &'a OwnedFilterCondition) -> Self {
+        match filter {
+            OwnedFilterCondition::Not(filter) => FilterCondition::Not(Box::new((&**filter).into())),
+            OwnedFilterCondition::Condition { fid, op } => {
+                FilterCondition::Condition { fid: fid.as_str().into(), op: op.into() }
+            }
+            OwnedFilterCondition::In { fid, els } => FilterCondition::In {
+                fid: fid.as_str().into(),
+                els: els.iter().map(Into::into).collect(),
+            },
+            OwnedFilterCondition::Or(filters) => {
+                FilterCondition::Or(filters.iter().map(Into::into).collect())
+            }
+            OwnedFilterCondition::And(filters) => {
+                FilterCondition::And(filters.iter().map(Into::into).collect())
+            }
+            OwnedFilterCondition::GeoLowerThan { point, radius } => FilterCondition::GeoLowerThan {
+                point: borrowed_point(point),
+                radius: radius.as_str().into(),
+            },
+            OwnedFilterCondition::GeoBoundingBox { top_right_point, bottom_left_point } => {
+                FilterCondition::GeoBoundingBox {
+                    top_right_point: borrowed_point(top_right_point),
+                    bottom_left_point: borrowed_point(bottom_left_point),
+                }
+            }
+            OwnedFilterCondition::GeoPolygon { points } => {
+                FilterCondition::GeoPolygon { points: points.iter().map(borrowed_point).collect() }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FilterValue;
+
+    #[test]
+    fn round_trip() {
+        let input = "(channel = Ponce OR 'dog race' IN [Borzoi, \"Bernese \\\"Mountain\\\"\"]) AND NOT subscribers 12 TO 13 AND _geoRadius(12, 13, 14) AND _geoPolygon([1, 2], [3, 4], [5, 6]) AND tags IS NOT EMPTY AND title STARTS WITH Star";
+        let filter = FilterCondition::parse(input).unwrap().unwrap();
+        let owned = OwnedFilterCondition::from(&filter);
+
+        let json = serde_json::to_string(&owned).unwrap();
+        let deserialized: OwnedFilterCondition = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, owned);
+
+        let borrowed = FilterCondition::from(&deserialized);
+        assert_eq!(borrowed.to_string(), filter.to_string());
+        assert_eq!(OwnedFilterCondition::from(borrowed), owned);
+    }
+
+    #[test]
+    fn serialize() {
+        let params = [FilterValue::from(100), vec!["nike", "adidas"].into()];
+        let filter = FilterCondition::parse_with_params("price > ? AND brand IN ?", &params)
+            .unwrap()
+            .unwrap();
+        let owned = OwnedFilterCondition::from(filter);
+
+        insta::assert_snapshot!(serde_json::to_string(&owned).unwrap(), @r###"{"and":[{"condition":{"fid":"price","op":{"greaterThan":{"value":"100"}}}},{"in":{"fid":"brand","els":[{"value":"nike","quoted":true},{"value":"adidas","quoted":true}]}}]}"###);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{Condition, Error, ErrorKind, FilterCondition, Span, Token};
+
+/// A typed value bound to a `?` placeholder of a filter.
+///
+/// ```text
+/// price > ? AND brand IN ?
+/// ```
+///
+/// The bound values are used as is and never parsed as filter syntax,
+/// there is no need to quote or escape them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    List(Vec<FilterValue>),
+}
+
+impl From<&str> for FilterValue {
+    fn from(s: &str) -> Self {
+        FilterValue::String(s.to_owned())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(s: String) -> Self {
+        FilterValue::String(s)
+    }
+}
+
+impl From<bool> for FilterValue {
+    fn from(b: bool) -> Self {
+        FilterValue::Bool(b)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($ty:ty),+) => {
+        $(
+            impl From<$ty> for FilterValue {
+                fn from(n: $ty) -> Self {
+                    FilterValue::Number(n as f64)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_number!(f64, f32, i64, i32, u64, u32, usize);
+
+impl<T: Into<FilterValue>> From<Vec<T>> for FilterValue {
+    fn from(values: Vec<T>) -> Self {
+        FilterValue::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<'a> Token<'a> {
+    /// Replaces the placeholder by the given parameter written as it would have been in the
+    /// filter, lists can't be bound to a single token and numbers must be finite.
+    fn bind(&mut self, param: &FilterValue) -> Result<(), Error<'a>> {
+        let value = match param {
+            FilterValue::String(s) => s.clone(),
+            FilterValue::Number(n) if n.is_finite() => n.to_string(),
+            FilterValue::Number(_) => {
+                return Err(Error::new_from_kind(self.span, ErrorKind::NonFiniteFloat))
+            }
+            FilterValue::Bool(b) => b.to_string(),
+            FilterValue::List(_) => {
+                return Err(Error::new_from_kind(self.span, ErrorKind::MisusedListParameter))
+            }
+        };
+        self.value = Some(value);
+        self.quoted = matches!(param, FilterValue::String(_));
+        Ok(())
+    }
+}
+
+impl<'a> FilterCondition<'a> {
+    /// Binds the parameters to the `?` placeholders in the order in which they appear in the input.
+    pub(crate) fn bind_params(
+        &mut self,
+        input: Span<'a>,
+        params: &[FilterValue],
+    ) -> Result<(), Error<'a>> {
+        let mut offsets = Vec::new();
+        self.visit_tokens(&mut |token| {
+            if token.placeholder {
+                offsets.push(token.span.location_offset());
+            }
+            Ok(())
+        })?;
+        offsets.sort_unstable();
+
+        if offsets.len() != params.len() {
+            return Err(Error::new_from_kind(
+                input,
+                ErrorKind::ParametersCount { expected: offsets.len(), received: params.len() },
+            ));
+        }
+
+        let param_of =
+            |token: &Token<'a>| match offsets.binary_search(&token.span.location_offset()) {
+                Ok(index) => Ok(&params[index]),
+                Err(_) => Err(Error::new_from_kind(
+                    token.span,
+                    ErrorKind::InternalError(nom::error::ErrorKind::Verify),
+                )),
+            };
+
+        // the bare placeholder of an `IN ?` condition is expanded into the list of values,
+        // the lists bound to the other placeholders are rejected when binding the tokens
+        self.visit_in_lists(&mut |els| {
+            match els.as_slice() {
+                [token] if token.list_placeholder => {
+                    if let FilterValue::List(values) = param_of(token)? {
+                        let span = token.span;
+                        *els = Vec::with_capacity(values.len());
+                        for value in values {
+                            let mut token = Token::placeholder(span);
+                            token.bind(value)?;
+                            els.push(token);
+                        }
+                    }
+                }
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        self.visit_tokens(&mut |token| {
+            if token.placeholder && token.value.is_none() {
+                token.bind(param_of(token)?)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// Calls the given function on every token of the filter.
+    fn visit_tokens(
+        &mut self,
+        f: &mut impl FnMut(&mut Token<'a>) -> Result<(), Error<'a>>,
+    ) -> Result<(), Error<'a>> {
+        match self {
+            FilterCondition::Not(filter) => filter.visit_tokens(f),
+            FilterCondition::Condition { fid, op } => {
+                f(fid)?;
+                match op {
+                    Condition::GreaterThan(token)
+                    | Condition::GreaterThanOrEqual(token)
+                    | Condition::Equal(token)
+                    | Condition::NotEqual(token)
+                    | Condition::LowerThan(token)
+                    | Condition::LowerThanOrEqual(token)
+                    | Condition::StartsWith(token)
+                    | Condition::Contains(token) => f(token),
+                    Condition::Between { from, to } => {
+                        f(from)?;
+                        f(to)
+                    }
+                    Condition::Exists | Condition::Null | Condition::Empty => Ok(()),
+                }
+            }
+            FilterCondition::In { fid, els } => {
+                f(fid)?;
+                els.iter_mut().try_for_each(f)
+            }
+            FilterCondition::Or(filters) | FilterCondition::And(filters) => {
+                filters.iter_mut().try_for_each(|filter| filter.visit_tokens(f))
+            }
+            FilterCondition::GeoLowerThan { point, radius } => {
+                point.iter_mut().try_for_each(&mut *f)?;
+                f(radius)
+            }
+            FilterCondition::GeoBoundingBox { top_right_point, bottom_left_point } => {
+                top_right_point.iter_mut().chain(bottom_left_point).try_for_each(f)
+            }
+            FilterCondition::GeoPolygon { points } => points.iter_mut().flatten().try_for_each(f),
+        }
+    }
+
+    /// Calls the given function on the list of values of every `IN` condition of the filter.
+    fn visit_in_lists(
+        &mut self,
+        f: &mut impl FnMut(&mut Vec<Token<'a>>) -> Result<(), Error<'a>>,
+    ) -> Result<(), Error<'a>> {
+        match self {
+            FilterCondition::Not(filter) => filter.visit_in_lists(f),
+            FilterCondition::In { els, .. } => f(els),
+            FilterCondition::Or(filters) | FilterCondition::And(filters) => {
+                filters.iter_mut().try_for_each(|filter| filter.visit_in_lists(f))
+            }
+            FilterCondition::Condition { .. }
+            | FilterCondition::GeoLowerThan { .. }
+            | FilterCondition::GeoBoundingBox { .. }
+            | FilterCondition::GeoPolygon { .. } => Ok(()),
+        }
+    }
+}
@@ -1,12 +1,15 @@
 use nom::branch::alt;
-use nom::bytes::complete::{take_till, take_while, take_while1};
+use nom::bytes::complete::{tag, take_till, take_while, take_while1};
 use nom::character::complete::{char, multispace0};
 use nom::combinator::cut;
 use nom::sequence::{delimited, terminated};
 use nom::{InputIter, InputLength, InputTake, Slice};
 
 use crate::error::{ExpectedValueKind, NomErrorExt};
-use crate::{parse_geo_point, parse_geo_radius, Error, ErrorKind, IResult, Span, Token};
+use crate::{
+    parse_geo_bounding_box, parse_geo_point, parse_geo_polygon, parse_geo_radius, Error, ErrorKind,
+    IResult, Span, Token,
+};
 
 /// This function goes through all characters in the [Span] if it finds any escaped character (`\`).
 /// It generates a new string with all `\` removed from the [Span].
@@ -19,7 +22,7 @@ fn unescape(buf: Span, char_to_escape: char) -> String {
 fn quoted_by(quote: char, input: Span) -> IResult<Token> {
     // empty fields / values are valid in json
     if input.is_empty() {
-        return Ok((input.slice(input.input_len()..), input.into()));
+        return Ok((input.slice(input.input_len()..), Token::quoted(input, None)));
     }
 
     let mut escaped = false;
@@ -28,7 +31,7 @@ fn quoted_by(quote: char, input: Span) -> IResult<Token> {
     while let Some((idx, c)) = i.next() {
         if c == quote {
             let (rem, output) = input.take_split(idx);
-            return Ok((rem, Token::new(output, escaped.then(|| unescape(output, quote)))));
+            return Ok((rem, Token::quoted(output, escaped.then(|| unescape(output, quote)))));
         } else if c == '\\' {
             if let Some((_, c)) = i.next() {
                 escaped |= c == quote;
@@ -44,14 +47,31 @@ fn quoted_by(quote: char, input: Span) -> IResult<Token> {
 
     Ok((
         input.slice(input.input_len()..),
-        Token::new(input, escaped.then(|| unescape(input, quote))),
+        Token::quoted(input, escaped.then(|| unescape(input, quote))),
     ))
 }
 
-// word           = (alphanumeric | _ | - | .)+    except for reserved keywords
+// placeholder    = "?"
+pub fn parse_placeholder(input: Span) -> IResult<Token> {
+    tag("?")(input).map(|(input, placeholder)| (input, Token::placeholder(placeholder)))
+}
+
+// word           = (alphanumeric | _ | - | .)+ | numberOrDate
+// numberOrDate   = digit (alphanumeric | _ | - | . | : | +)*
+fn word(input: Span) -> IResult<Token> {
+    if input.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        let (rest, word) = take_while1(is_number_or_date_component)(input)?;
+        // the `:` and `+` are only valid in the numbers and the dates, `1:2` or `1+` are not words
+        if !word.contains([':', '+']) || is_number_or_date(&word) {
+            return Ok((rest, word.into()));
+        }
+    }
+    take_while1(is_value_component)(input).map(|(s, t)| (s, t.into()))
+}
+
+// word           = (alphanumeric | _ | - | .)+ | numberOrDate    except for reserved keywords
 pub fn word_not_keyword<'a>(input: Span<'a>) -> IResult<Token<'a>> {
-    let (input, word): (_, Token<'a>) =
-        take_while1(is_value_component)(input).map(|(s, t)| (s, t.into()))?;
+    let (input, word) = word(input)?;
     if is_keyword(word.value()) {
         return Err(nom::Err::Error(Error::new_from_kind(
             input,
@@ -64,8 +84,7 @@ pub fn word_not_keyword<'a>(input: Span<'a>) -> IResult<Token<'a>> {
 // word           = {tag}
 pub fn word_exact<'a, 'b: 'a>(tag: &'b str) -> impl Fn(Span<'a>) -> IResult<'a, Token<'a>> {
     move |input| {
-        let (input, word): (_, Token<'a>) =
-            take_while1(is_value_component)(input).map(|(s, t)| (s, t.into()))?;
+        let (input, word) = word(input)?;
         if word.value() == tag {
             Ok((input, word))
         } else {
@@ -77,7 +96,7 @@ pub fn word_exact<'a, 'b: 'a>(tag: &'b str) -> impl Fn(Span<'a>) -> IResult<'a,
     }
 }
 
-/// value          = WS* ( word | singleQuoted | doubleQuoted) WS+
+/// value          = WS* ( word | singleQuoted | doubleQuoted | placeholder) WS+
 pub fn parse_value(input: Span) -> IResult<Token> {
     // to get better diagnostic message we are going to strip the left whitespaces from the input right now
     let (input, _) = take_while(char::is_whitespace)(input)?;
@@ -99,6 +118,40 @@ pub fn parse_value(input: Span) -> IResult<Token> {
         }
         _ => (),
     }
+    match parse_geo_bounding_box(input) {
+        Ok(_) => {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::MisusedGeoBoundingBox,
+            )))
+        }
+        // if we encountered a failure it means the user badly wrote a _geoBoundingBox filter.
+        // But instead of showing him how to fix his syntax we are going to tell him he should not use this filter as a value.
+        Err(e) if e.is_failure() => {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::MisusedGeoBoundingBox,
+            )))
+        }
+        _ => (),
+    }
+    match parse_geo_polygon(input) {
+        Ok(_) => {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::MisusedGeoPolygon,
+            )))
+        }
+        // if we encountered a failure it means the user badly wrote a _geoPolygon filter.
+        // But instead of showing him how to fix his syntax we are going to tell him he should not use this filter as a value.
+        Err(e) if e.is_failure() => {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::MisusedGeoPolygon,
+            )))
+        }
+        _ => (),
+    }
 
     // this parser is only used when an error is encountered and it parse the
     // largest string possible that do not contain any “language” syntax.
@@ -113,6 +166,7 @@ pub fn parse_value(input: Span) -> IResult<Token> {
         alt((
             delimited(char('\''), cut(|input| quoted_by('\'', input)), cut(char('\''))),
             delimited(char('"'), cut(|input| quoted_by('"', input)), cut(char('"'))),
+            parse_placeholder,
             word_not_keyword,
         )),
         multispace0,
@@ -150,12 +204,66 @@ fn is_value_component(c: char) -> bool {
     c.is_alphanumeric() || ['_', '-', '.'].contains(&c)
 }
 
+/// The numbers and the RFC 3339 dates can also contain the `:` of
+/// the times and the `+` of the exponents and the time offsets.
+fn is_number_or_date_component(c: char) -> bool {
+    is_value_component(c) || [':', '+'].contains(&c)
+}
+
+/// Returns `true` if the word is a number or has the shape of an
+/// RFC 3339 date, e.g. `2023-01-01T10:30:00.5+02:00`.
+fn is_number_or_date(word: &str) -> bool {
+    if word.parse::<f64>().is_ok() {
+        return true;
+    }
+
+    fn digits(s: &[u8], n: usize) -> Option<&[u8]> {
+        let head = s.get(..n)?;
+        head.iter().all(u8::is_ascii_digit).then_some(&s[n..])
+    }
+    fn byte<'a>(s: &'a [u8], expected: &[u8]) -> Option<&'a [u8]> {
+        let (head, tail) = s.split_first()?;
+        expected.contains(head).then_some(tail)
+    }
+    fn date(s: &[u8]) -> Option<&[u8]> {
+        let s = digits(byte(digits(byte(digits(s, 4)?, b"-")?, 2)?, b"-")?, 2)?;
+        let s = digits(byte(digits(byte(digits(byte(s, b"Tt")?, 2)?, b":")?, 2)?, b":")?, 2)?;
+        let s = match byte(s, b".") {
+            Some(fraction) => {
+                let len = fraction.iter().take_while(|b| b.is_ascii_digit()).count();
+                if len == 0 {
+                    return None;
+                }
+                &fraction[len..]
+            }
+            None => s,
+        };
+        match byte(s, b"Zz") {
+            Some(s) => Some(s),
+            None => digits(byte(digits(byte(s, b"+-")?, 2)?, b":")?, 2),
+        }
+    }
+
+    date(word.as_bytes()).is_some_and(|rest| rest.is_empty())
+}
+
 fn is_syntax_component(c: char) -> bool {
     c.is_whitespace() || ['(', ')', '=', '<', '>', '!'].contains(&c)
 }
 
 fn is_keyword(s: &str) -> bool {
-    matches!(s, "AND" | "OR" | "IN" | "NOT" | "TO" | "EXISTS" | "_geoRadius")
+    matches!(
+        s,
+        "AND"
+            | "OR"
+            | "IN"
+            | "NOT"
+            | "TO"
+            | "EXISTS"
+            | "_geoRadius"
+            | "_geoBoundingBox"
+            | "_geoPolygon"
+    )
 }
 
 #[cfg(test)]
@@ -187,6 +295,15 @@ pub mod test {
             ("\"cha'nnel\"", rtok("'", "cha'nnel")),
             ("\"cha'nnel\"", rtok("'", "cha'nnel")),
             ("I'm tamo", rtok("'m tamo", "I")),
+            ("2023-01-01T10:30:00+02:00", rtok("", "2023-01-01T10:30:00+02:00")),
+            ("1e+5", rtok("", "1e+5")),
+            ("2023-01-01T10:30:00.123Z", rtok("", "2023-01-01T10:30:00.123Z")),
+            ("1:2", rtok("", "1")),
+            ("12:30", rtok("", "12")),
+            ("1+", rtok("", "1")),
+            ("2023-01-01T10:30", rtok("", "2023-01-01T10")),
+            ("a:b", rtok("", "a")),
+            ("c++", rtok("", "c")),
             ("\"I'm \\\"super\\\" tamo\"", rtok("\"", "I'm \\\"super\\\" tamo")),
         ];
 
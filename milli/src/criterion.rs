@@ -159,6 +159,16 @@ mod tests {
             ("_geoPoint(42, 75):asc", ReservedNameForSort { name: S("_geoPoint") }),
             ("_geoRadius:asc", ReservedNameForFilter { name: S("_geoRadius") }),
             ("_geoRadius(42, 75, 59):asc", ReservedNameForFilter { name: S("_geoRadius") }),
+            ("_geoBoundingBox:asc", ReservedNameForFilter { name: S("_geoBoundingBox") }),
+            (
+                "_geoBoundingBox([42, 75], [59, 11]):asc",
+                ReservedNameForFilter { name: S("_geoBoundingBox") },
+            ),
+            ("_geoPolygon:asc", ReservedNameForFilter { name: S("_geoPolygon") }),
+            (
+                "_geoPolygon([42, 75], [59, 11], [12, 13]):asc",
+                ReservedNameForFilter { name: S("_geoPolygon") },
+            ),
         ];
 
         for (input, expected) in invalid_criteria {
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 pub enum FacetType {
     String,
     Number,
+    Boolean,
 }
 
 impl fmt::Display for FacetType {
@@ -15,6 +16,7 @@ impl fmt::Display for FacetType {
         match self {
             FacetType::String => f.write_str("string"),
             FacetType::Number => f.write_str("number"),
+            FacetType::Boolean => f.write_str("boolean"),
         }
     }
 }
@@ -27,6 +29,8 @@ impl FromStr for FacetType {
             Ok(FacetType::String)
         } else if s.trim().eq_ignore_ascii_case("number") {
             Ok(FacetType::Number)
+        } else if s.trim().eq_ignore_ascii_case("boolean") {
+            Ok(FacetType::Boolean)
         } else {
             Err(InvalidFacetType)
         }
@@ -38,7 +42,7 @@ pub struct InvalidFacetType;
 
 impl fmt::Display for InvalidFacetType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(r#"Invalid facet type, must be "string" or "number""#)
+        f.write_str(r#"Invalid facet type, must be "string", "number" or "boolean""#)
     }
 }
 
@@ -2,5 +2,15 @@ mod facet_type;
 mod facet_value;
 pub mod value_encoding;
 
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
 pub use self::facet_type::FacetType;
 pub use self::facet_value::FacetValue;
+
+/// Converts an RFC 3339 date into the number of seconds since the unix epoch,
+/// which is how the dates are stored in the facet number databases.
+pub fn rfc3339_to_timestamp(date: &str) -> Option<f64> {
+    let date = OffsetDateTime::parse(date.trim(), &Rfc3339).ok()?;
+    Some(date.unix_timestamp_nanos() as f64 / 1_000_000_000.0)
+}
@@ -0,0 +1,32 @@
+use std::borrow::Cow;
+
+use crate::{try_split_array_at, FieldId};
+
+/// The key of the [`facet_id_bool_docids`][`crate::Index::facet_id_bool_docids`] database,
+/// a field id followed by a byte equal to `0` for `false` or `1` for `true`.
+pub struct FieldIdBoolCodec;
+
+impl<'a> heed::BytesDecode<'a> for FieldIdBoolCodec {
+    type DItem = (FieldId, bool);
+
+    fn bytes_decode(bytes: &'a [u8]) -> Option<Self::DItem> {
+        let (field_id_bytes, bytes) = try_split_array_at(bytes)?;
+        let field_id = u16::from_be_bytes(field_id_bytes);
+        match bytes {
+            [0] => Some((field_id, false)),
+            [1] => Some((field_id, true)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> heed::BytesEncode<'a> for FieldIdBoolCodec {
+    type EItem = (FieldId, bool);
+
+    fn bytes_encode((field_id, value): &Self::EItem) -> Option<Cow<[u8]>> {
+        let mut bytes = Vec::with_capacity(2 + 1);
+        bytes.extend_from_slice(&field_id.to_be_bytes());
+        bytes.push(*value as u8);
+        Some(Cow::Owned(bytes))
+    }
+}
@@ -1,4 +1,5 @@
 mod field_doc_id_facet_codec;
+mod field_id_bool_codec;
 mod ordered_f64_codec;
 
 use std::borrow::Cow;
@@ -10,9 +11,10 @@ use heed::{BytesDecode, BytesEncode};
 use roaring::RoaringBitmap;
 
 pub use self::field_doc_id_facet_codec::FieldDocIdFacetCodec;
+pub use self::field_id_bool_codec::FieldIdBoolCodec;
 pub use self::ordered_f64_codec::OrderedF64Codec;
 use super::StrRefCodec;
-use crate::{CboRoaringBitmapCodec, BEU16};
+use crate::{CboRoaringBitmapCodec, CboRoaringBitmapLenCodec, BEU16};
 
 pub type FieldDocIdFacetF64Codec = FieldDocIdFacetCodec<OrderedF64Codec>;
 pub type FieldDocIdFacetStringCodec = FieldDocIdFacetCodec<StrRefCodec>;
@@ -101,3 +103,12 @@ impl<'a> heed::BytesDecode<'a> for FacetGroupValueCodec {
         Some(FacetGroupValue { size, bitmap })
     }
 }
+
+/// Only decodes the length of the bitmap of a [`FacetGroupValue`].
+pub struct FacetGroupValueLenCodec;
+impl<'a> heed::BytesDecode<'a> for FacetGroupValueLenCodec {
+    type DItem = u64;
+    fn bytes_decode(bytes: &'a [u8]) -> Option<Self::DItem> {
+        CboRoaringBitmapLenCodec::bytes_decode(bytes.get(1..)?)
+    }
+}
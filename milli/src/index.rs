@@ -16,14 +16,14 @@ use crate::facet::FacetType;
 use crate::fields_ids_map::FieldsIdsMap;
 use crate::heed_codec::facet::{
     FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec,
-    FieldIdCodec, OrderedF64Codec,
+    FieldIdBoolCodec, FieldIdCodec, OrderedF64Codec,
 };
 use crate::heed_codec::StrRefCodec;
 use crate::{
     default_criteria, BEU32StrCodec, BoRoaringBitmapCodec, CboRoaringBitmapCodec, Criterion,
     DocumentId, ExternalDocumentsIds, FacetDistribution, FieldDistribution, FieldId,
     FieldIdWordCountCodec, GeoPoint, ObkvCodec, Result, RoaringBitmapCodec, RoaringBitmapLenCodec,
-    Search, StrBEU32Codec, U8StrStrCodec, BEU16, BEU32,
+    Search, StrBEU32Codec, SynonymRule, Synonyms, U8StrStrCodec, BEU16, BEU32,
 };
 
 pub const DEFAULT_MIN_WORD_LEN_ONE_TYPO: u8 = 5;
@@ -38,12 +38,15 @@ pub mod main_key {
     pub const HIDDEN_FACETED_FIELDS_KEY: &str = "hidden-faceted-fields";
     pub const FILTERABLE_FIELDS_KEY: &str = "filterable-fields";
     pub const SORTABLE_FIELDS_KEY: &str = "sortable-fields";
+    pub const DATE_FIELDS_KEY: &str = "date-fields";
+    pub const CASE_SENSITIVE_FACETS_KEY: &str = "case-sensitive-facets";
     pub const FIELD_DISTRIBUTION_KEY: &str = "fields-distribution";
     pub const FIELDS_IDS_MAP_KEY: &str = "fields-ids-map";
     pub const GEO_FACETED_DOCUMENTS_IDS_KEY: &str = "geo-faceted-documents-ids";
     pub const GEO_RTREE_KEY: &str = "geo-rtree";
     pub const HARD_EXTERNAL_DOCUMENTS_IDS_KEY: &str = "hard-external-documents-ids";
     pub const NUMBER_FACETED_DOCUMENTS_IDS_PREFIX: &str = "number-faceted-documents-ids";
+    pub const BOOLEAN_FACETED_DOCUMENTS_IDS_PREFIX: &str = "boolean-faceted-documents-ids";
     pub const PRIMARY_KEY_KEY: &str = "primary-key";
     pub const SEARCHABLE_FIELDS_KEY: &str = "searchable-fields";
     pub const USER_DEFINED_SEARCHABLE_FIELDS_KEY: &str = "user-defined-searchable-fields";
@@ -51,10 +54,14 @@ pub mod main_key {
     pub const STOP_WORDS_KEY: &str = "stop-words";
     pub const STRING_FACETED_DOCUMENTS_IDS_PREFIX: &str = "string-faceted-documents-ids";
     pub const SYNONYMS_KEY: &str = "synonyms";
+    pub const SYNONYM_RULES_KEY: &str = "synonym-rules";
+    pub const SYNONYM_EXPANSIONS_KEY: &str = "synonym-expansions";
+    pub const INDEX_TIME_SYNONYMS_KEY: &str = "index-time-synonyms";
     pub const WORDS_FST_KEY: &str = "words-fst";
     pub const WORDS_PREFIXES_FST_KEY: &str = "words-prefixes-fst";
     pub const CREATED_AT_KEY: &str = "created-at";
     pub const UPDATED_AT_KEY: &str = "updated-at";
+    pub const GENERATION_KEY: &str = "generation";
     pub const AUTHORIZE_TYPOS: &str = "authorize-typos";
     pub const ONE_TYPO_WORD_LEN: &str = "one-typo-word-len";
     pub const TWO_TYPOS_WORD_LEN: &str = "two-typos-word-len";
@@ -79,7 +86,10 @@ pub mod db_name {
     pub const FIELD_ID_WORD_COUNT_DOCIDS: &str = "field-id-word-count-docids";
     pub const FACET_ID_F64_DOCIDS: &str = "facet-id-f64-docids";
     pub const FACET_ID_EXISTS_DOCIDS: &str = "facet-id-exists-docids";
+    pub const FACET_ID_IS_NULL_DOCIDS: &str = "facet-id-is-null-docids";
+    pub const FACET_ID_IS_EMPTY_DOCIDS: &str = "facet-id-is-empty-docids";
     pub const FACET_ID_STRING_DOCIDS: &str = "facet-id-string-docids";
+    pub const FACET_ID_BOOL_DOCIDS: &str = "facet-id-bool-docids";
     pub const FIELD_ID_DOCID_FACET_F64S: &str = "field-id-docid-facet-f64s";
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
     pub const DOCUMENTS: &str = "documents";
@@ -124,11 +134,17 @@ pub struct Index {
 
     /// Maps the facet field id and the docids for which this field exists
     pub facet_id_exists_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
+    /// Maps the facet field id and the docids for which this field is set as null
+    pub facet_id_is_null_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
+    /// Maps the facet field id and the docids for which this field is considered empty
+    pub facet_id_is_empty_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
 
     /// Maps the facet field id and ranges of numbers with the docids that corresponds to them.
     pub facet_id_f64_docids: Database<FacetGroupKeyCodec<OrderedF64Codec>, FacetGroupValueCodec>,
     /// Maps the facet field id and ranges of strings with the docids that corresponds to them.
     pub facet_id_string_docids: Database<FacetGroupKeyCodec<StrRefCodec>, FacetGroupValueCodec>,
+    /// Maps the facet field id and a boolean with the docids that corresponds to it.
+    pub facet_id_bool_docids: Database<FieldIdBoolCodec, CboRoaringBitmapCodec>,
 
     /// Maps the document id, the facet field id and the numbers.
     pub field_id_docid_facet_f64s: Database<FieldDocIdFacetF64Codec, Unit>,
@@ -148,7 +164,7 @@ impl Index {
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(18);
+        options.max_dbs(21);
         unsafe { options.flag(Flags::MdbAlwaysFreePages) };
 
         let env = options.open(path)?;
@@ -168,7 +184,10 @@ impl Index {
         let word_prefix_position_docids = env.create_database(Some(WORD_PREFIX_POSITION_DOCIDS))?;
         let facet_id_f64_docids = env.create_database(Some(FACET_ID_F64_DOCIDS))?;
         let facet_id_string_docids = env.create_database(Some(FACET_ID_STRING_DOCIDS))?;
+        let facet_id_bool_docids = env.create_database(Some(FACET_ID_BOOL_DOCIDS))?;
         let facet_id_exists_docids = env.create_database(Some(FACET_ID_EXISTS_DOCIDS))?;
+        let facet_id_is_null_docids = env.create_database(Some(FACET_ID_IS_NULL_DOCIDS))?;
+        let facet_id_is_empty_docids = env.create_database(Some(FACET_ID_IS_EMPTY_DOCIDS))?;
 
         let field_id_docid_facet_f64s = env.create_database(Some(FIELD_ID_DOCID_FACET_F64S))?;
         let field_id_docid_facet_strings =
@@ -193,7 +212,10 @@ impl Index {
             field_id_word_count_docids,
             facet_id_f64_docids,
             facet_id_string_docids,
+            facet_id_bool_docids,
             facet_id_exists_docids,
+            facet_id_is_null_docids,
+            facet_id_is_empty_docids,
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             documents,
@@ -230,9 +252,12 @@ impl Index {
         Ok(())
     }
 
-    /// Create a write transaction to be able to write into the index.
+    /// Create a write transaction to be able to write into the index,
+    /// it increments the generation of the index once committed.
     pub fn write_txn(&self) -> heed::Result<RwTxn> {
-        self.env.write_txn()
+        let mut wtxn = self.env.write_txn()?;
+        self.increment_generation(&mut wtxn)?;
+        Ok(wtxn)
     }
 
     /// Create a read transaction to be able to read the index.
@@ -712,6 +737,70 @@ impl Index {
         Ok(fields.into_iter().filter_map(|name| fields_ids_map.id(&name)).collect())
     }
 
+    /* date fields */
+
+    /// Writes the date fields names in the database.
+    pub(crate) fn put_date_fields(
+        &self,
+        wtxn: &mut RwTxn,
+        fields: &HashSet<String>,
+    ) -> heed::Result<()> {
+        self.main.put::<_, Str, SerdeJson<_>>(wtxn, main_key::DATE_FIELDS_KEY, fields)
+    }
+
+    /// Deletes the date fields names in the database.
+    pub(crate) fn delete_date_fields(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.delete::<_, Str>(wtxn, main_key::DATE_FIELDS_KEY)
+    }
+
+    /// Returns the date fields names, the RFC 3339 strings of these fields
+    /// are stored as timestamps in the facet number databases.
+    pub fn date_fields(&self, rtxn: &RoTxn) -> heed::Result<HashSet<String>> {
+        Ok(self
+            .main
+            .get::<_, Str, SerdeJson<_>>(rtxn, main_key::DATE_FIELDS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Identical to `date_fields`, but returns ids instead.
+    pub fn date_fields_ids(&self, rtxn: &RoTxn) -> Result<HashSet<FieldId>> {
+        let fields = self.date_fields(rtxn)?;
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        Ok(fields.into_iter().filter_map(|name| fields_ids_map.id(&name)).collect())
+    }
+
+    /* case sensitive facets */
+
+    /// Writes the case sensitive facets names in the database.
+    pub(crate) fn put_case_sensitive_facets(
+        &self,
+        wtxn: &mut RwTxn,
+        fields: &HashSet<String>,
+    ) -> heed::Result<()> {
+        self.main.put::<_, Str, SerdeJson<_>>(wtxn, main_key::CASE_SENSITIVE_FACETS_KEY, fields)
+    }
+
+    /// Deletes the case sensitive facets names in the database.
+    pub(crate) fn delete_case_sensitive_facets(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.delete::<_, Str>(wtxn, main_key::CASE_SENSITIVE_FACETS_KEY)
+    }
+
+    /// Returns the case sensitive facets names, the string values of these fields
+    /// are neither lowercased nor trimmed in the facet databases.
+    pub fn case_sensitive_facets(&self, rtxn: &RoTxn) -> heed::Result<HashSet<String>> {
+        Ok(self
+            .main
+            .get::<_, Str, SerdeJson<_>>(rtxn, main_key::CASE_SENSITIVE_FACETS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Identical to `case_sensitive_facets`, but returns ids instead.
+    pub fn case_sensitive_facets_ids(&self, rtxn: &RoTxn) -> Result<HashSet<FieldId>> {
+        let fields = self.case_sensitive_facets(rtxn)?;
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        Ok(fields.into_iter().filter_map(|name| fields_ids_map.id(&name)).collect())
+    }
+
     /* faceted fields */
 
     /// Writes the faceted fields in the database.
@@ -799,6 +888,7 @@ impl Index {
         let key = match facet_type {
             FacetType::String => main_key::STRING_FACETED_DOCUMENTS_IDS_PREFIX,
             FacetType::Number => main_key::NUMBER_FACETED_DOCUMENTS_IDS_PREFIX,
+            FacetType::Boolean => main_key::BOOLEAN_FACETED_DOCUMENTS_IDS_PREFIX,
         };
         let mut buffer = vec![0u8; key.len() + size_of::<FieldId>()];
         buffer[..key.len()].copy_from_slice(key.as_bytes());
@@ -816,6 +906,7 @@ impl Index {
         let key = match facet_type {
             FacetType::String => main_key::STRING_FACETED_DOCUMENTS_IDS_PREFIX,
             FacetType::Number => main_key::NUMBER_FACETED_DOCUMENTS_IDS_PREFIX,
+            FacetType::Boolean => main_key::BOOLEAN_FACETED_DOCUMENTS_IDS_PREFIX,
         };
         let mut buffer = vec![0u8; key.len() + size_of::<FieldId>()];
         buffer[..key.len()].copy_from_slice(key.as_bytes());
@@ -838,6 +929,44 @@ impl Index {
         }
     }
 
+    /// Retrieve all the documents which contain this field id set as null
+    pub fn null_faceted_documents_ids(
+        &self,
+        rtxn: &RoTxn,
+        field_id: FieldId,
+    ) -> heed::Result<RoaringBitmap> {
+        match self.facet_id_is_null_docids.get(rtxn, &BEU16::new(field_id))? {
+            Some(docids) => Ok(docids),
+            None => Ok(RoaringBitmap::new()),
+        }
+    }
+
+    /// Retrieve all the documents which contain this field id as an empty
+    /// string, an empty array or an empty object
+    pub fn empty_faceted_documents_ids(
+        &self,
+        rtxn: &RoTxn,
+        field_id: FieldId,
+    ) -> heed::Result<RoaringBitmap> {
+        match self.facet_id_is_empty_docids.get(rtxn, &BEU16::new(field_id))? {
+            Some(docids) => Ok(docids),
+            None => Ok(RoaringBitmap::new()),
+        }
+    }
+
+    /// Retrieve all the documents which contain this field id set to the given boolean.
+    pub fn bool_faceted_documents_ids(
+        &self,
+        rtxn: &RoTxn,
+        field_id: FieldId,
+        value: bool,
+    ) -> heed::Result<RoaringBitmap> {
+        match self.facet_id_bool_docids.get(rtxn, &(field_id, value))? {
+            Some(docids) => Ok(docids),
+            None => Ok(RoaringBitmap::new()),
+        }
+    }
+
     /* distinct field */
 
     pub(crate) fn put_distinct_field(
@@ -938,13 +1067,92 @@ impl Index {
             .unwrap_or_default())
     }
 
+    /// Writes the synonym rules as defined by the user and their normalized expansions.
+    pub(crate) fn put_synonym_rules(
+        &self,
+        wtxn: &mut RwTxn,
+        rules: &[SynonymRule],
+        expansions: &Synonyms,
+    ) -> heed::Result<()> {
+        self.main.put::<_, Str, SerdeBincode<_>>(wtxn, main_key::SYNONYM_RULES_KEY, &rules)?;
+        self.main.put::<_, Str, SerdeBincode<_>>(wtxn, main_key::SYNONYM_EXPANSIONS_KEY, expansions)
+    }
+
+    pub(crate) fn delete_synonym_rules(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.delete::<_, Str>(wtxn, main_key::SYNONYM_EXPANSIONS_KEY)?;
+        self.main.delete::<_, Str>(wtxn, main_key::SYNONYM_RULES_KEY)
+    }
+
+    /// Returns the synonym rules as defined by the user.
+    pub fn synonym_rules(&self, rtxn: &RoTxn) -> heed::Result<Vec<SynonymRule>> {
+        Ok(self
+            .main
+            .get::<_, Str, SerdeBincode<_>>(rtxn, main_key::SYNONYM_RULES_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the normalized expansions of the synonym rules.
+    pub fn synonym_expansions(&self, rtxn: &RoTxn) -> heed::Result<Synonyms> {
+        Ok(self
+            .main
+            .get::<_, Str, SerdeBincode<_>>(rtxn, main_key::SYNONYM_EXPANSIONS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Returns all the synonyms, both from the synonyms and from
+    /// the expansions of the synonym rules, indexed by the words they expand.
+    pub fn all_synonyms(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HashMap<Vec<String>, Vec<Vec<String>>>> {
+        let mut synonyms = self.synonyms(rtxn)?;
+        for (words, expansions) in self.synonym_expansions(rtxn)?.iter() {
+            let entry = synonyms.entry(words.to_vec()).or_default();
+            entry.extend_from_slice(expansions);
+            entry.sort_unstable();
+            entry.dedup();
+        }
+        Ok(synonyms)
+    }
+
+    /// Returns `true` if the synonyms are expanded when indexing the documents
+    /// rather than when searching, defaults to `false`.
+    pub fn index_time_synonyms(&self, rtxn: &RoTxn) -> heed::Result<bool> {
+        match self.main.get::<_, Str, OwnedType<u8>>(rtxn, main_key::INDEX_TIME_SYNONYMS_KEY)? {
+            Some(flag) => Ok(flag != 0),
+            None => Ok(false),
+        }
+    }
+
+    pub(crate) fn put_index_time_synonyms(&self, wtxn: &mut RwTxn, flag: bool) -> heed::Result<()> {
+        // It is not possible to put a bool in heed with OwnedType, so we put a u8 instead.
+        self.main.put::<_, Str, OwnedType<u8>>(
+            wtxn,
+            main_key::INDEX_TIME_SYNONYMS_KEY,
+            &(flag as u8),
+        )
+    }
+
+    pub(crate) fn delete_index_time_synonyms(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.delete::<_, Str>(wtxn, main_key::INDEX_TIME_SYNONYMS_KEY)
+    }
+
+    /// Returns the synonyms of the given normalized words, both from
+    /// the synonyms and from the expansions of the synonym rules.
     pub fn words_synonyms<S: AsRef<str>>(
         &self,
         rtxn: &RoTxn,
         words: &[S],
     ) -> heed::Result<Option<Vec<Vec<String>>>> {
         let words: Vec<_> = words.iter().map(|s| s.as_ref().to_owned()).collect();
-        Ok(self.synonyms(rtxn)?.remove(&words))
+        let mut synonyms = self.synonyms(rtxn)?.remove(&words);
+        if let Some(expansions) = self.synonym_expansions(rtxn)?.get(&words) {
+            let entry = synonyms.get_or_insert_with(Vec::new);
+            entry.extend_from_slice(expansions);
+            entry.sort_unstable();
+            entry.dedup();
+        }
+        Ok(synonyms)
     }
 
     /* words prefixes fst */
@@ -1058,6 +1266,17 @@ impl Index {
         self.main.put::<_, Str, SerdeJson<OffsetDateTime>>(wtxn, main_key::UPDATED_AT_KEY, time)
     }
 
+    /// Returns a counter that is incremented by every write transaction of the index,
+    /// two reads returning the same generation see the same documents and settings.
+    pub fn generation(&self, rtxn: &RoTxn) -> heed::Result<u64> {
+        Ok(self.main.get::<_, Str, OwnedType<u64>>(rtxn, main_key::GENERATION_KEY)?.unwrap_or(0))
+    }
+
+    fn increment_generation(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        let generation = self.generation(wtxn)?.wrapping_add(1);
+        self.main.put::<_, Str, OwnedType<u64>>(wtxn, main_key::GENERATION_KEY, &generation)
+    }
+
     pub fn authorize_typos(&self, txn: &RoTxn) -> heed::Result<bool> {
         // It is not possible to put a bool in heed with OwnedType, so we put a u8 instead. We
         // identify 0 as being false, and anything else as true. The absence of a value is true,
@@ -2281,8 +2500,13 @@ pub(crate) mod tests {
 
         let rtxn = index.read_txn().unwrap();
         let search = Search::new(&rtxn, &index);
-        let SearchResult { matching_words: _, candidates: _, mut documents_ids } =
-            search.execute().unwrap();
+        let SearchResult {
+            matching_words: _,
+            candidates: _,
+            mut documents_ids,
+            document_scores: _,
+            geo_distances: _,
+        } = search.execute().unwrap();
         let primary_key_id = index.fields_ids_map(&rtxn).unwrap().id("primary_key").unwrap();
         documents_ids.sort_unstable();
         let docs = index.documents(&rtxn, documents_ids).unwrap();
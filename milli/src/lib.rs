@@ -11,7 +11,9 @@ mod fields_ids_map;
 pub mod heed_codec;
 pub mod index;
 pub mod proximity;
+pub mod score_details;
 mod search;
+mod synonyms;
 pub mod update;
 
 #[cfg(test)]
@@ -22,7 +24,10 @@ use std::collections::{BTreeMap, HashMap};
 use std::convert::{TryFrom, TryInto};
 use std::hash::BuildHasherDefault;
 
-pub use filter_parser::{Condition, FilterCondition, Span, Token};
+pub use filter_parser::{
+    Condition, FilterCondition, FilterValue, OwnedCondition, OwnedFilterCondition, OwnedValue, Span,
+    Token,
+};
 use fxhash::{FxHasher32, FxHasher64};
 pub use grenad::CompressionType;
 use serde_json::Value;
@@ -41,11 +46,15 @@ pub use self::heed_codec::{
     RoaringBitmapLenCodec, StrBEU32Codec, U8StrStrCodec, UncheckedU8StrStrCodec,
 };
 pub use self::index::Index;
+pub use self::score_details::ScoreDetails;
 pub use self::search::{
-    CriterionImplementationStrategy, FacetDistribution, Filter, FormatOptions, MatchBounds,
-    MatcherBuilder, MatchingWord, MatchingWords, Search, SearchResult, TermsMatchingStrategy,
-    DEFAULT_VALUES_PER_FACET,
+    CriterionImplementationStrategy, FacetBucket, FacetBuckets, FacetDistribution, FacetRange,
+    FacetStats, FacetValueHit, FederatedHit, FederatedSearch, FederatedSearchResult, Filter,
+    FilterCache, FormatOptions, MatchBounds, MatcherBuilder, MatchingWord, MatchingWords,
+    NumericFacetStats, OrderBy, Search, SearchForFacetValues, SearchResult,
+    TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
 };
+pub use self::synonyms::{SynonymRule, Synonyms};
 
 pub type Result<T> = std::result::Result<T, error::Error>;
 
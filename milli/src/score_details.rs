@@ -0,0 +1,147 @@
+use serde::Serialize;
+
+use crate::distance_between_two_points;
+
+/// The details of the bucket a document has been ranked in, for one criterion.
+///
+/// A search returns one list of details per document, in the order the criteria
+/// have been applied. [`ScoreDetails::global_score`] merges such a list into a
+/// single relevancy score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScoreDetails {
+    Words(Rank),
+    Typo(Typo),
+    Proximity(Rank),
+    Attribute(Rank),
+    Exactness(Rank),
+    Sort(Sort),
+    GeoSort(GeoSort),
+}
+
+impl ScoreDetails {
+    /// Returns the rank of the bucket, or `None` if the criterion does not participate to the
+    /// relevancy score, as it is the case for the sort criteria.
+    pub fn rank(&self) -> Option<Rank> {
+        match self {
+            ScoreDetails::Typo(details) => Some(details.rank()),
+            ScoreDetails::Words(rank)
+            | ScoreDetails::Proximity(rank)
+            | ScoreDetails::Attribute(rank)
+            | ScoreDetails::Exactness(rank) => Some(*rank),
+            ScoreDetails::Sort(_) | ScoreDetails::GeoSort(_) => None,
+        }
+    }
+
+    /// Computes a score between `0.0` and `1.0` from the details of all the criteria
+    /// a document went through, the first criteria having precedence over the following ones.
+    pub fn global_score<'a>(details: impl IntoIterator<Item = &'a ScoreDetails>) -> f64 {
+        Rank::global_score(details.into_iter().filter_map(ScoreDetails::rank))
+    }
+}
+
+/// The position of a bucket among all the buckets a criterion can return,
+/// `max_rank` being the best possible bucket and `1` the worst one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rank {
+    pub rank: u32,
+    pub max_rank: u32,
+}
+
+impl Rank {
+    /// Returns the score of this rank between `0.0` and `1.0`.
+    pub fn local_score(self) -> f64 {
+        self.rank as f64 / self.max_rank.max(1) as f64
+    }
+
+    /// Merges the ranks lexicographically: each rank splits the score interval
+    /// left by the previous ranks into `max_rank` buckets of equal width.
+    pub fn global_score(ranks: impl IntoIterator<Item = Rank>) -> f64 {
+        let mut lower_bound = 0.0;
+        let mut width = 1.0;
+        for Rank { rank, max_rank } in ranks {
+            width /= max_rank.max(1) as f64;
+            lower_bound += rank.saturating_sub(1) as f64 * width;
+        }
+        lower_bound + width
+    }
+}
+
+/// The details of the `Typo` criterion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Typo {
+    pub typo_count: u32,
+    pub max_typo_count: u32,
+}
+
+impl Typo {
+    pub fn rank(&self) -> Rank {
+        Rank {
+            rank: self.max_typo_count.saturating_sub(self.typo_count) + 1,
+            max_rank: self.max_typo_count + 1,
+        }
+    }
+}
+
+/// The details of an `Asc`/`Desc` criterion, `value` is the facet value
+/// that determined the bucket or `null` if the document doesn't have one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sort {
+    pub field_name: String,
+    pub ascending: bool,
+    pub value: serde_json::Value,
+}
+
+/// The details of a `_geoPoint` sort, `value` is the position of the document
+/// or `None` if the document isn't geolocated.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoSort {
+    pub target_point: [f64; 2],
+    pub ascending: bool,
+    pub value: Option<[f64; 2]>,
+}
+
+impl GeoSort {
+    /// Returns the distance in meters between the target point and the document.
+    pub fn distance(&self) -> Option<f64> {
+        self.value.map(|value| distance_between_two_points(&self.target_point, &value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn global_score_is_lexicographic() {
+        let best =
+            Rank::global_score([Rank { rank: 3, max_rank: 3 }, Rank { rank: 1, max_rank: 4 }]);
+        let worst =
+            Rank::global_score([Rank { rank: 2, max_rank: 3 }, Rank { rank: 4, max_rank: 4 }]);
+        assert!(best > worst);
+
+        assert_eq!(Rank::global_score(std::iter::empty()), 1.0);
+        assert_eq!(Rank::global_score([Rank { rank: 1, max_rank: 2 }]), 0.5);
+        assert_eq!(
+            Rank::global_score([Rank { rank: 2, max_rank: 2 }, Rank { rank: 1, max_rank: 2 }]),
+            0.75
+        );
+    }
+
+    #[test]
+    fn sort_details_are_ignored() {
+        let details = [
+            ScoreDetails::Typo(Typo { typo_count: 1, max_typo_count: 1 }),
+            ScoreDetails::Sort(Sort {
+                field_name: "price".to_string(),
+                ascending: true,
+                value: serde_json::json!(12.0),
+            }),
+        ];
+        assert_eq!(ScoreDetails::global_score(&details), 0.5);
+    }
+}
@@ -4,16 +4,18 @@ use itertools::Itertools;
 use log::debug;
 use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
+use serde_json::Value;
 
 use super::{Criterion, CriterionParameters, CriterionResult};
 use crate::facet::FacetType;
 use crate::heed_codec::facet::FacetGroupKeyCodec;
 use crate::heed_codec::ByteSliceRefCodec;
+use crate::score_details::{self, ScoreDetails};
 use crate::search::criteria::{resolve_query_tree, CriteriaBuilder, InitialCandidates};
 use crate::search::facet::{ascending_facet_sort, descending_facet_sort};
 use crate::search::query_tree::Operation;
 use crate::search::CriterionImplementationStrategy;
-use crate::{FieldId, Index, Result};
+use crate::{DocumentId, FieldId, Index, Result};
 
 /// Threshold on the number of candidates that will make
 /// the system to choose between one algorithm or another.
@@ -29,6 +31,7 @@ pub struct AscDesc<'t> {
     candidates: Box<dyn Iterator<Item = heed::Result<RoaringBitmap>> + 't>,
     allowed_candidates: RoaringBitmap,
     initial_candidates: InitialCandidates,
+    score_details: Vec<ScoreDetails>,
     faceted_candidates: RoaringBitmap,
     implementation_strategy: CriterionImplementationStrategy,
     parent: Box<dyn Criterion + 't>,
@@ -71,7 +74,9 @@ impl<'t> AscDesc<'t> {
                     index.faceted_documents_ids(rtxn, field_id, FacetType::Number)?;
                 let string_faceted =
                     index.faceted_documents_ids(rtxn, field_id, FacetType::String)?;
-                number_faceted | string_faceted
+                let bool_faceted =
+                    index.faceted_documents_ids(rtxn, field_id, FacetType::Boolean)?;
+                number_faceted | string_faceted | bool_faceted
             }
             None => RoaringBitmap::default(),
         };
@@ -87,10 +92,56 @@ impl<'t> AscDesc<'t> {
             allowed_candidates: RoaringBitmap::new(),
             faceted_candidates,
             initial_candidates: InitialCandidates::Estimated(RoaringBitmap::new()),
+            score_details: Vec::new(),
             implementation_strategy,
             parent,
         })
     }
+
+    /// Returns the facet value that determined the bucket of the given document,
+    /// the smallest one when ascending and the biggest one when descending.
+    fn facet_value(&self, docid: DocumentId) -> Result<Value> {
+        let field_id = match self.field_id {
+            Some(field_id) => field_id,
+            None => return Ok(Value::Null),
+        };
+
+        let left = (field_id, docid, f64::MIN);
+        let right = (field_id, docid, f64::MAX);
+        let mut iter = self.index.field_id_docid_facet_f64s.range(self.rtxn, &(left..=right))?;
+        let entry = if self.is_ascending { iter.next() } else { iter.last() };
+        if let Some(((_, _, value), ())) = entry.transpose()? {
+            return Ok(Value::from(value));
+        }
+
+        let left = (field_id, docid, "");
+        let right = (field_id, docid.saturating_add(1), "");
+        let mut iter = self.index.field_id_docid_facet_strings.range(self.rtxn, &(left..right))?;
+        let entry = if self.is_ascending { iter.next() } else { iter.last() };
+        if let Some((_, original)) = entry.transpose()? {
+            return Ok(Value::from(original));
+        }
+
+        let booleans = if self.is_ascending { [false, true] } else { [true, false] };
+        for boolean in booleans {
+            let docids = self.index.bool_faceted_documents_ids(self.rtxn, field_id, boolean)?;
+            if docids.contains(docid) {
+                return Ok(Value::Bool(boolean));
+            }
+        }
+
+        Ok(Value::Null)
+    }
+
+    fn bucket_score_details(&self, value: Value) -> Vec<ScoreDetails> {
+        let mut score_details = self.score_details.clone();
+        score_details.push(ScoreDetails::Sort(score_details::Sort {
+            field_name: self.field_name.clone(),
+            ascending: self.is_ascending,
+            value,
+        }));
+        score_details
+    }
 }
 
 impl<'t> Criterion for AscDesc<'t> {
@@ -108,11 +159,13 @@ impl<'t> Criterion for AscDesc<'t> {
 
             match self.candidates.next().transpose()? {
                 None if !self.allowed_candidates.is_empty() => {
+                    // The remaining candidates don't have any value for this field.
                     return Ok(Some(CriterionResult {
                         query_tree: self.query_tree.clone(),
                         candidates: Some(take(&mut self.allowed_candidates)),
                         filtered_candidates: None,
                         initial_candidates: Some(self.initial_candidates.take()),
+                        score_details: self.bucket_score_details(Value::Null),
                     }));
                 }
                 None => match self.parent.next(params)? {
@@ -121,8 +174,10 @@ impl<'t> Criterion for AscDesc<'t> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         self.query_tree = query_tree;
+                        self.score_details = score_details;
                         let mut candidates = match (&self.query_tree, candidates) {
                             (_, Some(candidates)) => candidates,
                             (Some(qt), None) => {
@@ -165,11 +220,16 @@ impl<'t> Criterion for AscDesc<'t> {
                 Some(mut candidates) => {
                     candidates -= params.excluded_candidates;
                     self.allowed_candidates -= &candidates;
+                    let value = match candidates.min() {
+                        Some(docid) => self.facet_value(docid)?,
+                        None => Value::Null,
+                    };
                     return Ok(Some(CriterionResult {
                         query_tree: self.query_tree.clone(),
                         candidates: Some(candidates),
                         filtered_candidates: None,
                         initial_candidates: Some(self.initial_candidates.take()),
+                        score_details: self.bucket_score_details(value),
                     }));
                 }
             }
@@ -191,9 +251,16 @@ fn facet_ordered_iterative<'t>(
         is_ascending,
         candidates.clone(),
     )?;
-    let string_iter =
-        iterative_facet_string_ordered_iter(index, rtxn, field_id, is_ascending, candidates)?;
-    Ok(Box::new(number_iter.chain(string_iter).map(Ok)) as Box<dyn Iterator<Item = _>>)
+    let string_iter = iterative_facet_string_ordered_iter(
+        index,
+        rtxn,
+        field_id,
+        is_ascending,
+        candidates.clone(),
+    )?;
+    let bool_iter = facet_bool_ordered_iter(index, rtxn, field_id, is_ascending, candidates)?;
+    Ok(Box::new(number_iter.chain(string_iter).chain(bool_iter).map(Ok))
+        as Box<dyn Iterator<Item = _>>)
 }
 
 fn facet_ordered_set_based<'t>(
@@ -216,10 +283,31 @@ fn facet_ordered_set_based<'t>(
         rtxn,
         index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
         field_id,
-        candidates,
+        candidates.clone(),
     )?;
 
-    Ok(Box::new(number_iter.chain(string_iter)))
+    let bool_iter = facet_bool_ordered_iter(index, rtxn, field_id, is_ascending, candidates)?;
+
+    Ok(Box::new(number_iter.chain(string_iter).chain(bool_iter.map(Ok))))
+}
+
+/// Returns the candidates grouped by boolean value, `false` comes before `true` when ascending.
+fn facet_bool_ordered_iter(
+    index: &Index,
+    rtxn: &heed::RoTxn,
+    field_id: FieldId,
+    is_ascending: bool,
+    candidates: RoaringBitmap,
+) -> heed::Result<impl Iterator<Item = RoaringBitmap>> {
+    let booleans = if is_ascending { [false, true] } else { [true, false] };
+    let mut groups = Vec::with_capacity(booleans.len());
+    for boolean in booleans {
+        let docids = index.bool_faceted_documents_ids(rtxn, field_id, boolean)? & &candidates;
+        if !docids.is_empty() {
+            groups.push(docids);
+        }
+    }
+    Ok(groups.into_iter())
 }
 
 /// Returns an iterator over groups of the given candidates in ascending or descending order.
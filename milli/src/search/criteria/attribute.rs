@@ -7,12 +7,13 @@ use std::mem::take;
 use roaring::RoaringBitmap;
 
 use super::{resolve_query_tree, Context, Criterion, CriterionParameters, CriterionResult};
+use crate::score_details::{Rank, ScoreDetails};
 use crate::search::criteria::{InitialCandidates, Query};
 use crate::search::query_tree::{Operation, QueryKind};
 use crate::search::{
     build_dfa, word_derivations, CriterionImplementationStrategy, WordDerivationsCache,
 };
-use crate::Result;
+use crate::{absolute_from_relative_position, RelativePosition, Result};
 
 /// To be able to divide integers by the number of words in the query
 /// we want to find a multiplier that allow us to divide by any number between 1 and 10.
@@ -29,6 +30,9 @@ pub struct Attribute<'t> {
     ctx: &'t dyn Context<'t>,
     state: Option<(Operation, FlattenedQueryTree, RoaringBitmap)>,
     initial_candidates: InitialCandidates,
+    score_details: Vec<ScoreDetails>,
+    /// One more than the highest position a word can have in the searchable fields.
+    max_rank: u32,
     parent: Box<dyn Criterion + 't>,
     linear_buckets: Option<btree_map::IntoIter<u64, RoaringBitmap>>,
     set_buckets: Option<BinaryHeap<Branch<'t>>>,
@@ -45,12 +49,25 @@ impl<'t> Attribute<'t> {
             ctx,
             state: None,
             initial_candidates: InitialCandidates::Estimated(RoaringBitmap::new()),
+            score_details: Vec::new(),
+            max_rank: 1,
             parent,
             linear_buckets: None,
             set_buckets: None,
             implementation_strategy,
         }
     }
+
+    /// The rank of the documents that could not be ranked by their attributes.
+    fn worst_rank(&self) -> Rank {
+        Rank { rank: 1, max_rank: self.max_rank }
+    }
+
+    fn bucket_score_details(&self, rank: Rank) -> Vec<ScoreDetails> {
+        let mut score_details = self.score_details.clone();
+        score_details.push(ScoreDetails::Attribute(rank));
+        score_details
+    }
 }
 
 impl<'t> Criterion for Attribute<'t> {
@@ -69,10 +86,11 @@ impl<'t> Criterion for Attribute<'t> {
                         candidates: Some(RoaringBitmap::new()),
                         filtered_candidates: None,
                         initial_candidates: Some(self.initial_candidates.take()),
+                        score_details: self.bucket_score_details(self.worst_rank()),
                     }));
                 }
                 Some((query_tree, flattened_query_tree, mut allowed_candidates)) => {
-                    let found_candidates = if matches!(
+                    let (found_rank, found_candidates) = if matches!(
                         self.implementation_strategy,
                         CriterionImplementationStrategy::OnlyIterative
                     ) || (matches!(
@@ -94,13 +112,16 @@ impl<'t> Criterion for Attribute<'t> {
                         };
 
                         match linear_buckets.next() {
-                            Some((_score, candidates)) => candidates,
+                            Some((score, candidates)) => {
+                                (attribute_rank(score, self.max_rank), candidates)
+                            }
                             None => {
                                 return Ok(Some(CriterionResult {
                                     query_tree: Some(query_tree),
                                     candidates: Some(RoaringBitmap::new()),
                                     filtered_candidates: None,
                                     initial_candidates: Some(self.initial_candidates.take()),
+                                    score_details: self.bucket_score_details(self.worst_rank()),
                                 }));
                             }
                         }
@@ -119,13 +140,16 @@ impl<'t> Criterion for Attribute<'t> {
                         };
 
                         match set_compute_candidates(set_buckets, &allowed_candidates)? {
-                            Some((_score, candidates)) => candidates,
+                            Some((score, candidates)) => {
+                                (attribute_rank(score as u64, self.max_rank), candidates)
+                            }
                             None => {
                                 return Ok(Some(CriterionResult {
                                     query_tree: Some(query_tree),
                                     candidates: Some(allowed_candidates),
                                     filtered_candidates: None,
                                     initial_candidates: Some(self.initial_candidates.take()),
+                                    score_details: self.bucket_score_details(self.worst_rank()),
                                 }));
                             }
                         }
@@ -141,6 +165,7 @@ impl<'t> Criterion for Attribute<'t> {
                         candidates: Some(found_candidates),
                         filtered_candidates: None,
                         initial_candidates: Some(self.initial_candidates.take()),
+                        score_details: self.bucket_score_details(found_rank),
                     }));
                 }
                 None => match self.parent.next(params)? {
@@ -149,6 +174,7 @@ impl<'t> Criterion for Attribute<'t> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         let mut candidates = match candidates {
                             Some(candidates) => candidates,
@@ -173,18 +199,22 @@ impl<'t> Criterion for Attribute<'t> {
 
                         self.state = Some((query_tree, flattened_query_tree, candidates));
                         self.linear_buckets = None;
+                        self.score_details = score_details;
+                        self.max_rank = max_attribute_rank(self.ctx)?;
                     }
                     Some(CriterionResult {
                         query_tree: None,
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         return Ok(Some(CriterionResult {
                             query_tree: None,
                             candidates,
                             filtered_candidates,
                             initial_candidates,
+                            score_details,
                         }));
                     }
                     None => return Ok(None),
@@ -563,6 +593,21 @@ fn initialize_linear_buckets(
     Ok(candidates)
 }
 
+/// Returns one more than the highest absolute position a word can have
+/// in the searchable fields, the number of buckets this criterion can return.
+fn max_attribute_rank(ctx: &dyn Context) -> Result<u32> {
+    let max_field_id = ctx.searchable_fields_ids()?.into_iter().max().unwrap_or(0);
+    Ok(absolute_from_relative_position(max_field_id, RelativePosition::MAX).saturating_add(1))
+}
+
+/// Converts the score of a bucket, the mean position of the query words multiplied
+/// by [`LCM_10_FIRST_NUMBERS`], into a rank where the lowest positions are the best ones.
+fn attribute_rank(score: u64, max_rank: u32) -> Rank {
+    let mean_position = score / LCM_10_FIRST_NUMBERS as u64;
+    let mean_position = mean_position.min(max_rank as u64 - 1) as u32;
+    Rank { rank: max_rank - mean_position, max_rank }
+}
+
 // TODO can we keep refs of Query
 fn flatten_query_tree(query_tree: &Operation) -> FlattenedQueryTree {
     use crate::search::criteria::Operation::{And, Or, Phrase};
@@ -595,7 +640,7 @@ fn flatten_query_tree(query_tree: &Operation) -> FlattenedQueryTree {
                     ops.iter().flat_map(recurse).collect()
                 }
             }
-            Phrase(words) => {
+            Phrase(words, _) => {
                 let queries = words
                     .iter()
                     .filter_map(|w| w.as_ref())
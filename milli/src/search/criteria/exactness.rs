@@ -6,6 +6,7 @@ use std::mem::take;
 use log::debug;
 use roaring::{MultiOps, RoaringBitmap};
 
+use crate::score_details::{Rank, ScoreDetails};
 use crate::search::criteria::{
     resolve_phrase, resolve_query_tree, Context, Criterion, CriterionParameters, CriterionResult,
     InitialCandidates,
@@ -18,6 +19,7 @@ pub struct Exactness<'t> {
     query_tree: Option<Operation>,
     state: Option<State>,
     initial_candidates: InitialCandidates,
+    score_details: Vec<ScoreDetails>,
     parent: Box<dyn Criterion + 't>,
     query: Vec<ExactQueryPart>,
     cache: Option<ExactWordsCombinationCache>,
@@ -39,6 +41,7 @@ impl<'t> Exactness<'t> {
             query_tree: None,
             state: None,
             initial_candidates: InitialCandidates::Estimated(RoaringBitmap::new()),
+            score_details: Vec::new(),
             parent,
             query,
             cache: None,
@@ -65,15 +68,20 @@ impl<'t> Criterion for Exactness<'t> {
                     // the primitive query, which does not change
                 }
                 Some(state) => {
+                    let rank = state.rank(self.query.len());
                     let (candidates, state) =
                         resolve_state(self.ctx, take(state), &self.query, &mut self.cache)?;
                     self.state = state;
 
+                    let mut score_details = self.score_details.clone();
+                    score_details.push(ScoreDetails::Exactness(rank));
+
                     return Ok(Some(CriterionResult {
                         query_tree: self.query_tree.clone(),
                         candidates: Some(candidates),
                         filtered_candidates: None,
                         initial_candidates: Some(self.initial_candidates.take()),
+                        score_details,
                     }));
                 }
                 None => match self.parent.next(params)? {
@@ -82,6 +90,7 @@ impl<'t> Criterion for Exactness<'t> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         let mut candidates = match candidates {
                             Some(candidates) => candidates,
@@ -104,18 +113,21 @@ impl<'t> Criterion for Exactness<'t> {
 
                         self.state = Some(State::new(candidates));
                         self.query_tree = Some(query_tree);
+                        self.score_details = score_details;
                     }
                     Some(CriterionResult {
                         query_tree: None,
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         return Ok(Some(CriterionResult {
                             query_tree: None,
                             candidates,
                             filtered_candidates,
                             initial_candidates,
+                            score_details,
                         }));
                     }
                     None => return Ok(None),
@@ -153,6 +165,19 @@ impl State {
         }
     }
 
+    /// Returns the rank of the bucket this state will return, a state
+    /// returns one bucket per exact word count plus the two attribute buckets.
+    fn rank(&self, query_len: usize) -> Rank {
+        let max_rank = query_len as u32 + 3;
+        let rank = match self {
+            Self::ExactAttribute(_) => max_rank,
+            Self::AttributeStartsWith(_) => max_rank - 1,
+            Self::ExactWords(_) => query_len as u32 + 1,
+            Self::Remainings(candidates_array) => candidates_array.len() as u32,
+        };
+        Rank { rank, max_rank }
+    }
+
     fn is_empty(&self) -> bool {
         match self {
             Self::ExactAttribute(candidates)
@@ -320,7 +345,8 @@ impl ExactQueryPart {
                     None => ExactQueryPart::Synonyms(vec![word.clone()]),
                 }
             }
-            PrimitiveQueryPart::Phrase(phrase) => ExactQueryPart::Phrase(phrase.clone()),
+            // the words of a phrase with a slop are only exact when they are consecutive.
+            PrimitiveQueryPart::Phrase(phrase, _) => ExactQueryPart::Phrase(phrase.clone()),
         };
 
         Ok(part)
@@ -351,7 +377,7 @@ fn compute_combinations(
             }
             // compute intersection on pair of words with a proximity of 0.
             Phrase(phrase) => {
-                candidates |= resolve_phrase(ctx, phrase)?;
+                candidates |= resolve_phrase(ctx, phrase, 0)?;
             }
         }
         parts_candidates_array.push(candidates);
@@ -529,7 +555,7 @@ mod tests {
 
         let rtxn = index.read_txn().unwrap();
 
-        let SearchResult { matching_words: _, candidates: _, documents_ids } =
+        let SearchResult { matching_words: _, candidates: _, documents_ids, .. } =
             index.search(&rtxn).query("cats are better than dogs").execute().unwrap();
 
         insta::assert_snapshot!(format!("{documents_ids:?}"), @"[5, 4, 3, 2, 1]");
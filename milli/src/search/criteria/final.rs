@@ -2,6 +2,7 @@ use log::debug;
 use roaring::RoaringBitmap;
 
 use super::{resolve_query_tree, Context, Criterion, CriterionParameters, CriterionResult};
+use crate::score_details::ScoreDetails;
 use crate::search::criteria::InitialCandidates;
 use crate::search::query_tree::Operation;
 use crate::search::WordDerivationsCache;
@@ -16,6 +17,8 @@ pub struct FinalResult {
     pub candidates: RoaringBitmap,
     /// Candidates that comes from the current bucket of the initial criterion.
     pub initial_candidates: InitialCandidates,
+    /// The details of the bucket of every criterion these candidates have been ranked in.
+    pub score_details: Vec<ScoreDetails>,
 }
 
 pub struct Final<'t> {
@@ -51,6 +54,7 @@ impl<'t> Final<'t> {
                 candidates,
                 filtered_candidates,
                 initial_candidates,
+                score_details,
             }) => {
                 let mut candidates = match (candidates, query_tree.as_ref()) {
                     (Some(candidates), _) => candidates,
@@ -69,7 +73,7 @@ impl<'t> Final<'t> {
 
                 self.returned_candidates |= &candidates;
 
-                Ok(Some(FinalResult { query_tree, candidates, initial_candidates }))
+                Ok(Some(FinalResult { query_tree, candidates, initial_candidates, score_details }))
             }
             None => Ok(None),
         }
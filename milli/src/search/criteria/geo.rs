@@ -4,6 +4,7 @@ use roaring::RoaringBitmap;
 use rstar::RTree;
 
 use super::{Criterion, CriterionParameters, CriterionResult};
+use crate::score_details::{self, ScoreDetails};
 use crate::search::criteria::{resolve_query_tree, CriteriaBuilder, InitialCandidates};
 use crate::{lat_lng_to_xyz, GeoPoint, Index, Result};
 
@@ -12,9 +13,11 @@ pub struct Geo<'t> {
     rtxn: &'t heed::RoTxn<'t>,
     ascending: bool,
     parent: Box<dyn Criterion + 't>,
-    candidates: Box<dyn Iterator<Item = RoaringBitmap>>,
+    /// The buckets of candidates associated with their geo point.
+    candidates: Box<dyn Iterator<Item = (RoaringBitmap, [f64; 2])>>,
     allowed_candidates: RoaringBitmap,
     initial_candidates: InitialCandidates,
+    score_details: Vec<ScoreDetails>,
     rtree: Option<RTree<GeoPoint>>,
     point: [f64; 2],
 }
@@ -58,6 +61,7 @@ impl<'t> Geo<'t> {
             candidates,
             allowed_candidates,
             initial_candidates,
+            score_details: Vec::new(),
             rtree,
             point,
         })
@@ -70,14 +74,23 @@ impl Criterion for Geo<'_> {
 
         loop {
             match self.candidates.next() {
-                Some(mut candidates) => {
+                Some((mut candidates, value)) => {
                     candidates -= params.excluded_candidates;
                     self.allowed_candidates -= &candidates;
+
+                    let mut score_details = self.score_details.clone();
+                    score_details.push(ScoreDetails::GeoSort(score_details::GeoSort {
+                        target_point: self.point,
+                        ascending: self.ascending,
+                        value: Some(value),
+                    }));
+
                     return Ok(Some(CriterionResult {
                         query_tree: None,
                         candidates: Some(candidates),
                         filtered_candidates: None,
                         initial_candidates: Some(self.initial_candidates.clone()),
+                        score_details,
                     }));
                 }
                 None => match self.parent.next(params)? {
@@ -86,6 +99,7 @@ impl Criterion for Geo<'_> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         let mut candidates = match (&query_tree, candidates) {
                             (_, Some(candidates)) => candidates,
@@ -110,6 +124,7 @@ impl Criterion for Geo<'_> {
                         if candidates.is_empty() {
                             continue;
                         }
+                        self.score_details = score_details;
                         self.allowed_candidates = &candidates - params.excluded_candidates;
                         self.candidates = match rtree {
                             Some(rtree) => geo_point(
@@ -133,13 +148,15 @@ fn geo_point(
     mut candidates: RoaringBitmap,
     point: [f64; 2],
     ascending: bool,
-) -> Box<dyn Iterator<Item = RoaringBitmap>> {
+) -> Box<dyn Iterator<Item = (RoaringBitmap, [f64; 2])>> {
     let point = lat_lng_to_xyz(&point);
 
+    // A document can have several points, it is
+    // ranked by the nearest one as it is seen first.
     let mut results = Vec::new();
     for point in rtree.nearest_neighbor_iter(&point) {
         if candidates.remove(point.data.0) {
-            results.push(std::iter::once(point.data.0).collect());
+            results.push((std::iter::once(point.data.0).collect(), point.data.1));
             if candidates.is_empty() {
                 break;
             }
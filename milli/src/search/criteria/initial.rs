@@ -28,6 +28,7 @@ impl<'t, D> Initial<'t, D> {
             candidates: None,
             filtered_candidates,
             initial_candidates: None,
+            score_details: Vec::new(),
         };
         Initial { ctx, answer: Some(answer), exhaustive_number_hits, distinct }
     }
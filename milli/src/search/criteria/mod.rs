@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::mem::take;
 use std::ops::{BitOr, BitOrAssign};
@@ -13,12 +14,17 @@ use self::proximity::Proximity;
 use self::r#final::Final;
 use self::typo::Typo;
 use self::words::Words;
-use super::query_tree::{Operation, PrimitiveQueryPart, Query, QueryKind};
+use super::query_tree::{Operation, PrimitiveQueryPart, Query, QueryKind, Slop};
 use super::CriterionImplementationStrategy;
+use crate::proximity::MAX_DISTANCE;
+use crate::score_details::ScoreDetails;
 use crate::search::criteria::geo::Geo;
 use crate::search::{word_derivations, Distinct, WordDerivationsCache};
 use crate::update::{MAX_LENGTH_FOR_PREFIX_PROXIMITY_DB, MAX_PROXIMITY_FOR_PREFIX_PROXIMITY_DB};
-use crate::{AscDesc as AscDescName, DocumentId, FieldId, Index, Member, Result};
+use crate::{
+    absolute_from_relative_position, relative_from_absolute_position, AscDesc as AscDescName,
+    DocumentId, FieldId, Index, Member, RelativePosition, Result,
+};
 
 mod asc_desc;
 mod attribute;
@@ -46,6 +52,8 @@ pub struct CriterionResult {
     filtered_candidates: Option<RoaringBitmap>,
     /// Candidates that comes from the current bucket of the initial criterion.
     initial_candidates: Option<InitialCandidates>,
+    /// The details of the buckets these candidates have been ranked in by the previous criteria.
+    score_details: Vec<ScoreDetails>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -186,6 +194,12 @@ pub struct CriteriaBuilder<'t> {
     index: &'t Index,
     words_fst: fst::Set<Cow<'t, [u8]>>,
     words_prefixes_fst: fst::Set<Cow<'t, [u8]>>,
+    /// The synonyms and the expansions of the synonym rules, read once per search.
+    synonyms: HashMap<Vec<String>, Vec<Vec<String>>>,
+    /// The only fields in which the words are matched, all the searchable fields if `None`.
+    restricted_fields_ids: Option<Vec<FieldId>>,
+    /// The documents containing the words, or the prefixes, in one of the restricted fields.
+    restricted_docids_cache: RefCell<HashMap<(String, bool), RoaringBitmap>>,
 }
 
 /// Return the docids for the following word pairs and proximities using [`Context::word_pair_proximity_docids`].
@@ -256,19 +270,23 @@ impl<'c> Context<'c> for CriteriaBuilder<'c> {
     }
 
     fn word_docids(&self, word: &str) -> heed::Result<Option<RoaringBitmap>> {
-        self.index.word_docids.get(self.rtxn, word)
+        let docids = self.index.word_docids.get(self.rtxn, word)?;
+        self.restrict_docids(docids, word, false)
     }
 
     fn exact_word_docids(&self, word: &str) -> heed::Result<Option<RoaringBitmap>> {
-        self.index.exact_word_docids.get(self.rtxn, word)
+        let docids = self.index.exact_word_docids.get(self.rtxn, word)?;
+        self.restrict_docids(docids, word, false)
     }
 
     fn word_prefix_docids(&self, word: &str) -> heed::Result<Option<RoaringBitmap>> {
-        self.index.word_prefix_docids.get(self.rtxn, word)
+        let docids = self.index.word_prefix_docids.get(self.rtxn, word)?;
+        self.restrict_docids(docids, word, true)
     }
 
     fn exact_word_prefix_docids(&self, word: &str) -> heed::Result<Option<RoaringBitmap>> {
-        self.index.exact_word_prefix_docids.get(self.rtxn, word)
+        let docids = self.index.exact_word_prefix_docids.get(self.rtxn, word)?;
+        self.restrict_docids(docids, word, true)
     }
 
     fn word_pair_proximity_docids(
@@ -277,7 +295,9 @@ impl<'c> Context<'c> for CriteriaBuilder<'c> {
         right: &str,
         proximity: u8,
     ) -> heed::Result<Option<RoaringBitmap>> {
-        self.index.word_pair_proximity_docids.get(self.rtxn, &(proximity, left, right))
+        let docids =
+            self.index.word_pair_proximity_docids.get(self.rtxn, &(proximity, left, right))?;
+        self.restrict_pair_docids(docids, (left, false), (right, false))
     }
 
     fn word_prefix_pair_proximity_docids(
@@ -286,7 +306,11 @@ impl<'c> Context<'c> for CriteriaBuilder<'c> {
         prefix: &str,
         proximity: u8,
     ) -> heed::Result<Option<RoaringBitmap>> {
-        self.index.word_prefix_pair_proximity_docids.get(self.rtxn, &(proximity, left, prefix))
+        let docids = self
+            .index
+            .word_prefix_pair_proximity_docids
+            .get(self.rtxn, &(proximity, left, prefix))?;
+        self.restrict_pair_docids(docids, (left, false), (prefix, true))
     }
     fn prefix_word_pair_proximity_docids(
         &self,
@@ -294,7 +318,11 @@ impl<'c> Context<'c> for CriteriaBuilder<'c> {
         right: &str,
         proximity: u8,
     ) -> heed::Result<Option<RoaringBitmap>> {
-        self.index.prefix_word_pair_proximity_docids.get(self.rtxn, &(proximity, prefix, right))
+        let docids = self
+            .index
+            .prefix_word_pair_proximity_docids
+            .get(self.rtxn, &(proximity, prefix, right))?;
+        self.restrict_pair_docids(docids, (prefix, true), (right, false))
     }
 
     fn words_fst<'t>(&self) -> &'t fst::Set<Cow<[u8]>> {
@@ -311,8 +339,16 @@ impl<'c> Context<'c> for CriteriaBuilder<'c> {
     ) -> heed::Result<HashMap<String, RoaringBitmap>> {
         let mut words_positions = HashMap::new();
         for result in self.index.docid_word_positions.prefix_iter(self.rtxn, &(docid, ""))? {
-            let ((_, word), positions) = result?;
-            words_positions.insert(word.to_string(), positions);
+            let ((_, word), mut positions) = result?;
+            if let Some(fields_ids) = &self.restricted_fields_ids {
+                positions = positions
+                    .into_iter()
+                    .filter(|&pos| fields_ids.contains(&relative_from_absolute_position(pos).0))
+                    .collect();
+            }
+            if !positions.is_empty() {
+                words_positions.insert(word.to_string(), positions);
+            }
         }
         Ok(words_positions)
     }
@@ -335,14 +371,25 @@ impl<'c> Context<'c> for CriteriaBuilder<'c> {
             false => self.index.word_position_docids,
         };
 
-        Ok(Box::new(db.range(self.rtxn, &range)?))
+        let iter = db.range(self.rtxn, &range)?;
+        match self.restricted_fields_ids.clone() {
+            Some(fields_ids) => Ok(Box::new(iter.filter(move |result| match result {
+                Ok(((_, pos), _)) => fields_ids.contains(&relative_from_absolute_position(*pos).0),
+                Err(_) => true,
+            }))),
+            None => Ok(Box::new(iter)),
+        }
     }
 
     fn synonyms(&self, word: &str) -> heed::Result<Option<Vec<Vec<String>>>> {
-        self.index.words_synonyms(self.rtxn, &[word])
+        Ok(self.synonyms.get([word.to_owned()].as_slice()).cloned())
     }
 
     fn searchable_fields_ids(&self) -> Result<Vec<FieldId>> {
+        if let Some(fields_ids) = &self.restricted_fields_ids {
+            return Ok(fields_ids.clone());
+        }
+
         match self.index.searchable_fields_ids(self.rtxn)? {
             Some(searchable_fields_ids) => Ok(searchable_fields_ids),
             None => Ok(self.index.fields_ids_map(self.rtxn)?.ids().collect()),
@@ -359,6 +406,12 @@ impl<'c> Context<'c> for CriteriaBuilder<'c> {
     }
 
     fn word_position_docids(&self, word: &str, pos: u32) -> heed::Result<Option<RoaringBitmap>> {
+        if let Some(fields_ids) = &self.restricted_fields_ids {
+            if !fields_ids.contains(&relative_from_absolute_position(pos).0) {
+                return Ok(None);
+            }
+        }
+
         let key = (word, pos);
         self.index.word_position_docids.get(self.rtxn, &key)
     }
@@ -368,7 +421,79 @@ impl<'t> CriteriaBuilder<'t> {
     pub fn new(rtxn: &'t heed::RoTxn<'t>, index: &'t Index) -> Result<Self> {
         let words_fst = index.words_fst(rtxn)?;
         let words_prefixes_fst = index.words_prefixes_fst(rtxn)?;
-        Ok(Self { rtxn, index, words_fst, words_prefixes_fst })
+        let synonyms = index.all_synonyms(rtxn)?;
+        Ok(Self {
+            rtxn,
+            index,
+            words_fst,
+            words_prefixes_fst,
+            synonyms,
+            restricted_fields_ids: None,
+            restricted_docids_cache: RefCell::default(),
+        })
+    }
+
+    /// Only matches the words in the given fields, using the fields ids
+    /// encoded in the positions of the words.
+    pub fn restrict_searchable_fields(&mut self, fields_ids: Vec<FieldId>) {
+        self.restricted_fields_ids = Some(fields_ids);
+        self.restricted_docids_cache.get_mut().clear();
+    }
+
+    /// Keeps the documents containing the word, or the prefix, in one of the restricted fields.
+    fn restrict_docids(
+        &self,
+        docids: Option<RoaringBitmap>,
+        word: &str,
+        is_prefix: bool,
+    ) -> heed::Result<Option<RoaringBitmap>> {
+        let (fields_ids, mut docids) = match (&self.restricted_fields_ids, docids) {
+            (Some(fields_ids), Some(docids)) => (fields_ids, docids),
+            (_, docids) => return Ok(docids),
+        };
+
+        let key = (word.to_string(), is_prefix);
+        if let Some(fields_docids) = self.restricted_docids_cache.borrow().get(&key) {
+            docids &= fields_docids;
+            return Ok(Some(docids));
+        }
+
+        let db = match is_prefix {
+            true => self.index.word_prefix_position_docids,
+            false => self.index.word_position_docids,
+        };
+
+        let mut fields_docids = RoaringBitmap::new();
+        for &field_id in fields_ids {
+            let left = (word, absolute_from_relative_position(field_id, 0));
+            let right = (word, absolute_from_relative_position(field_id, RelativePosition::MAX));
+            for result in db.range(self.rtxn, &(left..=right))? {
+                // the keys of the longer words starting with this word can be in the range.
+                let ((key_word, _), positions_docids) = result?;
+                if key_word == word {
+                    fields_docids |= positions_docids;
+                }
+            }
+        }
+
+        docids &= &fields_docids;
+        self.restricted_docids_cache.borrow_mut().insert(key, fields_docids);
+        Ok(Some(docids))
+    }
+
+    /// Keeps the documents containing both words of the pair in the restricted fields.
+    ///
+    /// This is an approximation: the positions of the pair are not checked, so a document
+    /// is kept when the pair is close in another field and both words appear somewhere
+    /// in the restricted fields. Checking the positions of every pair would be too costly.
+    fn restrict_pair_docids(
+        &self,
+        docids: Option<RoaringBitmap>,
+        (left, left_is_prefix): (&str, bool),
+        (right, right_is_prefix): (&str, bool),
+    ) -> heed::Result<Option<RoaringBitmap>> {
+        let docids = self.restrict_docids(docids, left, left_is_prefix)?;
+        self.restrict_docids(docids, right, right_is_prefix)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -488,7 +613,7 @@ pub fn resolve_query_tree(
                 }
                 Ok(candidates)
             }
-            Phrase(words) => resolve_phrase(ctx, words),
+            Phrase(words, slop) => resolve_phrase(ctx, words, *slop),
             Or(_, ops) => {
                 let mut candidates = RoaringBitmap::new();
                 for op in ops {
@@ -504,7 +629,14 @@ pub fn resolve_query_tree(
     resolve_operation(ctx, query_tree, wdcache)
 }
 
-pub fn resolve_phrase(ctx: &dyn Context, phrase: &[Option<String>]) -> Result<RoaringBitmap> {
+/// Returns the documents containing the words of the phrase, with at most `slop` words
+/// between two consecutive words of the phrase. The slop is bounded by the maximum
+/// proximity stored in the word pair proximity database.
+pub fn resolve_phrase(
+    ctx: &dyn Context,
+    phrase: &[Option<String>],
+    slop: Slop,
+) -> Result<RoaringBitmap> {
     let mut candidates = RoaringBitmap::new();
     let mut first_iter = true;
     let winsize = phrase.len().min(3);
@@ -527,7 +659,7 @@ pub fn resolve_phrase(ctx: &dyn Context, phrase: &[Option<String>]) -> Result<Ro
                 .enumerate()
                 .filter_map(|(index, word)| word.as_ref().map(|word| (index, word)))
             {
-                if dist == 0 {
+                if dist == 0 && slop == 0 {
                     match ctx.word_pair_proximity_docids(s1, s2, 1)? {
                         Some(m) => bitmaps.push(m),
                         // If there are no document for this pair, there will be no
@@ -535,9 +667,12 @@ pub fn resolve_phrase(ctx: &dyn Context, phrase: &[Option<String>]) -> Result<Ro
                         None => return Ok(RoaringBitmap::new()),
                     }
                 } else {
+                    // each of the words between the pair can be separated by `slop` other words.
+                    let max_proximity =
+                        ((dist + 1) * (slop as usize + 1)).min(MAX_DISTANCE as usize - 1);
                     let mut bitmap = RoaringBitmap::new();
-                    for dist in 0..=dist {
-                        if let Some(m) = ctx.word_pair_proximity_docids(s1, s2, dist as u8 + 1)? {
+                    for proximity in 1..=max_proximity {
+                        if let Some(m) = ctx.word_pair_proximity_docids(s1, s2, proximity as u8)? {
                             bitmap |= m
                         }
                     }
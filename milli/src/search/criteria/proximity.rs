@@ -9,6 +9,7 @@ use super::{
     query_docids, query_pair_proximity_docids, resolve_phrase, resolve_query_tree, Context,
     Criterion, CriterionParameters, CriterionResult,
 };
+use crate::score_details::{Rank, ScoreDetails};
 use crate::search::criteria::InitialCandidates;
 use crate::search::query_tree::{maximum_proximity, Operation, Query, QueryKind};
 use crate::search::{build_dfa, CriterionImplementationStrategy, WordDerivationsCache};
@@ -30,6 +31,7 @@ pub struct Proximity<'t> {
     state: Option<(u8, Operation, RoaringBitmap)>,
     proximity: u8,
     initial_candidates: InitialCandidates,
+    score_details: Vec<ScoreDetails>,
     parent: Box<dyn Criterion + 't>,
     candidates_cache: Cache,
     plane_sweep_cache: Option<btree_map::IntoIter<u8, RoaringBitmap>>,
@@ -47,6 +49,7 @@ impl<'t> Proximity<'t> {
             state: None,
             proximity: 0,
             initial_candidates: InitialCandidates::Estimated(RoaringBitmap::new()),
+            score_details: Vec::new(),
             parent,
             candidates_cache: Cache::new(),
             plane_sweep_cache: None,
@@ -77,7 +80,7 @@ impl<'t> Criterion for Proximity<'t> {
                 {
                     self.state = None; // reset state
                 }
-                Some((_, query_tree, allowed_candidates)) => {
+                Some((max_prox, query_tree, allowed_candidates)) => {
                     let mut new_candidates = if matches!(
                         self.implementation_strategy,
                         CriterionImplementationStrategy::OnlyIterative
@@ -122,6 +125,13 @@ impl<'t> Criterion for Proximity<'t> {
 
                     new_candidates &= &*allowed_candidates;
                     *allowed_candidates -= &new_candidates;
+
+                    let mut score_details = self.score_details.clone();
+                    score_details.push(ScoreDetails::Proximity(Rank {
+                        rank: max_prox.saturating_sub(self.proximity) as u32 + 1,
+                        max_rank: *max_prox as u32 + 1,
+                    }));
+
                     self.proximity += 1;
 
                     return Ok(Some(CriterionResult {
@@ -129,6 +139,7 @@ impl<'t> Criterion for Proximity<'t> {
                         candidates: Some(new_candidates),
                         filtered_candidates: None,
                         initial_candidates: Some(self.initial_candidates.take()),
+                        score_details,
                     }));
                 }
                 None => match self.parent.next(params)? {
@@ -137,6 +148,7 @@ impl<'t> Criterion for Proximity<'t> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         let mut candidates = match candidates {
                             Some(candidates) => candidates,
@@ -161,18 +173,21 @@ impl<'t> Criterion for Proximity<'t> {
                         self.state = Some((maximum_proximity as u8, query_tree, candidates));
                         self.proximity = 0;
                         self.plane_sweep_cache = None;
+                        self.score_details = score_details;
                     }
                     Some(CriterionResult {
                         query_tree: None,
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         return Ok(Some(CriterionResult {
                             query_tree: None,
                             candidates,
                             filtered_candidates,
                             initial_candidates,
+                            score_details,
                         }));
                     }
                     None => return Ok(None),
@@ -200,8 +215,10 @@ fn resolve_candidates(
 
         let result = match query_tree {
             And(ops) => mdfs(ctx, ops, proximity, cache, wdcache)?,
-            Phrase(words) => {
-                if proximity == 0 {
+            // The phrase is scored by the smallest slop its words are found with,
+            // a phrase without slop can only be found with a proximity of zero.
+            Phrase(words, slop) => {
+                if proximity <= *slop {
                     let most_left = words
                         .iter()
                         .filter_map(|o| o.as_ref())
@@ -215,7 +232,13 @@ fn resolve_candidates(
                         .map(|w| Query { prefix: false, kind: QueryKind::exact(w.clone()) });
 
                     match (most_left, most_right) {
-                        (Some(l), Some(r)) => vec![(l, r, resolve_phrase(ctx, words)?)],
+                        (Some(l), Some(r)) => {
+                            let mut candidates = resolve_phrase(ctx, words, proximity)?;
+                            if proximity > 0 {
+                                candidates -= resolve_phrase(ctx, words, proximity - 1)?;
+                            }
+                            vec![(l, r, candidates)]
+                        }
                         _otherwise => Default::default(),
                     }
                 } else {
@@ -492,7 +515,7 @@ fn resolve_plane_sweep_candidates(
                 }
                 plane_sweep(groups_positions, false)?
             }
-            Phrase(words) => {
+            Phrase(words, slop) => {
                 let mut groups_positions = Vec::with_capacity(words.len());
 
                 // group stop_words together.
@@ -514,7 +537,7 @@ fn resolve_plane_sweep_candidates(
                     match subgroup.len() {
                         0 => {}
                         1 => groups_positions.push(subgroup.pop().unwrap()),
-                        _ => groups_positions.push(plane_sweep(subgroup, true)?),
+                        _ => groups_positions.push(plane_sweep(subgroup, *slop == 0)?),
                     }
                 }
                 match groups_positions.len() {
@@ -599,7 +622,7 @@ mod tests {
 
     use crate::documents::{DocumentsBatchBuilder, DocumentsBatchReader};
     use crate::index::tests::TempIndex;
-    use crate::{Criterion, CriterionImplementationStrategy, SearchResult};
+    use crate::{Criterion, CriterionImplementationStrategy, SearchResult, SynonymRule};
 
     fn documents_with_enough_different_words_for_prefixes(prefixes: &[&str]) -> Vec<crate::Object> {
         let mut documents = Vec::new();
@@ -663,7 +686,7 @@ mod tests {
 
         let rtxn = index.read_txn().unwrap();
 
-        let SearchResult { matching_words: _, candidates: _, documents_ids } = index
+        let SearchResult { matching_words: _, candidates: _, documents_ids, .. } = index
             .search(&rtxn)
             .query("zero c")
             .criterion_implementation_strategy(CriterionImplementationStrategy::OnlySetBased)
@@ -671,7 +694,7 @@ mod tests {
             .unwrap();
         insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 3, 4, 1, 5, 0]");
 
-        let SearchResult { matching_words: _, candidates: _, documents_ids } = index
+        let SearchResult { matching_words: _, candidates: _, documents_ids, .. } = index
             .search(&rtxn)
             .query("zero co")
             .criterion_implementation_strategy(CriterionImplementationStrategy::OnlySetBased)
@@ -679,7 +702,7 @@ mod tests {
             .unwrap();
         insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 3, 4, 1, 5, 0]");
 
-        let SearchResult { matching_words: _, candidates: _, documents_ids } = index
+        let SearchResult { matching_words: _, candidates: _, documents_ids, .. } = index
             .search(&rtxn)
             .query("zero con")
             .criterion_implementation_strategy(CriterionImplementationStrategy::OnlySetBased)
@@ -690,7 +713,7 @@ mod tests {
         // all of its word derivations
         insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 3, 4, 5]");
 
-        let SearchResult { matching_words: _, candidates: _, documents_ids } = index
+        let SearchResult { matching_words: _, candidates: _, documents_ids, .. } = index
             .search(&rtxn)
             .criterion_implementation_strategy(CriterionImplementationStrategy::OnlySetBased)
             .query("zero conf")
@@ -700,7 +723,7 @@ mod tests {
         // that contain `conf` exactly, and not as a prefix.
         insta::assert_snapshot!(format!("{documents_ids:?}"), @"[4, 5, 0, 1, 2, 3]");
 
-        let SearchResult { matching_words: _, candidates: _, documents_ids } = index
+        let SearchResult { matching_words: _, candidates: _, documents_ids, .. } = index
             .search(&rtxn)
             .criterion_implementation_strategy(CriterionImplementationStrategy::OnlySetBased)
             .query("zero config")
@@ -709,4 +732,80 @@ mod tests {
         // `config` is not a common prefix, so the normal methods are used
         insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 3, 1, 0, 4, 5]");
     }
+
+    #[test]
+    fn test_proximity_criterion_multi_word_synonyms() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_criteria(vec![Criterion::Words, Criterion::Proximity]);
+                settings.set_synonym_rules(vec![SynonymRule::Mutual(vec![
+                    S("nyc"),
+                    S("new york city"),
+                ])]);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "text": "new york city is a town with a lot of pizza" },
+                { "id": 1, "text": "new york city has the best pizza" },
+                { "id": 2, "text": "new york city pizza" },
+                { "id": 3, "text": "nyc pizza" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // The last word of the expansion is the one that must be close to the following word.
+        for strategy in [
+            CriterionImplementationStrategy::OnlySetBased,
+            CriterionImplementationStrategy::OnlyIterative,
+        ] {
+            let SearchResult { documents_ids, .. } = index
+                .search(&rtxn)
+                .query("nyc pizza")
+                .criterion_implementation_strategy(strategy)
+                .execute()
+                .unwrap();
+            assert_eq!(documents_ids, vec![2, 3, 1, 0]);
+        }
+    }
+
+    #[test]
+    fn test_proximity_criterion_phrase_slop() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_criteria(vec![Criterion::Words, Criterion::Proximity]);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "text": "the quick brown fox" },
+                { "id": 1, "text": "the quick fox" },
+                { "id": 2, "text": "quick is the red fox" },
+                { "id": 3, "text": "quick is a fox" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // The documents are ranked by the number of words found between the words of the phrase.
+        for strategy in [
+            CriterionImplementationStrategy::OnlySetBased,
+            CriterionImplementationStrategy::OnlyIterative,
+        ] {
+            let SearchResult { documents_ids, .. } = index
+                .search(&rtxn)
+                .query("\"quick fox\"~3")
+                .criterion_implementation_strategy(strategy)
+                .execute()
+                .unwrap();
+            assert_eq!(documents_ids, vec![1, 0, 3, 2]);
+        }
+    }
 }
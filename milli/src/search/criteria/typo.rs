@@ -9,6 +9,7 @@ use super::{
     query_docids, resolve_query_tree, Candidates, Context, Criterion, CriterionParameters,
     CriterionResult,
 };
+use crate::score_details::{self, ScoreDetails};
 use crate::search::criteria::{resolve_phrase, InitialCandidates};
 use crate::search::query_tree::{maximum_typo, Operation, Query, QueryKind};
 use crate::search::{word_derivations, WordDerivationsCache};
@@ -23,6 +24,7 @@ pub struct Typo<'t> {
     state: Option<(u8, Operation, Candidates)>,
     typos: u8,
     initial_candidates: Option<InitialCandidates>,
+    score_details: Vec<ScoreDetails>,
     parent: Box<dyn Criterion + 't>,
     candidates_cache: HashMap<(Operation, u8), RoaringBitmap>,
 }
@@ -34,6 +36,7 @@ impl<'t> Typo<'t> {
             state: None,
             typos: 0,
             initial_candidates: None,
+            score_details: Vec::new(),
             parent,
             candidates_cache: HashMap::new(),
         }
@@ -66,7 +69,7 @@ impl<'t> Criterion for Typo<'t> {
                 Some((_, _, Allowed(allowed_candidates))) if allowed_candidates.is_empty() => {
                     self.state = None; // reset state
                 }
-                Some((_, query_tree, candidates_authorization)) => {
+                Some((max_typos, query_tree, candidates_authorization)) => {
                     let fst = self.ctx.words_fst();
                     let new_query_tree = match self.typos {
                         typos if typos < MAX_TYPOS_PER_WORD => alterate_query_tree(
@@ -125,6 +128,12 @@ impl<'t> Criterion for Typo<'t> {
                         None => InitialCandidates::Estimated(candidates.clone()),
                     };
 
+                    let mut score_details = self.score_details.clone();
+                    score_details.push(ScoreDetails::Typo(score_details::Typo {
+                        typo_count: self.typos as u32,
+                        max_typo_count: *max_typos as u32,
+                    }));
+
                     self.typos += 1;
 
                     return Ok(Some(CriterionResult {
@@ -132,6 +141,7 @@ impl<'t> Criterion for Typo<'t> {
                         candidates: Some(candidates),
                         filtered_candidates: None,
                         initial_candidates: Some(initial_candidates),
+                        score_details,
                     }));
                 }
                 None => match self.parent.next(params)? {
@@ -140,6 +150,7 @@ impl<'t> Criterion for Typo<'t> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         self.initial_candidates =
                             match (self.initial_candidates.take(), initial_candidates) {
@@ -157,18 +168,21 @@ impl<'t> Criterion for Typo<'t> {
                         let maximum_typos = maximum_typo(&query_tree) as u8;
                         self.state = Some((maximum_typos, query_tree, candidates));
                         self.typos = 0;
+                        self.score_details = score_details;
                     }
                     Some(CriterionResult {
                         query_tree: None,
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         return Ok(Some(CriterionResult {
                             query_tree: None,
                             candidates,
                             filtered_candidates,
                             initial_candidates,
+                            score_details,
                         }));
                     }
                     None => return Ok(None),
@@ -200,7 +214,7 @@ fn alterate_query_tree(
                 ops.iter_mut().try_for_each(|op| recurse(words_fst, op, number_typos, wdcache))
             }
             // Because Phrases don't allow typos, no alteration can be done.
-            Phrase(_words) => Ok(()),
+            Phrase(..) => Ok(()),
             Operation::Query(q) => {
                 if let QueryKind::Tolerant { typo, word } = &q.kind {
                     // if no typo is allowed we don't call word_derivations function,
@@ -257,7 +271,7 @@ fn resolve_candidates(
 
         match query_tree {
             And(ops) => mdfs(ctx, ops, number_typos, cache, wdcache),
-            Phrase(words) => resolve_phrase(ctx, words),
+            Phrase(words, slop) => resolve_phrase(ctx, words, *slop),
             Or(_, ops) => {
                 let mut candidates = RoaringBitmap::new();
                 for op in ops {
@@ -356,7 +370,7 @@ mod test {
 
         let result = display_criteria(criteria, criterion_parameters);
         insta::assert_snapshot!(result, @r###"
-        CriterionResult { query_tree: None, candidates: None, filtered_candidates: None, initial_candidates: None }
+        CriterionResult { query_tree: None, candidates: None, filtered_candidates: None, initial_candidates: None, score_details: [] }
 
         "###);
     }
@@ -399,7 +413,7 @@ mod test {
             Exact { word: "split" }
             Exact { word: "this" }
             Exact { word: "world" }
-        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)) }
+        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)), score_details: [Typo(Typo { typo_count: 0, max_typo_count: 1 })] }
 
         CriterionResult { query_tree: Some(OR
           AND
@@ -408,7 +422,7 @@ mod test {
             OR
               Exact { word: "word" }
               Exact { word: "world" }
-        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)) }
+        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)), score_details: [Typo(Typo { typo_count: 1, max_typo_count: 1 })] }
 
         "###);
     }
@@ -434,7 +448,7 @@ mod test {
 
         let result = display_criteria(criteria, criterion_parameters);
         insta::assert_snapshot!(result, @r###"
-        CriterionResult { query_tree: None, candidates: None, filtered_candidates: Some(RoaringBitmap<8000 values between 986424 and 4294786076>), initial_candidates: None }
+        CriterionResult { query_tree: None, candidates: None, filtered_candidates: Some(RoaringBitmap<8000 values between 986424 and 4294786076>), initial_candidates: None, score_details: [] }
 
         "###);
     }
@@ -482,7 +496,7 @@ mod test {
             Exact { word: "split" }
             Exact { word: "this" }
             Exact { word: "world" }
-        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)) }
+        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)), score_details: [Typo(Typo { typo_count: 0, max_typo_count: 1 })] }
 
         CriterionResult { query_tree: Some(OR
           AND
@@ -491,7 +505,7 @@ mod test {
             OR
               Exact { word: "word" }
               Exact { word: "world" }
-        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)) }
+        ), candidates: Some(RoaringBitmap<[]>), filtered_candidates: None, initial_candidates: Some(Estimated(RoaringBitmap<[]>)), score_details: [Typo(Typo { typo_count: 1, max_typo_count: 1 })] }
 
         "###);
     }
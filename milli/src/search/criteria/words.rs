@@ -2,6 +2,7 @@ use log::debug;
 use roaring::RoaringBitmap;
 
 use super::{resolve_query_tree, Context, Criterion, CriterionParameters, CriterionResult};
+use crate::score_details::{Rank, ScoreDetails};
 use crate::search::criteria::InitialCandidates;
 use crate::search::query_tree::Operation;
 use crate::Result;
@@ -9,9 +10,12 @@ use crate::Result;
 pub struct Words<'t> {
     ctx: &'t dyn Context<'t>,
     query_trees: Vec<Operation>,
+    /// The number of query trees the last parent bucket has been exploded into.
+    max_rank: u32,
     candidates: Option<RoaringBitmap>,
     initial_candidates: Option<InitialCandidates>,
     filtered_candidates: Option<RoaringBitmap>,
+    score_details: Vec<ScoreDetails>,
     parent: Box<dyn Criterion + 't>,
 }
 
@@ -20,10 +24,12 @@ impl<'t> Words<'t> {
         Words {
             ctx,
             query_trees: Vec::default(),
+            max_rank: 0,
             candidates: None,
             initial_candidates: None,
             parent,
             filtered_candidates: None,
+            score_details: Vec::new(),
         }
     }
 }
@@ -54,11 +60,20 @@ impl<'t> Criterion for Words<'t> {
 
                     let initial_candidates = self.initial_candidates.clone();
 
+                    // The query trees are popped from the one matching the most words,
+                    // the remaining ones are the worse buckets.
+                    let mut score_details = self.score_details.clone();
+                    score_details.push(ScoreDetails::Words(Rank {
+                        rank: self.query_trees.len() as u32 + 1,
+                        max_rank: self.max_rank,
+                    }));
+
                     return Ok(Some(CriterionResult {
                         query_tree: Some(query_tree),
                         candidates,
                         filtered_candidates: self.filtered_candidates.clone(),
                         initial_candidates,
+                        score_details,
                     }));
                 }
                 None => match self.parent.next(params)? {
@@ -67,10 +82,13 @@ impl<'t> Criterion for Words<'t> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         self.query_trees = explode_query_tree(query_tree);
+                        self.max_rank = self.query_trees.len() as u32;
                         self.candidates = candidates;
                         self.filtered_candidates = filtered_candidates;
+                        self.score_details = score_details;
 
                         self.initial_candidates =
                             match (self.initial_candidates.take(), initial_candidates) {
@@ -83,12 +101,14 @@ impl<'t> Criterion for Words<'t> {
                         candidates,
                         filtered_candidates,
                         initial_candidates,
+                        score_details,
                     }) => {
                         return Ok(Some(CriterionResult {
                             query_tree: None,
                             candidates,
                             filtered_candidates,
                             initial_candidates,
+                            score_details,
                         }));
                     }
                     None => return Ok(None),
@@ -95,6 +95,20 @@ impl<'a> FacetDistinctIter<'a> {
         Ok(())
     }
 
+    fn distinct_bool(&mut self, id: DocumentId) -> Result<()> {
+        for boolean in [false, true] {
+            let facet_docids =
+                self.index.bool_faceted_documents_ids(self.txn, self.distinct, boolean)?;
+            if facet_docids.contains(id) {
+                self.excluded |= facet_docids;
+            }
+        }
+
+        self.excluded.remove(id);
+
+        Ok(())
+    }
+
     /// Performs the next iteration of the facet distinct. This is a convenience method that is
     /// called by the Iterator::next implementation that transposes the result. It makes error
     /// handling easier.
@@ -105,9 +119,10 @@ impl<'a> FacetDistinctIter<'a> {
         let mut candidates_iter = self.candidates.iter().skip(self.iter_offset);
         match candidates_iter.next() {
             Some(id) => {
-                // We distinct the document id on its facet strings and facet numbers.
+                // We distinct the document id on its facet strings, numbers and booleans.
                 self.distinct_string(id)?;
                 self.distinct_number(id)?;
+                self.distinct_bool(id)?;
 
                 // The first document of each iteration is kept, since the next call to
                 // `difference_with` will filter out all the documents for that facet value. By
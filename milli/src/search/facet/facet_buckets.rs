@@ -0,0 +1,455 @@
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::{ControlFlow, RangeBounds};
+use std::{fmt, mem};
+
+use heed::types::ByteSlice;
+use heed::BytesDecode;
+use roaring::RoaringBitmap;
+
+use super::{facet_distribution_iter, get_first_facet_value, get_highest_level};
+use crate::error::UserError;
+use crate::heed_codec::facet::{
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetF64Codec,
+    OrderedF64Codec,
+};
+use crate::heed_codec::ByteSliceRefCodec;
+use crate::{FieldId, Index, Result};
+
+/// The maximum number of buckets a fixed interval can generate.
+pub const MAX_FACET_BUCKETS: usize = 1000;
+
+/// Threshold on the number of candidates that will make
+/// the system to choose between one algorithm or another.
+const CANDIDATES_THRESHOLD: u64 = 3000;
+
+/// A range of numeric facet values, including `from` and excluding `to`,
+/// `None` meaning that the range is unbounded on this side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FacetRange {
+    pub from: Option<f64>,
+    pub to: Option<f64>,
+}
+
+impl FacetRange {
+    pub fn new(from: Option<f64>, to: Option<f64>) -> FacetRange {
+        FacetRange { from, to }
+    }
+
+    fn bounds(&self) -> (Bound<f64>, Bound<f64>) {
+        (self.from.map_or(Unbounded, Included), self.to.map_or(Unbounded, Excluded))
+    }
+
+    fn contains(&self, value: f64) -> bool {
+        self.bounds().contains(&value)
+    }
+
+    /// Returns `true` if the range contains some but not all of the values
+    /// of the `left..right` interval, `None` meaning that it is unbounded on the right.
+    fn straddles(&self, left: f64, right: Option<f64>) -> bool {
+        let contains_all = self.from.map_or(true, |from| from <= left)
+            && self.to.map_or(true, |to| right.map_or(false, |right| right <= to));
+        let is_disjoint = self.to.map_or(false, |to| to <= left)
+            || self.from.map_or(false, |from| right.map_or(false, |right| right <= from));
+        !contains_all && !is_disjoint
+    }
+}
+
+/// A range of numeric facet values along with the number of candidates in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FacetBucket {
+    pub range: FacetRange,
+    /// The number of candidates with at least one value in the range.
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Buckets {
+    /// Consecutive buckets of the same width aligned on the multiples of the width,
+    /// from the one containing the smallest value to the one containing the biggest value.
+    Interval(f64),
+    Ranges(Vec<FacetRange>),
+}
+
+/// Counts the candidates whose numeric facet values fall in each bucket.
+///
+/// The documents of each bucket are retrieved by exploring the facet levels of
+/// the `facet_id_f64_docids` database which is much cheaper than evaluating a filter.
+pub struct FacetBuckets<'a> {
+    facet: String,
+    candidates: Option<RoaringBitmap>,
+    buckets: Buckets,
+    rtxn: &'a heed::RoTxn<'a>,
+    index: &'a Index,
+}
+
+impl<'a> FacetBuckets<'a> {
+    /// Creates the buckets of the given facet, by default the facet is split by
+    /// the given ranges, use [`FacetBuckets::interval`] to generate fixed-width buckets.
+    pub fn new(
+        rtxn: &'a heed::RoTxn,
+        index: &'a Index,
+        facet: impl Into<String>,
+        ranges: Vec<FacetRange>,
+    ) -> FacetBuckets<'a> {
+        FacetBuckets {
+            facet: facet.into(),
+            candidates: None,
+            buckets: Buckets::Ranges(ranges),
+            rtxn,
+            index,
+        }
+    }
+
+    /// Replaces the ranges by consecutive buckets of the given width.
+    pub fn interval(&mut self, width: f64) -> &mut Self {
+        self.buckets = Buckets::Interval(width);
+        self
+    }
+
+    pub fn candidates(&mut self, candidates: RoaringBitmap) -> &mut Self {
+        self.candidates = Some(candidates);
+        self
+    }
+
+    fn facet_db(
+        &self,
+    ) -> heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec> {
+        self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>()
+    }
+
+    /// Returns the smallest and the biggest facet values of the candidates.
+    fn min_max(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+    ) -> heed::Result<Option<(f64, f64)>> {
+        let mut min = None;
+        facet_distribution_iter::iterate_over_facet_distribution(
+            self.rtxn,
+            self.facet_db(),
+            field_id,
+            candidates,
+            |facet_key, _, _| {
+                min = OrderedF64Codec::bytes_decode(facet_key);
+                Ok(ControlFlow::Break(()))
+            },
+        )?;
+
+        let mut max = None;
+        facet_distribution_iter::rev_iterate_over_facet_distribution(
+            self.rtxn,
+            self.facet_db(),
+            field_id,
+            candidates,
+            |facet_key, _, _| {
+                max = OrderedF64Codec::bytes_decode(facet_key);
+                Ok(ControlFlow::Break(()))
+            },
+        )?;
+
+        Ok(min.zip(max))
+    }
+
+    /// Generates the ranges of the fixed-width buckets covering the values of the candidates.
+    fn interval_ranges(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+        width: f64,
+    ) -> Result<Vec<FacetRange>> {
+        if !width.is_finite() || width <= 0.0 {
+            return Err(UserError::InvalidFacetBucketsInterval(width).into());
+        }
+
+        let (min, max) = match self.min_max(field_id, candidates)? {
+            Some(min_max) => min_max,
+            None => return Ok(Vec::new()),
+        };
+
+        let first = (min / width).floor();
+        let last = (max / width).floor();
+        if last - first >= MAX_FACET_BUCKETS as f64 {
+            return Err(UserError::InvalidFacetBucketsInterval(width).into());
+        }
+
+        let ranges = (0..=(last - first) as usize)
+            .map(|i| {
+                // The bounds are computed the same way so that adjacent buckets share them.
+                let from = (first + i as f64) * width;
+                let to = (first + i as f64 + 1.0) * width;
+                FacetRange::new(Some(from), Some(to))
+            })
+            .collect();
+
+        Ok(ranges)
+    }
+
+    /// There is a small amount of candidates, we read the facet values of each one
+    /// of them and count the candidates having at least one value in each range.
+    fn counts_from_documents(
+        &self,
+        field_id: FieldId,
+        ranges: &[FacetRange],
+        candidates: &RoaringBitmap,
+    ) -> heed::Result<Vec<u64>> {
+        let mut counts = vec![0; ranges.len()];
+        let mut in_ranges = vec![false; ranges.len()];
+        let mut key_buffer: Vec<_> = field_id.to_be_bytes().to_vec();
+
+        let db = self.index.field_id_docid_facet_f64s;
+        for docid in candidates {
+            key_buffer.truncate(mem::size_of::<FieldId>());
+            key_buffer.extend_from_slice(&docid.to_be_bytes());
+            let iter = db
+                .remap_key_type::<ByteSlice>()
+                .prefix_iter(self.rtxn, &key_buffer)?
+                .remap_key_type::<FieldDocIdFacetF64Codec>();
+
+            in_ranges.iter_mut().for_each(|in_range| *in_range = false);
+            for result in iter {
+                let ((_, _, value), ()) = result?;
+                for (range, in_range) in ranges.iter().zip(&mut in_ranges) {
+                    *in_range |= range.contains(value);
+                }
+            }
+
+            for (count, in_range) in counts.iter_mut().zip(&in_ranges) {
+                *count += *in_range as u64;
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// There is too many candidates to read their values one by one, we explore the facet
+    /// levels once, restricted to the candidates, and add the candidates of each group to the
+    /// buckets containing all of its values. The groups straddling a bucket bound are opened.
+    fn counts_from_facet_levels(
+        &self,
+        field_id: FieldId,
+        ranges: &[FacetRange],
+        candidates: &RoaringBitmap,
+    ) -> heed::Result<Vec<u64>> {
+        let db = self.facet_db();
+        let mut buckets_docids = vec![RoaringBitmap::new(); ranges.len()];
+        let highest_level = get_highest_level(self.rtxn, db, field_id)?;
+        if let Some(first_bound) =
+            get_first_facet_value::<ByteSliceRefCodec>(self.rtxn, db, field_id)?
+        {
+            let starting_key =
+                FacetGroupKey { field_id, level: highest_level, left_bound: first_bound };
+            self.fill_buckets(
+                starting_key,
+                usize::MAX,
+                None,
+                candidates,
+                ranges,
+                &mut buckets_docids,
+            )?;
+        }
+
+        Ok(buckets_docids.iter().map(RoaringBitmap::len).collect())
+    }
+
+    /// Adds the candidates of the groups starting at the given key to the buckets containing
+    /// their values. The values of the last group are below `right_bound`, the bound of the
+    /// group that follows the parent group, `None` meaning that there is no such group.
+    fn fill_buckets(
+        &self,
+        starting_key: FacetGroupKey<&'a [u8]>,
+        group_size: usize,
+        right_bound: Option<f64>,
+        candidates: &RoaringBitmap,
+        ranges: &[FacetRange],
+        buckets_docids: &mut [RoaringBitmap],
+    ) -> heed::Result<()> {
+        let FacetGroupKey { field_id, level, .. } = starting_key;
+
+        let mut groups = Vec::new();
+        for result in self.facet_db().range(self.rtxn, &(starting_key..))?.take(group_size) {
+            let (key, value) = result?;
+            // The range is unbounded on the right and the group size for the highest level is MAX,
+            // so we need to check that we are not iterating over the next field id
+            if key.field_id != field_id {
+                break;
+            }
+            groups.push((key.left_bound, value));
+        }
+
+        for (i, (left_bound, value)) in groups.iter().enumerate() {
+            let docids = &value.bitmap & candidates;
+            if docids.is_empty() {
+                continue;
+            }
+
+            // The values of the group are in the `left..right` interval.
+            let left = OrderedF64Codec::bytes_decode(left_bound).unwrap();
+            let right = match groups.get(i + 1) {
+                Some((next_bound, _)) => OrderedF64Codec::bytes_decode(next_bound),
+                None => right_bound,
+            };
+
+            let straddles = ranges.iter().any(|range| range.straddles(left, right));
+            if level == 0 || !straddles {
+                for (range, bucket_docids) in ranges.iter().zip(buckets_docids.iter_mut()) {
+                    if range.contains(left) {
+                        *bucket_docids |= &docids;
+                    }
+                }
+            } else {
+                let key = FacetGroupKey { field_id, level: level - 1, left_bound };
+                self.fill_buckets(
+                    key,
+                    value.size as usize,
+                    right,
+                    &docids,
+                    ranges,
+                    buckets_docids,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the buckets in the order of the given ranges or in ascending order for
+    /// the fixed-width buckets, the buckets without any candidates are also returned.
+    pub fn execute(&self) -> Result<Vec<FacetBucket>> {
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+        if !crate::is_faceted(&self.facet, &filterable_fields) {
+            return Err(UserError::InvalidFacetBuckets {
+                invalid_facets_name: std::iter::once(self.facet.clone()).collect(),
+                valid_facets_name: filterable_fields.into_iter().collect(),
+            }
+            .into());
+        }
+
+        let candidates = match self.candidates {
+            Some(ref candidates) => candidates.clone(),
+            None => self.index.documents_ids(self.rtxn)?,
+        };
+
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let field_id = fields_ids_map.id(&self.facet);
+
+        let ranges = match (&self.buckets, field_id) {
+            (Buckets::Ranges(ranges), _) => ranges.clone(),
+            (Buckets::Interval(width), Some(field_id)) => {
+                self.interval_ranges(field_id, &candidates, *width)?
+            }
+            (Buckets::Interval(_), None) => Vec::new(),
+        };
+
+        let counts = match field_id {
+            Some(field_id) if candidates.len() <= CANDIDATES_THRESHOLD => {
+                self.counts_from_documents(field_id, &ranges, &candidates)?
+            }
+            Some(field_id) => self.counts_from_facet_levels(field_id, &ranges, &candidates)?,
+            None => vec![0; ranges.len()],
+        };
+
+        let buckets = ranges
+            .into_iter()
+            .zip(counts)
+            .map(|(range, count)| FacetBucket { range, count })
+            .collect();
+
+        Ok(buckets)
+    }
+}
+
+impl fmt::Debug for FacetBuckets<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let FacetBuckets { facet, candidates, buckets, rtxn: _, index: _ } = self;
+
+        f.debug_struct("FacetBuckets")
+            .field("facet", facet)
+            .field("candidates", candidates)
+            .field("buckets", buckets)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::{FacetBuckets, FacetRange};
+    use crate::index::tests::TempIndex;
+
+    fn counts(buckets: &FacetBuckets) -> Vec<(Option<f64>, Option<f64>, u64)> {
+        let buckets = buckets.execute().unwrap();
+        buckets.into_iter().map(|b| (b.range.from, b.range.to, b.count)).collect()
+    }
+
+    #[test]
+    fn facet_buckets() {
+        let mut index = TempIndex::new_with_map_size(4096 * 10_000);
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("price") }))
+            .unwrap();
+
+        // More documents than the threshold to count the buckets from the facet levels
+        // without candidates and from the values of the documents with the candidates.
+        let documents = (0..4000)
+            .map(|i| serde_json::json!({ "price": i as f64 / 40.0 }).as_object().unwrap().clone())
+            .collect();
+        let documents = crate::documents::documents_batch_reader_from_objects(documents);
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let ranges = vec![
+            FacetRange::new(None, Some(10.0)),
+            FacetRange::new(Some(10.0), Some(50.0)),
+            FacetRange::new(Some(50.0), None),
+            FacetRange::new(Some(50.0), Some(10.0)),
+        ];
+        let mut buckets = FacetBuckets::new(&txn, &index, "price", ranges);
+        assert_eq!(
+            counts(&buckets),
+            vec![
+                (None, Some(10.0), 400),
+                (Some(10.0), Some(50.0), 1600),
+                (Some(50.0), None, 2000),
+                (Some(50.0), Some(10.0), 0),
+            ]
+        );
+
+        buckets.candidates((0..150).collect());
+        assert_eq!(
+            counts(&buckets),
+            vec![
+                (None, Some(10.0), 150),
+                (Some(10.0), Some(50.0), 0),
+                (Some(50.0), None, 0),
+                (Some(50.0), Some(10.0), 0),
+            ]
+        );
+
+        buckets.candidates((50..420).collect()).interval(4.0);
+        assert_eq!(
+            counts(&buckets),
+            vec![
+                (Some(0.0), Some(4.0), 110),
+                (Some(4.0), Some(8.0), 160),
+                (Some(8.0), Some(12.0), 100),
+            ]
+        );
+
+        buckets.interval(0.001);
+        assert!(buckets.execute().is_err());
+
+        buckets.candidates(Default::default()).interval(1.0);
+        assert!(counts(&buckets).is_empty());
+
+        let error = FacetBuckets::new(&txn, &index, "size", Vec::new()).execute().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid facet buckets, attribute `size` is not filterable. The available filterable attribute is `price`."
+        );
+    }
+}
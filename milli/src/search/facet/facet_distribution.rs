@@ -4,6 +4,8 @@ use std::{fmt, mem};
 
 use heed::types::ByteSlice;
 use heed::BytesDecode;
+use indexmap::IndexMap;
+use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
 
 use crate::error::UserError;
@@ -14,7 +16,7 @@ use crate::heed_codec::facet::{
 };
 use crate::heed_codec::{ByteSliceRefCodec, StrRefCodec};
 use crate::search::facet::facet_distribution_iter;
-use crate::{FieldId, Index, Result};
+use crate::{DocumentId, FieldId, Index, Result};
 
 /// The default number of values by facets that will
 /// be fetched from the key-value store.
@@ -24,10 +26,42 @@ pub const DEFAULT_VALUES_PER_FACET: usize = 100;
 /// the system to choose between one algorithm or another.
 const CANDIDATES_THRESHOLD: u64 = 3000;
 
+/// The order in which the values of a facet are returned. It also determines
+/// which values are returned when a facet has more than `max_values_per_facet` values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBy {
+    /// The values are returned in lexicographic order, the default.
+    /// The numbers come first, in numeric order, followed by the strings.
+    #[default]
+    Lexicographic,
+    /// The values are returned in reverse lexicographic order.
+    /// The strings come first, in reverse order, followed by the numbers from the highest.
+    ReverseLexicographic,
+    /// The values contained by the most documents are returned first,
+    /// the values with the same count are in lexicographic order.
+    Count,
+}
+
+/// The number and string values of a facet, the numbers are ordered numerically
+/// and come before the strings, which are ordered lexicographically.
+#[derive(Default)]
+struct ValuesDistribution {
+    numbers: BTreeMap<OrderedFloat<f64>, u64>,
+    strings: BTreeMap<String, u64>,
+}
+
+impl ValuesDistribution {
+    fn into_ordered_values(self) -> Vec<(String, u64)> {
+        let numbers = self.numbers.into_iter().map(|(number, count)| (number.0.to_string(), count));
+        numbers.chain(self.strings).collect()
+    }
+}
+
 pub struct FacetDistribution<'a> {
     facets: Option<HashSet<String>>,
     candidates: Option<RoaringBitmap>,
     max_values_per_facet: usize,
+    order_by: OrderBy,
     rtxn: &'a heed::RoTxn<'a>,
     index: &'a Index,
 }
@@ -38,6 +72,7 @@ impl<'a> FacetDistribution<'a> {
             facets: None,
             candidates: None,
             max_values_per_facet: DEFAULT_VALUES_PER_FACET,
+            order_by: OrderBy::default(),
             rtxn,
             index,
         }
@@ -58,6 +93,21 @@ impl<'a> FacetDistribution<'a> {
         self
     }
 
+    pub fn order_by(&mut self, order_by: OrderBy) -> &mut Self {
+        self.order_by = order_by;
+        self
+    }
+
+    /// The maximum number of values to fetch while iterating over the facet values of the
+    /// documents. Only the lexicographic order can stop early, the other orders need to see
+    /// every value before deciding which ones to return.
+    fn max_values_from_documents(&self) -> usize {
+        match self.order_by {
+            OrderBy::Lexicographic => self.max_values_per_facet,
+            OrderBy::ReverseLexicographic | OrderBy::Count => usize::MAX,
+        }
+    }
+
     /// There is a small amount of candidates OR we ask for facet string values so we
     /// decide to iterate over the facet values of each one of them, one by one.
     fn facet_distribution_from_documents(
@@ -65,13 +115,12 @@ impl<'a> FacetDistribution<'a> {
         field_id: FieldId,
         facet_type: FacetType,
         candidates: &RoaringBitmap,
-        distribution: &mut BTreeMap<String, u64>,
+        distribution: &mut ValuesDistribution,
     ) -> heed::Result<()> {
         match facet_type {
             FacetType::Number => {
                 let mut key_buffer: Vec<_> = field_id.to_be_bytes().to_vec();
 
-                let distribution_prelength = distribution.len();
                 let db = self.index.field_id_docid_facet_f64s;
                 for docid in candidates.into_iter() {
                     key_buffer.truncate(mem::size_of::<FieldId>());
@@ -83,10 +132,9 @@ impl<'a> FacetDistribution<'a> {
 
                     for result in iter {
                         let ((_, _, value), ()) = result?;
-                        *distribution.entry(value.to_string()).or_insert(0) += 1;
+                        *distribution.numbers.entry(OrderedFloat(value)).or_insert(0) += 1;
 
-                        if distribution.len() - distribution_prelength == self.max_values_per_facet
-                        {
+                        if distribution.numbers.len() == self.max_values_from_documents() {
                             break;
                         }
                     }
@@ -112,7 +160,7 @@ impl<'a> FacetDistribution<'a> {
                             .or_insert_with(|| (original_value, 0));
                         *count += 1;
 
-                        if normalized_distribution.len() == self.max_values_per_facet {
+                        if normalized_distribution.len() == self.max_values_from_documents() {
                             break 'outer;
                         }
                     }
@@ -121,23 +169,42 @@ impl<'a> FacetDistribution<'a> {
                 let iter = normalized_distribution
                     .into_iter()
                     .map(|(_normalized, (original, count))| (original.to_string(), count));
-                distribution.extend(iter);
+                distribution.strings.extend(iter);
             }
+            // The boolean values are counted apart, see `facet_booleans_distribution`.
+            FacetType::Boolean => (),
         }
 
         Ok(())
     }
 
+    /// Returns the number of candidates of both values of a boolean facet.
+    fn facet_booleans_distribution(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+    ) -> heed::Result<BTreeMap<bool, u64>> {
+        let mut distribution = BTreeMap::new();
+        for boolean in [false, true] {
+            let docids = self.index.bool_faceted_documents_ids(self.rtxn, field_id, boolean)?;
+            let count = docids.intersection_len(candidates);
+            if count != 0 {
+                distribution.insert(boolean, count);
+            }
+        }
+
+        Ok(distribution)
+    }
+
     /// There is too much documents, we use the facet levels to move throught
     /// the facet values, to find the candidates and values associated.
     fn facet_numbers_distribution_from_facet_levels(
         &self,
         field_id: FieldId,
         candidates: &RoaringBitmap,
-        distribution: &mut BTreeMap<String, u64>,
+        distribution: &mut ValuesDistribution,
     ) -> heed::Result<()> {
-        facet_distribution_iter::iterate_over_facet_distribution(
-            self.rtxn,
+        self.iterate_over_facet_distribution(
             self.index
                 .facet_id_f64_docids
                 .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
@@ -145,8 +212,8 @@ impl<'a> FacetDistribution<'a> {
             candidates,
             |facet_key, nbr_docids, _| {
                 let facet_key = OrderedF64Codec::bytes_decode(facet_key).unwrap();
-                distribution.insert(facet_key.to_string(), nbr_docids);
-                if distribution.len() == self.max_values_per_facet {
+                distribution.numbers.insert(OrderedFloat(facet_key), nbr_docids);
+                if distribution.numbers.len() == self.max_values_per_facet {
                     Ok(ControlFlow::Break(()))
                 } else {
                     Ok(ControlFlow::Continue(()))
@@ -159,10 +226,9 @@ impl<'a> FacetDistribution<'a> {
         &self,
         field_id: FieldId,
         candidates: &RoaringBitmap,
-        distribution: &mut BTreeMap<String, u64>,
+        distribution: &mut ValuesDistribution,
     ) -> heed::Result<()> {
-        facet_distribution_iter::iterate_over_facet_distribution(
-            self.rtxn,
+        self.iterate_over_facet_distribution(
             self.index
                 .facet_id_string_docids
                 .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
@@ -179,8 +245,8 @@ impl<'a> FacetDistribution<'a> {
                     .unwrap()
                     .to_owned();
 
-                distribution.insert(original_string, nbr_docids);
-                if distribution.len() == self.max_values_per_facet {
+                distribution.strings.insert(original_string, nbr_docids);
+                if distribution.strings.len() == self.max_values_per_facet {
                     Ok(ControlFlow::Break(()))
                 } else {
                     Ok(ControlFlow::Continue(()))
@@ -189,13 +255,44 @@ impl<'a> FacetDistribution<'a> {
         )
     }
 
+    /// Iterates over the facet values of the candidates in the order that
+    /// selects the values to return first.
+    fn iterate_over_facet_distribution<CB>(
+        &self,
+        db: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+        callback: CB,
+    ) -> heed::Result<()>
+    where
+        CB: FnMut(&'a [u8], u64, DocumentId) -> heed::Result<ControlFlow<()>>,
+    {
+        use facet_distribution_iter::{
+            count_iterate_over_facet_distribution, iterate_over_facet_distribution,
+            rev_iterate_over_facet_distribution,
+        };
+
+        let rtxn = self.rtxn;
+        match self.order_by {
+            OrderBy::Lexicographic => {
+                iterate_over_facet_distribution(rtxn, db, field_id, candidates, callback)
+            }
+            OrderBy::ReverseLexicographic => {
+                rev_iterate_over_facet_distribution(rtxn, db, field_id, candidates, callback)
+            }
+            OrderBy::Count => {
+                count_iterate_over_facet_distribution(rtxn, db, field_id, candidates, callback)
+            }
+        }
+    }
+
     /// Placeholder search, a.k.a. no candidates were specified. We iterate throught the
     /// facet values one by one and iterate on the facet level 0 for numbers.
     fn facet_values_from_raw_facet_database(
         &self,
         field_id: FieldId,
-    ) -> heed::Result<BTreeMap<String, u64>> {
-        let mut distribution = BTreeMap::new();
+    ) -> heed::Result<ValuesDistribution> {
+        let mut distribution = ValuesDistribution::default();
 
         let db = self.index.facet_id_f64_docids;
         let mut prefix = vec![];
@@ -209,8 +306,8 @@ impl<'a> FacetDistribution<'a> {
 
         for result in iter {
             let (key, value) = result?;
-            distribution.insert(key.left_bound.to_string(), value.bitmap.len());
-            if distribution.len() == self.max_values_per_facet {
+            distribution.numbers.insert(OrderedFloat(key.left_bound), value.bitmap.len());
+            if distribution.numbers.len() == self.max_values_per_facet {
                 break;
             }
         }
@@ -230,8 +327,8 @@ impl<'a> FacetDistribution<'a> {
             let original_string =
                 self.index.field_id_docid_facet_strings.get(self.rtxn, &key)?.unwrap().to_owned();
 
-            distribution.insert(original_string, value.bitmap.len());
-            if distribution.len() == self.max_values_per_facet {
+            distribution.strings.insert(original_string, value.bitmap.len());
+            if distribution.strings.len() == self.max_values_per_facet {
                 break;
             }
         }
@@ -239,14 +336,35 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
-    fn facet_values(&self, field_id: FieldId) -> heed::Result<BTreeMap<String, u64>> {
+    fn facet_values(&self, field_id: FieldId) -> heed::Result<IndexMap<String, u64>> {
         use FacetType::{Number, String};
 
-        match self.candidates {
+        let mut distribution = match self.candidates {
+            // The lexicographic order can directly read the first values of the facet databases.
+            None if self.order_by == OrderBy::Lexicographic => {
+                self.facet_values_from_raw_facet_database(field_id)?
+            }
+            // The other orders need the facet levels to find the values to return,
+            // all the documents are considered as being candidates.
+            None => {
+                let candidates = self.index.documents_ids(self.rtxn)?;
+                let mut distribution = ValuesDistribution::default();
+                self.facet_numbers_distribution_from_facet_levels(
+                    field_id,
+                    &candidates,
+                    &mut distribution,
+                )?;
+                self.facet_strings_distribution_from_facet_levels(
+                    field_id,
+                    &candidates,
+                    &mut distribution,
+                )?;
+                distribution
+            }
             Some(ref candidates) => {
                 // Classic search, candidates were specified, we must return facet values only related
                 // to those candidates. We also enter here for facet strings for performance reasons.
-                let mut distribution = BTreeMap::new();
+                let mut distribution = ValuesDistribution::default();
                 if candidates.len() <= CANDIDATES_THRESHOLD {
                     self.facet_distribution_from_documents(
                         field_id,
@@ -272,56 +390,129 @@ impl<'a> FacetDistribution<'a> {
                         &mut distribution,
                     )?;
                 }
-                Ok(distribution)
+                distribution
+            }
+        };
+
+        // The booleans are counted with the strings, a `true` boolean adds to the `"true"` strings.
+        let booleans = match self.candidates {
+            Some(ref candidates) => self.facet_booleans_distribution(field_id, candidates)?,
+            None => {
+                let candidates = self.index.documents_ids(self.rtxn)?;
+                self.facet_booleans_distribution(field_id, &candidates)?
             }
-            None => self.facet_values_from_raw_facet_database(field_id),
+        };
+        for (boolean, count) in booleans {
+            *distribution.strings.entry(boolean.to_string()).or_default() += count;
+        }
+
+        // The values are selected and sorted by the same key: the numbers in numeric order
+        // before the strings in lexicographic order.
+        let mut distribution = distribution.into_ordered_values();
+        match self.order_by {
+            OrderBy::Lexicographic => (),
+            OrderBy::ReverseLexicographic => distribution.reverse(),
+            // The sort is stable, the values with the same count stay in lexicographic order.
+            OrderBy::Count => distribution.sort_by(|(_, a), (_, b)| b.cmp(a)),
         }
+
+        // The numbers and the strings have been selected separately, we keep the best of both.
+        distribution.truncate(self.max_values_per_facet);
+        Ok(distribution.into_iter().collect())
     }
 
+    /// Returns the distribution of the selected facets, the values of each facet are sorted
+    /// lexicographically whatever the [`OrderBy`], which only selects the values to return.
     pub fn execute(&self) -> Result<BTreeMap<String, BTreeMap<String, u64>>> {
+        let distribution = self.execute_ordered()?;
+        Ok(distribution
+            .into_iter()
+            .map(|(name, values)| (name, values.into_iter().collect()))
+            .collect())
+    }
+
+    /// Returns the distribution of the selected facets, the values of each
+    /// facet are kept in the order defined by the [`OrderBy`].
+    pub fn execute_ordered(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let fields = self.selected_fields()?;
+
+        let mut distribution = BTreeMap::new();
+        for (fid, name) in fields_ids_map.iter() {
+            if crate::is_faceted(name, &fields) {
+                let values = self.facet_values(fid)?;
+                distribution.insert(name.to_string(), values);
+            }
+        }
+
+        Ok(distribution)
+    }
+
+    /// Returns the number of candidates of the boolean values of the selected facets, without
+    /// the strings they are summed with in the [`execute`](Self::execute) distribution.
+    /// The facets without boolean values are omitted.
+    pub fn execute_booleans(&self) -> Result<BTreeMap<String, BTreeMap<bool, u64>>> {
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let fields = self.selected_fields()?;
+        let candidates = match self.candidates {
+            Some(ref candidates) => candidates.clone(),
+            None => self.index.documents_ids(self.rtxn)?,
+        };
+
+        let mut distribution = BTreeMap::new();
+        for (fid, name) in fields_ids_map.iter() {
+            if crate::is_faceted(name, &fields) {
+                let values = self.facet_booleans_distribution(fid, &candidates)?;
+                if !values.is_empty() {
+                    distribution.insert(name.to_string(), values);
+                }
+            }
+        }
+
+        Ok(distribution)
+    }
+
+    /// Returns the facets asked for, or all the filterable fields if none were specified.
+    fn selected_fields(&self) -> Result<HashSet<String>> {
         let filterable_fields = self.index.filterable_fields(self.rtxn)?;
 
-        let fields = match self.facets {
+        match self.facets {
             Some(ref facets) => {
                 let invalid_fields: HashSet<_> = facets
                     .iter()
                     .filter(|facet| !crate::is_faceted(facet, &filterable_fields))
                     .collect();
                 if !invalid_fields.is_empty() {
-                    return Err(UserError::InvalidFacetsDistribution {
+                    Err(UserError::InvalidFacetsDistribution {
                         invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
                         valid_facets_name: filterable_fields.into_iter().collect(),
                     }
-                    .into());
+                    .into())
                 } else {
-                    facets.clone()
+                    Ok(facets.clone())
                 }
             }
-            None => filterable_fields,
-        };
-
-        let mut distribution = BTreeMap::new();
-        for (fid, name) in fields_ids_map.iter() {
-            if crate::is_faceted(name, &fields) {
-                let values = self.facet_values(fid)?;
-                distribution.insert(name.to_string(), values);
-            }
+            None => Ok(filterable_fields),
         }
-
-        Ok(distribution)
     }
 }
 
 impl fmt::Debug for FacetDistribution<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let FacetDistribution { facets, candidates, max_values_per_facet, rtxn: _, index: _ } =
-            self;
+        let FacetDistribution {
+            facets,
+            candidates,
+            max_values_per_facet,
+            order_by,
+            rtxn: _,
+            index: _,
+        } = self;
 
         f.debug_struct("FacetDistribution")
             .field("facets", facets)
             .field("candidates", candidates)
             .field("max_values_per_facet", max_values_per_facet)
+            .field("order_by", order_by)
             .finish()
     }
 }
@@ -331,6 +522,7 @@ mod tests {
     use big_s::S;
     use maplit::hashset;
 
+    use super::OrderBy;
     use crate::documents::documents_batch_reader_from_objects;
     use crate::index::tests::TempIndex;
     use crate::{milli_snap, FacetDistribution};
@@ -537,4 +729,108 @@ mod tests {
 
         milli_snap!(format!("{map:?}"), "candidates_0_5_000", @"825f23a4090d05756f46176987b7d992");
     }
+
+    #[test]
+    fn order_by() {
+        let mut index = TempIndex::new_with_map_size(4096 * 10_000);
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("colour") }))
+            .unwrap();
+
+        let facet_values = ["Red", "Red", "Red", "Blue", "Blue", "Green", "Yellow"];
+
+        let mut documents = vec![];
+        for i in 0..10_000 {
+            let document = serde_json::json!({
+                "colour": facet_values[i % 7],
+            })
+            .as_object()
+            .unwrap()
+            .clone();
+            documents.push(document);
+        }
+
+        let documents = documents_batch_reader_from_objects(documents);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(std::iter::once("colour"))
+            .order_by(OrderBy::Count)
+            .execute_ordered()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"Red": 4287, "Blue": 2857, "Green": 1428, "Yellow": 1428}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(std::iter::once("colour"))
+            .order_by(OrderBy::Count)
+            .max_values_per_facet(2)
+            .execute_ordered()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"Red": 4287, "Blue": 2857}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(std::iter::once("colour"))
+            .order_by(OrderBy::ReverseLexicographic)
+            .max_values_per_facet(2)
+            .execute_ordered()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"Yellow": 1428, "Red": 4287}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(std::iter::once("colour"))
+            .candidates((0..7).into_iter().collect())
+            .order_by(OrderBy::Count)
+            .execute_ordered()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"Red": 3, "Blue": 2, "Green": 1, "Yellow": 1}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(std::iter::once("colour"))
+            .candidates((0..5_000).into_iter().collect())
+            .order_by(OrderBy::Count)
+            .max_values_per_facet(3)
+            .execute_ordered()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"Red": 2144, "Blue": 1428, "Green": 714}}"###);
+    }
+
+    #[test]
+    fn order_by_reverse_lexicographic_numbers() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("price") }))
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 2 },
+                { "id": 1, "price": 10 },
+                { "id": 2, "price": 9 },
+                { "id": 3, "price": "cheap" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        // the numbers are selected and ordered numerically, after the strings
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(std::iter::once("price"))
+            .order_by(OrderBy::ReverseLexicographic)
+            .max_values_per_facet(3)
+            .execute_ordered()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"price": {"cheap": 1, "10": 1, "9": 1}}"###);
+    }
 }
@@ -1,10 +1,15 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 use std::ops::ControlFlow;
 
+use either::Either;
 use heed::Result;
 use roaring::RoaringBitmap;
 
 use super::{get_first_facet_value, get_highest_level};
-use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec};
+use crate::heed_codec::facet::{
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
+};
 use crate::heed_codec::ByteSliceRefCodec;
 use crate::DocumentId;
 
@@ -29,7 +34,40 @@ pub fn iterate_over_facet_distribution<'t, CB>(
 where
     CB: FnMut(&'t [u8], u64, DocumentId) -> Result<ControlFlow<()>>,
 {
-    let mut fd = FacetDistribution { rtxn, db, field_id, callback };
+    lexicographically_iterate_over_facet_distribution(
+        rtxn, db, field_id, candidates, false, callback,
+    )
+}
+
+/// Same as [`iterate_over_facet_distribution`] but the facet values are
+/// visited from the biggest to the smallest one.
+pub fn rev_iterate_over_facet_distribution<'t, CB>(
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
+    field_id: u16,
+    candidates: &RoaringBitmap,
+    callback: CB,
+) -> Result<()>
+where
+    CB: FnMut(&'t [u8], u64, DocumentId) -> Result<ControlFlow<()>>,
+{
+    lexicographically_iterate_over_facet_distribution(
+        rtxn, db, field_id, candidates, true, callback,
+    )
+}
+
+fn lexicographically_iterate_over_facet_distribution<'t, CB>(
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
+    field_id: u16,
+    candidates: &RoaringBitmap,
+    descending: bool,
+    callback: CB,
+) -> Result<()>
+where
+    CB: FnMut(&'t [u8], u64, DocumentId) -> Result<ControlFlow<()>>,
+{
+    let mut fd = FacetDistribution { rtxn, db, field_id, descending, callback };
     let highest_level = get_highest_level(
         rtxn,
         db.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
@@ -44,6 +82,117 @@ where
     }
 }
 
+/// Call the given closure on the facet distribution of the candidate documents,
+/// from the facet value with the highest number of candidates to the one with the lowest.
+/// The facet values with the same number of candidates are visited in lexicographic order.
+///
+/// The closure is called with the same arguments as in [`iterate_over_facet_distribution`].
+///
+/// The number of documents in a group is an upper bound of the number of documents of each
+/// of its facet values, the groups are therefore visited in a best-first fashion and only
+/// the groups that could contain the next most frequent facet value are opened.
+pub fn count_iterate_over_facet_distribution<'t, CB>(
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
+    field_id: u16,
+    candidates: &RoaringBitmap,
+    mut callback: CB,
+) -> Result<()>
+where
+    CB: FnMut(&'t [u8], u64, DocumentId) -> Result<ControlFlow<()>>,
+{
+    let highest_level = get_highest_level(rtxn, db, field_id)?;
+    let first_bound = match get_first_facet_value::<ByteSliceRefCodec>(rtxn, db, field_id)? {
+        Some(first_bound) => first_bound,
+        None => return Ok(()),
+    };
+
+    let mut heap = BinaryHeap::new();
+    let starting_key = FacetGroupKey { field_id, level: highest_level, left_bound: first_bound };
+    push_facet_groups(rtxn, db, starting_key, usize::MAX, candidates, &mut heap)?;
+    while let Some(group) = heap.pop() {
+        if group.level == 0 {
+            let any_docid_in_common = group.docids.min().unwrap();
+            if callback(group.left_bound, group.count, any_docid_in_common)?.is_break() {
+                break;
+            }
+        } else {
+            let starting_key =
+                FacetGroupKey { field_id, level: group.level - 1, left_bound: group.left_bound };
+            push_facet_groups(rtxn, db, starting_key, group.size, &group.docids, &mut heap)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Pushes the groups that contain some of the candidates into the heap, starting at
+/// the given key and stopping after `group_size` groups or at the end of the field.
+fn push_facet_groups<'t>(
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
+    starting_key: FacetGroupKey<&'t [u8]>,
+    group_size: usize,
+    candidates: &RoaringBitmap,
+    heap: &mut BinaryHeap<FacetGroupCount<'t>>,
+) -> Result<()> {
+    for el in db.range(rtxn, &(starting_key..))?.take(group_size) {
+        let (key, value) = el?;
+        // The range is unbounded on the right and the group size for the highest level is MAX,
+        // so we need to check that we are not iterating over the next field id
+        if key.field_id != starting_key.field_id {
+            break;
+        }
+        let docids = value.bitmap & candidates;
+        if !docids.is_empty() {
+            heap.push(FacetGroupCount {
+                count: docids.len(),
+                level: key.level,
+                left_bound: key.left_bound,
+                size: value.size as usize,
+                docids,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// A group of facet values visited by [`count_iterate_over_facet_distribution`].
+struct FacetGroupCount<'t> {
+    count: u64,
+    level: u8,
+    left_bound: &'t [u8],
+    size: usize,
+    docids: RoaringBitmap,
+}
+
+impl FacetGroupCount<'_> {
+    /// The groups with the most documents come first, then the smallest facet values.
+    fn priority(&self) -> (u64, Reverse<&[u8]>) {
+        (self.count, Reverse(self.left_bound))
+    }
+}
+
+impl PartialEq for FacetGroupCount<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
+    }
+}
+
+impl Eq for FacetGroupCount<'_> {}
+
+impl PartialOrd for FacetGroupCount<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FacetGroupCount<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority().cmp(&other.priority())
+    }
+}
+
 struct FacetDistribution<'t, CB>
 where
     CB: FnMut(&'t [u8], u64, DocumentId) -> Result<ControlFlow<()>>,
@@ -51,6 +200,7 @@ where
     rtxn: &'t heed::RoTxn<'t>,
     db: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
     field_id: u16,
+    descending: bool,
     callback: CB,
 }
 
@@ -58,6 +208,23 @@ impl<'t, CB> FacetDistribution<'t, CB>
 where
     CB: FnMut(&'t [u8], u64, DocumentId) -> Result<ControlFlow<()>>,
 {
+    /// Returns the elements of a group in the order they must be visited.
+    fn ordered(
+        &self,
+        iter: impl Iterator<Item = Result<(FacetGroupKey<&'t [u8]>, FacetGroupValue)>>,
+    ) -> impl Iterator<Item = Result<(FacetGroupKey<&'t [u8]>, FacetGroupValue)>> {
+        if self.descending {
+            // The groups can only be read forward, we collect their elements to reverse them.
+            let field_id = self.field_id;
+            let elements: Vec<_> = iter
+                .take_while(|el| el.as_ref().map_or(true, |(key, _)| key.field_id == field_id))
+                .collect();
+            Either::Left(elements.into_iter().rev())
+        } else {
+            Either::Right(iter)
+        }
+    }
+
     fn iterate_level_0(
         &mut self,
         candidates: &RoaringBitmap,
@@ -67,7 +234,7 @@ where
         let starting_key =
             FacetGroupKey { field_id: self.field_id, level: 0, left_bound: starting_bound };
         let iter = self.db.range(self.rtxn, &(starting_key..))?.take(group_size);
-        for el in iter {
+        for el in self.ordered(iter) {
             let (key, value) = el?;
             // The range is unbounded on the right and the group size for the highest level is MAX,
             // so we need to check that we are not iterating over the next field id
@@ -100,7 +267,7 @@ where
             FacetGroupKey { field_id: self.field_id, level, left_bound: starting_bound };
         let iter = self.db.range(self.rtxn, &(&starting_key..)).unwrap().take(group_size);
 
-        for el in iter {
+        for el in self.ordered(iter) {
             let (key, value) = el.unwrap();
             // The range is unbounded on the right and the group size for the highest level is MAX,
             // so we need to check that we are not iterating over the next field id
@@ -132,7 +299,10 @@ mod tests {
     use heed::BytesDecode;
     use roaring::RoaringBitmap;
 
-    use super::iterate_over_facet_distribution;
+    use super::{
+        count_iterate_over_facet_distribution, iterate_over_facet_distribution,
+        rev_iterate_over_facet_distribution,
+    };
     use crate::heed_codec::facet::OrderedF64Codec;
     use crate::milli_snap;
     use crate::search::facet::tests::{get_random_looking_index, get_simple_index};
@@ -192,4 +362,71 @@ mod tests {
             txn.commit().unwrap();
         }
     }
+
+    #[test]
+    fn filter_distribution_ordered() {
+        let indexes = [get_simple_index(), get_random_looking_index()];
+        for index in indexes.iter() {
+            let txn = index.env.read_txn().unwrap();
+            let candidates = (0..=255).into_iter().collect::<RoaringBitmap>();
+
+            let mut lexicographic = Vec::new();
+            iterate_over_facet_distribution(
+                &txn,
+                index.content,
+                0,
+                &candidates,
+                |facet, count, _| {
+                    let facet = OrderedF64Codec::bytes_decode(facet).unwrap();
+                    lexicographic.push((facet, count));
+                    Ok(ControlFlow::Continue(()))
+                },
+            )
+            .unwrap();
+
+            let mut reversed = Vec::new();
+            rev_iterate_over_facet_distribution(
+                &txn,
+                index.content,
+                0,
+                &candidates,
+                |facet, count, _| {
+                    let facet = OrderedF64Codec::bytes_decode(facet).unwrap();
+                    reversed.push((facet, count));
+                    Ok(ControlFlow::Continue(()))
+                },
+            )
+            .unwrap();
+
+            let mut expected = lexicographic.clone();
+            expected.reverse();
+            assert_eq!(reversed, expected);
+
+            // stop after the 10 most frequent facet values
+            let mut by_count = Vec::new();
+            count_iterate_over_facet_distribution(
+                &txn,
+                index.content,
+                0,
+                &candidates,
+                |facet, count, _| {
+                    let facet = OrderedF64Codec::bytes_decode(facet).unwrap();
+                    by_count.push((facet, count));
+                    if by_count.len() == 10 {
+                        Ok(ControlFlow::Break(()))
+                    } else {
+                        Ok(ControlFlow::Continue(()))
+                    }
+                },
+            )
+            .unwrap();
+
+            let mut expected = lexicographic.clone();
+            expected.sort_by(|(_, a), (_, b)| b.cmp(a));
+            expected.truncate(10);
+            assert_eq!(by_count, expected);
+
+            txn.commit().unwrap();
+        }
+    }
 }
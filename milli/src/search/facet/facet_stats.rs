@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::ops::ControlFlow;
+
+use heed::BytesDecode;
+use roaring::RoaringBitmap;
+
+use crate::error::UserError;
+use crate::facet::FacetType;
+use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
+use crate::heed_codec::ByteSliceRefCodec;
+use crate::search::facet::facet_distribution_iter;
+use crate::{FieldId, Index, Result};
+
+/// Computes statistics on the numeric values of the facets of the candidate documents.
+pub struct FacetStats<'a> {
+    facets: Option<HashSet<String>>,
+    candidates: Option<RoaringBitmap>,
+    rtxn: &'a heed::RoTxn<'a>,
+    index: &'a Index,
+}
+
+impl<'a> FacetStats<'a> {
+    pub fn new(rtxn: &'a heed::RoTxn, index: &'a Index) -> FacetStats<'a> {
+        FacetStats { facets: None, candidates: None, rtxn, index }
+    }
+
+    pub fn facets<I: IntoIterator<Item = A>, A: AsRef<str>>(&mut self, names: I) -> &mut Self {
+        self.facets = Some(names.into_iter().map(|s| s.as_ref().to_string()).collect());
+        self
+    }
+
+    pub fn candidates(&mut self, candidates: RoaringBitmap) -> &mut Self {
+        self.candidates = Some(candidates);
+        self
+    }
+
+    /// Computes the stats of a field by iterating over its facet values in ascending order,
+    /// the groups of values that don't contain any candidate are skipped.
+    fn field_stats(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+    ) -> heed::Result<Option<NumericFacetStats>> {
+        let faceted = self.index.faceted_documents_ids(self.rtxn, field_id, FacetType::Number)?;
+        let count = faceted.intersection_len(candidates);
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let mut min = None;
+        let mut max = f64::NEG_INFINITY;
+        let mut sum = 0.0;
+        let mut values_count = 0;
+        facet_distribution_iter::iterate_over_facet_distribution(
+            self.rtxn,
+            self.index
+                .facet_id_f64_docids
+                .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
+            field_id,
+            candidates,
+            |facet_key, nbr_docids, _| {
+                let value = OrderedF64Codec::bytes_decode(facet_key).unwrap();
+                min.get_or_insert(value);
+                max = value;
+                sum += value * nbr_docids as f64;
+                values_count += nbr_docids;
+                Ok(ControlFlow::Continue(()))
+            },
+        )?;
+
+        Ok(min.map(|min| NumericFacetStats {
+            min,
+            max,
+            count,
+            sum,
+            avg: sum / values_count as f64,
+        }))
+    }
+
+    /// Returns the stats of the filterable fields that have numeric values among the candidates.
+    pub fn execute(&self) -> Result<BTreeMap<String, NumericFacetStats>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+
+        let fields = match self.facets {
+            Some(ref facets) => {
+                let invalid_fields: HashSet<_> = facets
+                    .iter()
+                    .filter(|facet| !crate::is_faceted(facet, &filterable_fields))
+                    .collect();
+                if !invalid_fields.is_empty() {
+                    return Err(UserError::InvalidFacetsStats {
+                        invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
+                        valid_facets_name: filterable_fields.into_iter().collect(),
+                    }
+                    .into());
+                } else {
+                    facets.clone()
+                }
+            }
+            None => filterable_fields,
+        };
+
+        let candidates = match self.candidates {
+            Some(ref candidates) => candidates.clone(),
+            None => self.index.documents_ids(self.rtxn)?,
+        };
+
+        let mut stats = BTreeMap::new();
+        for (fid, name) in fields_ids_map.iter() {
+            if crate::is_faceted(name, &fields) {
+                if let Some(field_stats) = self.field_stats(fid, &candidates)? {
+                    stats.insert(name.to_string(), field_stats);
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+}
+
+impl fmt::Debug for FacetStats<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let FacetStats { facets, candidates, rtxn: _, index: _ } = self;
+
+        f.debug_struct("FacetStats")
+            .field("facets", facets)
+            .field("candidates", candidates)
+            .finish()
+    }
+}
+
+/// The statistics of the numeric values of a facet.
+///
+/// A document with several values counts once in `count` but
+/// all its values participate in the `sum` and the `avg`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericFacetStats {
+    pub min: f64,
+    pub max: f64,
+    /// The number of candidates that have at least one numeric value.
+    pub count: u64,
+    pub sum: f64,
+    pub avg: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use crate::index::tests::TempIndex;
+    use crate::FacetStats;
+
+    #[test]
+    fn facet_stats() {
+        let mut index = TempIndex::new();
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("price"), S("colour") })
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "price": 10, "colour": "blue" },
+                { "price": [2.5, 30], "colour": "red" },
+                { "price": "cheap", "colour": "green" },
+                { "price": 40 },
+                { "colour": "blue" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let stats = FacetStats::new(&txn, &index).execute().unwrap();
+        assert_eq!(stats.len(), 1);
+        let price = stats["price"];
+        assert_eq!((price.min, price.max, price.count), (2.5, 40.0, 3));
+        assert_eq!((price.sum, price.avg), (82.5, 20.625));
+
+        let stats = FacetStats::new(&txn, &index)
+            .facets(["price"])
+            .candidates([0, 2, 4].iter().copied().collect())
+            .execute()
+            .unwrap();
+        let price = stats["price"];
+        assert_eq!(
+            (price.min, price.max, price.count, price.sum, price.avg),
+            (10.0, 10.0, 1, 10.0, 10.0)
+        );
+
+        let stats = FacetStats::new(&txn, &index)
+            .candidates([2, 4].iter().copied().collect())
+            .execute()
+            .unwrap();
+        assert!(stats.is_empty());
+
+        let error = FacetStats::new(&txn, &index).facets(["size"]).execute().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid facet stats, attribute `size` is not filterable. The available filterable attributes are `colour, price`."
+        );
+    }
+}
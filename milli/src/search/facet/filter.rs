@@ -1,17 +1,29 @@
 use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::fmt::{Debug, Display};
 use std::ops::Bound::{self, Excluded, Included};
+use std::result::Result as StdResult;
 
 use either::Either;
-pub use filter_parser::{Condition, Error as FPError, FilterCondition, Span, Token};
+pub use filter_parser::{
+    Condition, Error as FPError, FilterCondition, FilterValue, OwnedFilterCondition, Span, Token,
+};
+use heed::types::ByteSlice;
 use roaring::RoaringBitmap;
+use rstar::AABB;
 
-use super::facet_range_search;
+use super::{facet_range_search, FilterCache};
 use crate::error::{Error, UserError};
+use crate::facet::{rfc3339_to_timestamp, FacetType};
 use crate::heed_codec::facet::{
-    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, FacetGroupValueLenCodec,
+    OrderedF64Codec,
+};
+use crate::heed_codec::StrRefCodec;
+use crate::{
+    distance_between_two_points, lat_lng_to_xyz, CboRoaringBitmapLenCodec, FieldId, FieldsIdsMap,
+    Index, Result, BEU16,
 };
-use crate::{distance_between_two_points, lat_lng_to_xyz, FieldId, Index, Result};
 
 /// The maximum number of filters the filter AST can process.
 const MAX_FILTER_DEPTH: usize = 2000;
@@ -21,12 +33,73 @@ pub struct Filter<'a> {
     condition: FilterCondition<'a>,
 }
 
+/// The settings of the index read once per evaluation and shared by all the sub-filters.
+struct EvaluationSettings {
+    filterable_fields: HashSet<String>,
+    case_sensitive_facets: HashSet<FieldId>,
+    fields_ids_map: FieldsIdsMap,
+    documents_count: u64,
+}
+
+impl EvaluationSettings {
+    fn new(rtxn: &heed::RoTxn, index: &Index) -> Result<Self> {
+        Ok(EvaluationSettings {
+            filterable_fields: index.filterable_fields(rtxn)?,
+            case_sensitive_facets: index.case_sensitive_facets_ids(rtxn)?,
+            fields_ids_map: index.fields_ids_map(rtxn)?,
+            documents_count: index.number_of_documents(rtxn)?,
+        })
+    }
+
+    /// Normalizes a value the same way the string facet values of the field are:
+    /// the values are lowercased unless the field is a case sensitive facet.
+    fn normalize_facet_value(&self, field_id: FieldId, value: &str) -> String {
+        if self.case_sensitive_facets.contains(&field_id) {
+            value.to_string()
+        } else {
+            value.to_lowercase()
+        }
+    }
+}
+
+/// The estimated number of documents matching a filter, with the estimates of its `OR` and `AND`
+/// sub-filters. The nested `AND` filters reuse the estimates computed for their parent.
+struct Estimate {
+    count: u64,
+    subfilters: Vec<Estimate>,
+}
+
+/// The cache of an evaluation with the owned version of the evaluated filter that keys its
+/// documents ids. The owned filter is built once, its sub-filters key the evaluated sub-filters.
+#[derive(Clone, Copy)]
+struct CacheContext<'c> {
+    cache: &'c FilterCache,
+    generation: u64,
+    filter: &'c OwnedFilterCondition,
+}
+
+impl<'c> CacheContext<'c> {
+    /// The context of the `index`-th sub-filter of a `NOT`, `OR` or `AND` filter.
+    fn subfilter(self, index: usize) -> Self {
+        let filter = match self.filter {
+            OwnedFilterCondition::Not(filter) => filter,
+            OwnedFilterCondition::Or(filters) | OwnedFilterCondition::And(filters) => {
+                &filters[index]
+            }
+            _ => unreachable!("the owned filter mirrors the evaluated filter"),
+        };
+        CacheContext { filter, ..self }
+    }
+}
+
 #[derive(Debug)]
 enum FilterError<'a> {
     AttributeNotFilterable { attribute: &'a str, filterable_fields: HashSet<String> },
     BadGeo(&'a str),
     BadGeoLat(f64),
     BadGeoLng(f64),
+    BadGeoBoundingBoxTopIsBelowBottom(f64, f64),
+    NotNumberOrDate(&'a str),
     Reserved(&'a str),
     TooDeep,
 }
@@ -62,9 +135,11 @@ impl<'a> Display for FilterError<'a> {
                 "`{}` is a reserved keyword and thus can't be used as a filter expression.",
                 keyword
             ),
-            Self::BadGeo(keyword) => write!(f, "`{}` is a reserved keyword and thus can't be used as a filter expression. Use the _geoRadius(latitude, longitude, distance) built-in rule to filter on _geo field coordinates.", keyword),
+            Self::BadGeo(keyword) => write!(f, "`{}` is a reserved keyword and thus can't be used as a filter expression. Use the _geoRadius(latitude, longitude, distance), _geoBoundingBox([latitude, longitude], [latitude, longitude]) or _geoPolygon([latitude, longitude], ...) built-in rules to filter on _geo field coordinates.", keyword),
             Self::BadGeoLat(lat) => write!(f, "Bad latitude `{}`. Latitude must be contained between -90 and 90 degrees. ", lat),
             Self::BadGeoLng(lng) => write!(f, "Bad longitude `{}`. Longitude must be contained between -180 and 180 degrees. ", lng),
+            Self::BadGeoBoundingBoxTopIsBelowBottom(top, bottom) => write!(f, "The top latitude `{}` is below the bottom latitude `{}`.", top, bottom),
+            Self::NotNumberOrDate(value) => write!(f, "Invalid value `{}`, expected a number or an RFC 3339 date like `2023-01-01T00:00:00Z`.", value),
         }
     }
 }
@@ -129,7 +204,18 @@ impl<'a> Filter<'a> {
 
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(expression: &'a str) -> Result<Option<Self>> {
-        let condition = match FilterCondition::parse(expression) {
+        Self::from_str_with_params(expression, &[])
+    }
+
+    /// Parses a filter in which the `?` placeholders are replaced, in order, by the given
+    /// parameters, e.g. `price > ? AND brand IN ?` with a number and a list of strings.
+    ///
+    /// The parameters are never interpreted as filter syntax and don't need to be escaped.
+    pub fn from_str_with_params(
+        expression: &'a str,
+        params: &[FilterValue],
+    ) -> Result<Option<Self>> {
+        let condition = match FilterCondition::parse_with_params(expression, params) {
             Ok(Some(fc)) => Ok(fc),
             Ok(None) => return Ok(None),
             Err(e) => Err(Error::UserError(UserError::InvalidFilter(e.to_string()))),
@@ -145,19 +231,85 @@ impl<'a> Filter<'a> {
 
 impl<'a> Filter<'a> {
     pub fn evaluate(&self, rtxn: &heed::RoTxn, index: &Index) -> Result<RoaringBitmap> {
+        self.evaluate_with_optional_cache(rtxn, index, None)
+    }
+
+    /// Evaluates the filter like [`Filter::evaluate`] but reuses the documents ids of the
+    /// filter and sub-filters already evaluated on the same version of the index.
+    pub fn evaluate_with_cache(
+        &self,
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        cache: &FilterCache,
+    ) -> Result<RoaringBitmap> {
+        self.evaluate_with_optional_cache(rtxn, index, Some(cache))
+    }
+
+    /// Checks the filter against the filterable attributes of the index without evaluating it.
+    ///
+    /// Unlike the evaluation that stops at the first error, all the errors are returned,
+    /// each one with the span of the part of the filter it comes from.
+    pub fn validate(&self, rtxn: &heed::RoTxn, index: &Index) -> Result<Vec<FPError>> {
+        let filterable_fields = index.filterable_fields(rtxn)?;
+
+        let mut errors = Vec::new();
+        if let Some(token) = self.condition.token_at_depth(MAX_FILTER_DEPTH) {
+            errors.push(token.as_external_error(FilterError::TooDeep));
+        }
+        validate_condition(&self.condition, &filterable_fields, &mut errors);
+        errors.sort_by_key(|error| error.context().location_offset());
+
+        Ok(errors)
+    }
+
+    fn evaluate_with_optional_cache(
+        &self,
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        cache: Option<&FilterCache>,
+    ) -> Result<RoaringBitmap> {
         // to avoid doing this for each recursive call we're going to do it ONCE ahead of time
         let soft_deleted_documents = index.soft_deleted_documents_ids(rtxn)?;
-        let filterable_fields = index.filterable_fields(rtxn)?;
+        let settings = EvaluationSettings::new(rtxn, index)?;
+        let owned_filter;
+        let cache = match cache {
+            Some(cache) => {
+                owned_filter = OwnedFilterCondition::from(&self.condition);
+                let generation = index.generation(rtxn)?;
+                Some(CacheContext { cache, generation, filter: &owned_filter })
+            }
+            None => None,
+        };
 
         // and finally we delete all the soft_deleted_documents, again, only once at the very end
-        self.inner_evaluate(rtxn, index, &filterable_fields)
+        self.inner_evaluate(rtxn, index, &settings, None, None, cache)
             .map(|result| result - soft_deleted_documents)
     }
 
+    /// Returns the center of the first `_geoRadius` the documents must match, the
+    /// `_geoRadius` under a `NOT` or an `OR` are ignored as a document may not match them.
+    pub(crate) fn geo_radius_point(&self) -> Option<[f64; 2]> {
+        fn inner(condition: &FilterCondition) -> Option<[f64; 2]> {
+            match condition {
+                FilterCondition::And(subfilters) => subfilters.iter().find_map(inner),
+                FilterCondition::GeoLowerThan { point, .. } => {
+                    let lat = point[0].parse_finite_float().ok()?;
+                    let lng = point[1].parse_finite_float().ok()?;
+                    Some([lat, lng])
+                }
+                _ => None,
+            }
+        }
+
+        inner(&self.condition)
+    }
+
     fn evaluate_operator(
         rtxn: &heed::RoTxn,
         index: &Index,
+        settings: &EvaluationSettings,
         field_id: FieldId,
+        universe: Option<&RoaringBitmap>,
         operator: &Condition<'a>,
     ) -> Result<RoaringBitmap> {
         let numbers_db = index.facet_id_f64_docids;
@@ -169,35 +321,40 @@ impl<'a> Filter<'a> {
 
         let (left, right) = match operator {
             Condition::GreaterThan(val) => {
-                (Excluded(val.parse_finite_float()?), Included(f64::MAX))
+                (Excluded(parse_number_or_date(val)?), Included(f64::MAX))
             }
             Condition::GreaterThanOrEqual(val) => {
-                (Included(val.parse_finite_float()?), Included(f64::MAX))
+                (Included(parse_number_or_date(val)?), Included(f64::MAX))
             }
-            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(val.parse_finite_float()?)),
+            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(parse_number_or_date(val)?)),
             Condition::LowerThanOrEqual(val) => {
-                (Included(f64::MIN), Included(val.parse_finite_float()?))
+                (Included(f64::MIN), Included(parse_number_or_date(val)?))
             }
             Condition::Between { from, to } => {
-                (Included(from.parse_finite_float()?), Included(to.parse_finite_float()?))
+                (Included(parse_number_or_date(from)?), Included(parse_number_or_date(to)?))
             }
             Condition::Exists => {
                 let exist = index.exists_faceted_documents_ids(rtxn, field_id)?;
                 return Ok(exist);
             }
+            Condition::Null => {
+                let is_null = index.null_faceted_documents_ids(rtxn, field_id)?;
+                return Ok(is_null);
+            }
+            Condition::Empty => {
+                let is_empty = index.empty_faceted_documents_ids(rtxn, field_id)?;
+                return Ok(is_empty);
+            }
             Condition::Equal(val) => {
+                if let Some(boolean) = parse_field_boolean(rtxn, index, field_id, val)? {
+                    return Ok(index.bool_faceted_documents_ids(rtxn, field_id, boolean)?);
+                }
+                let value = settings.normalize_facet_value(field_id, val.value());
                 let string_docids = strings_db
-                    .get(
-                        rtxn,
-                        &FacetGroupKey {
-                            field_id,
-                            level: 0,
-                            left_bound: &val.value().to_lowercase(),
-                        },
-                    )?
+                    .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: &value })?
                     .map(|v| v.bitmap)
                     .unwrap_or_default();
-                let number = val.parse_finite_float().ok();
+                let number = parse_number_or_date(val).ok();
                 let number_docids = match number {
                     Some(n) => numbers_db
                         .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: n })?
@@ -209,10 +366,24 @@ impl<'a> Filter<'a> {
             }
             Condition::NotEqual(val) => {
                 let operator = Condition::Equal(val.clone());
-                let docids = Self::evaluate_operator(rtxn, index, field_id, &operator)?;
-                let all_ids = index.documents_ids(rtxn)?;
+                let docids =
+                    Self::evaluate_operator(rtxn, index, settings, field_id, None, &operator)?;
+                let all_ids = match universe {
+                    Some(universe) => universe.clone(),
+                    None => index.documents_ids(rtxn)?,
+                };
                 return Ok(all_ids - docids);
             }
+            Condition::StartsWith(val) => {
+                let prefix = settings.normalize_facet_value(field_id, val.value());
+                return Self::evaluate_string_values(rtxn, index, field_id, &prefix, |_| true);
+            }
+            Condition::Contains(val) => {
+                let substring = settings.normalize_facet_value(field_id, val.value());
+                return Self::evaluate_string_values(rtxn, index, field_id, "", |value| {
+                    value.contains(&substring)
+                });
+            }
         };
 
         let mut output = RoaringBitmap::new();
@@ -220,6 +391,37 @@ impl<'a> Filter<'a> {
         Ok(output)
     }
 
+    /// Aggregates the documents ids of the string facet values starting with the
+    /// given prefix and matching the predicate, the values are read in the level 0.
+    fn evaluate_string_values(
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        field_id: FieldId,
+        prefix: &str,
+        predicate: impl Fn(&str) -> bool,
+    ) -> Result<RoaringBitmap> {
+        let mut key_prefix = Vec::new();
+        key_prefix.extend_from_slice(&field_id.to_be_bytes());
+        key_prefix.push(0); // read values from level 0 only
+        key_prefix.extend_from_slice(prefix.as_bytes());
+
+        let iter = index
+            .facet_id_string_docids
+            .remap_types::<ByteSlice, ByteSlice>()
+            .prefix_iter(rtxn, &key_prefix)?
+            .remap_types::<FacetGroupKeyCodec<StrRefCodec>, FacetGroupValueCodec>();
+
+        let mut output = RoaringBitmap::new();
+        for result in iter {
+            let (key, value) = result?;
+            if predicate(key.left_bound) {
+                output |= value.bitmap;
+            }
+        }
+
+        Ok(output)
+    }
+
     /// Aggregates the documents ids that are part of the specified range automatically
     /// going deeper through the levels.
     fn explore_facet_number_levels(
@@ -245,33 +447,211 @@ impl<'a> Filter<'a> {
         Ok(())
     }
 
+    /// Returns a cheap upper bound of the number of documents matching the filter,
+    /// only the lengths of the bitmaps are read from the databases.
+    ///
+    /// The filters that are costly to evaluate, like `NOT`, `!=` and the geo filters, are
+    /// estimated to match all the documents to be evaluated last. The filters on attributes
+    /// that aren't filterable are estimated to match nothing to return their error early.
+    fn estimate_cardinality(
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        settings: &EvaluationSettings,
+        condition: &FilterCondition,
+    ) -> Result<Estimate> {
+        let filterable_fields = &settings.filterable_fields;
+        let fields_ids_map = &settings.fields_ids_map;
+        let documents_count = settings.documents_count;
+        let field_id = |fid: &Token| {
+            if crate::is_faceted(fid.value(), filterable_fields) {
+                fields_ids_map.id(fid.value())
+            } else {
+                None
+            }
+        };
+        let exists_count = |field_id: FieldId| {
+            index
+                .facet_id_exists_docids
+                .remap_data_type::<CboRoaringBitmapLenCodec>()
+                .get(rtxn, &BEU16::new(field_id))
+                .map(Option::unwrap_or_default)
+        };
+
+        let mut subfilters_estimates = Vec::new();
+        let estimate = match condition {
+            FilterCondition::Condition { fid, op } => match field_id(fid) {
+                Some(field_id) => match op {
+                    Condition::Equal(val) => {
+                        Self::estimate_equal(rtxn, index, settings, field_id, val)?
+                    }
+                    Condition::NotEqual(_) => documents_count,
+                    Condition::Null => index
+                        .facet_id_is_null_docids
+                        .remap_data_type::<CboRoaringBitmapLenCodec>()
+                        .get(rtxn, &BEU16::new(field_id))?
+                        .unwrap_or_default(),
+                    Condition::Empty => index
+                        .facet_id_is_empty_docids
+                        .remap_data_type::<CboRoaringBitmapLenCodec>()
+                        .get(rtxn, &BEU16::new(field_id))?
+                        .unwrap_or_default(),
+                    Condition::Exists
+                    | Condition::GreaterThan(_)
+                    | Condition::GreaterThanOrEqual(_)
+                    | Condition::LowerThan(_)
+                    | Condition::LowerThanOrEqual(_)
+                    | Condition::Between { .. }
+                    | Condition::StartsWith(_)
+                    | Condition::Contains(_) => exists_count(field_id)?,
+                },
+                None => 0,
+            },
+            FilterCondition::In { fid, els } => match field_id(fid) {
+                Some(field_id) => {
+                    let mut estimate = 0u64;
+                    for el in els {
+                        let count = Self::estimate_equal(rtxn, index, settings, field_id, el)?;
+                        estimate = estimate.saturating_add(count);
+                    }
+                    estimate
+                }
+                None => 0,
+            },
+            FilterCondition::Not(_) => documents_count,
+            FilterCondition::Or(subfilters) => {
+                let mut estimate = 0u64;
+                for f in subfilters {
+                    let subfilter_estimate = Self::estimate_cardinality(rtxn, index, settings, f)?;
+                    estimate = estimate.saturating_add(subfilter_estimate.count);
+                    subfilters_estimates.push(subfilter_estimate);
+                }
+                estimate
+            }
+            FilterCondition::And(subfilters) => {
+                let mut estimate = if subfilters.is_empty() { 0 } else { documents_count };
+                for f in subfilters {
+                    let subfilter_estimate = Self::estimate_cardinality(rtxn, index, settings, f)?;
+                    estimate = estimate.min(subfilter_estimate.count);
+                    subfilters_estimates.push(subfilter_estimate);
+                }
+                estimate
+            }
+            FilterCondition::GeoLowerThan { .. }
+            | FilterCondition::GeoBoundingBox { .. }
+            | FilterCondition::GeoPolygon { .. } => {
+                if filterable_fields.contains("_geo") {
+                    documents_count
+                } else {
+                    0
+                }
+            }
+        };
+
+        Ok(Estimate { count: estimate.min(documents_count), subfilters: subfilters_estimates })
+    }
+
+    /// Returns the number of documents having the given facet value in the level 0.
+    fn estimate_equal(
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        settings: &EvaluationSettings,
+        field_id: FieldId,
+        val: &Token,
+    ) -> Result<u64> {
+        if let Some(boolean) = parse_field_boolean(rtxn, index, field_id, val)? {
+            let estimate = index
+                .facet_id_bool_docids
+                .remap_data_type::<CboRoaringBitmapLenCodec>()
+                .get(rtxn, &(field_id, boolean))?
+                .unwrap_or_default();
+            return Ok(estimate);
+        }
+        let value = settings.normalize_facet_value(field_id, val.value());
+        let mut estimate = index
+            .facet_id_string_docids
+            .remap_data_type::<FacetGroupValueLenCodec>()
+            .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: &value })?
+            .unwrap_or_default();
+        if let Ok(number) = parse_number_or_date(val) {
+            estimate += index
+                .facet_id_f64_docids
+                .remap_data_type::<FacetGroupValueLenCodec>()
+                .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: number })?
+                .unwrap_or_default();
+        }
+        Ok(estimate)
+    }
+
+    /// When a universe is given the returned documents ids are only
+    /// correct inside of it, the caller must intersect them with it.
+    ///
+    /// The `estimate` of the filter is given when it has already been computed by a parent filter.
     fn inner_evaluate(
         &self,
         rtxn: &heed::RoTxn,
         index: &Index,
-        filterable_fields: &HashSet<String>,
+        settings: &EvaluationSettings,
+        universe: Option<&RoaringBitmap>,
+        estimate: Option<&Estimate>,
+        cache: Option<CacheContext>,
+    ) -> Result<RoaringBitmap> {
+        let context = match cache {
+            Some(context) => context,
+            None => {
+                return self.evaluate_condition(rtxn, index, settings, universe, estimate, None)
+            }
+        };
+
+        // The documents ids computed without a universe are correct inside of any universe.
+        let CacheContext { cache, generation, filter } = context;
+        if let Some(docids) = cache.get(index.path(), generation, filter) {
+            return Ok(docids);
+        }
+
+        let docids =
+            self.evaluate_condition(rtxn, index, settings, universe, estimate, Some(context))?;
+        if universe.is_none() || !depends_on_universe(&self.condition) {
+            cache.insert(index.path(), generation, filter.clone(), docids.clone());
+        }
+        Ok(docids)
+    }
+
+    fn evaluate_condition(
+        &self,
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        settings: &EvaluationSettings,
+        universe: Option<&RoaringBitmap>,
+        estimate: Option<&Estimate>,
+        cache: Option<CacheContext>,
     ) -> Result<RoaringBitmap> {
+        let filterable_fields = &settings.filterable_fields;
         match &self.condition {
             FilterCondition::Not(f) => {
-                let all_ids = index.documents_ids(rtxn)?;
+                let all_ids = match universe {
+                    Some(universe) => universe.clone(),
+                    None => index.documents_ids(rtxn)?,
+                };
                 let selected = Self::inner_evaluate(
                     &(f.as_ref().clone()).into(),
                     rtxn,
                     index,
-                    filterable_fields,
+                    settings,
+                    Some(&all_ids),
+                    None,
+                    cache.map(|cache| cache.subfilter(0)),
                 )?;
                 Ok(all_ids - selected)
             }
             FilterCondition::In { fid, els } => {
                 if crate::is_faceted(fid.value(), filterable_fields) {
-                    let field_ids_map = index.fields_ids_map(rtxn)?;
-
-                    if let Some(fid) = field_ids_map.id(fid.value()) {
+                    if let Some(fid) = settings.fields_ids_map.id(fid.value()) {
                         let mut bitmap = RoaringBitmap::new();
 
                         for el in els {
                             let op = Condition::Equal(el.clone());
-                            let el_bitmap = Self::evaluate_operator(rtxn, index, fid, &op)?;
+                            let el_bitmap =
+                                Self::evaluate_operator(rtxn, index, settings, fid, None, &op)?;
                             bitmap |= el_bitmap;
                         }
                         Ok(bitmap)
@@ -279,87 +659,76 @@ impl<'a> Filter<'a> {
                         Ok(RoaringBitmap::new())
                     }
                 } else {
-                    Err(fid.as_external_error(FilterError::AttributeNotFilterable {
-                        attribute: fid.value(),
-                        filterable_fields: filterable_fields.clone(),
-                    }))?
+                    Err(attribute_not_filterable(fid, filterable_fields))?
                 }
             }
             FilterCondition::Condition { fid, op } => {
                 if crate::is_faceted(fid.value(), filterable_fields) {
-                    let field_ids_map = index.fields_ids_map(rtxn)?;
-                    if let Some(fid) = field_ids_map.id(fid.value()) {
-                        Self::evaluate_operator(rtxn, index, fid, op)
+                    if let Some(fid) = settings.fields_ids_map.id(fid.value()) {
+                        Self::evaluate_operator(rtxn, index, settings, fid, universe, op)
                     } else {
                         Ok(RoaringBitmap::new())
                     }
                 } else {
-                    match fid.lexeme() {
-                        attribute @ "_geo" => {
-                            Err(fid.as_external_error(FilterError::BadGeo(attribute)))?
-                        }
-                        attribute if attribute.starts_with("_geoPoint(") => {
-                            Err(fid.as_external_error(FilterError::BadGeo("_geoPoint")))?
-                        }
-                        attribute @ "_geoDistance" => {
-                            Err(fid.as_external_error(FilterError::Reserved(attribute)))?
-                        }
-                        attribute => {
-                            Err(fid.as_external_error(FilterError::AttributeNotFilterable {
-                                attribute,
-                                filterable_fields: filterable_fields.clone(),
-                            }))?
-                        }
-                    }
+                    Err(attribute_not_filterable(fid, filterable_fields))?
                 }
             }
             FilterCondition::Or(subfilters) => {
                 let mut bitmap = RoaringBitmap::new();
-                for f in subfilters {
-                    bitmap |=
-                        Self::inner_evaluate(&(f.clone()).into(), rtxn, index, filterable_fields)?;
+                for (i, f) in subfilters.iter().enumerate() {
+                    bitmap |= Self::inner_evaluate(
+                        &(f.clone()).into(),
+                        rtxn,
+                        index,
+                        settings,
+                        universe,
+                        estimate.map(|estimate| &estimate.subfilters[i]),
+                        cache.map(|cache| cache.subfilter(i)),
+                    )?;
                 }
                 Ok(bitmap)
             }
             FilterCondition::And(subfilters) => {
-                let mut subfilters_iter = subfilters.iter();
-                if let Some(first_subfilter) = subfilters_iter.next() {
-                    let mut bitmap = Self::inner_evaluate(
-                        &(first_subfilter.clone()).into(),
+                // We evaluate the most selective sub-filters first, the costly ones
+                // are evaluated last and only against the already intersected documents.
+                let computed_estimate;
+                let estimate = match estimate {
+                    Some(estimate) => estimate,
+                    None => {
+                        computed_estimate =
+                            Self::estimate_cardinality(rtxn, index, settings, &self.condition)?;
+                        &computed_estimate
+                    }
+                };
+                let mut subfilters: Vec<_> =
+                    subfilters.iter().zip(&estimate.subfilters).enumerate().collect();
+                subfilters.sort_by_key(|(_, (_, estimate))| estimate.count);
+
+                let mut bitmap: Option<RoaringBitmap> = None;
+                for (i, (f, estimate)) in subfilters {
+                    let docids = Self::inner_evaluate(
+                        &(f.clone()).into(),
                         rtxn,
                         index,
-                        filterable_fields,
+                        settings,
+                        bitmap.as_ref().or(universe),
+                        Some(estimate),
+                        cache.map(|cache| cache.subfilter(i)),
                     )?;
-                    for f in subfilters_iter {
-                        if bitmap.is_empty() {
-                            return Ok(bitmap);
-                        }
-                        bitmap &= Self::inner_evaluate(
-                            &(f.clone()).into(),
-                            rtxn,
-                            index,
-                            filterable_fields,
-                        )?;
+                    let docids = match bitmap {
+                        Some(bitmap) => bitmap & docids,
+                        None => docids,
+                    };
+                    if docids.is_empty() {
+                        return Ok(docids);
                     }
-                    Ok(bitmap)
-                } else {
-                    Ok(RoaringBitmap::new())
+                    bitmap = Some(docids);
                 }
+                Ok(bitmap.unwrap_or_default())
             }
             FilterCondition::GeoLowerThan { point, radius } => {
                 if filterable_fields.contains("_geo") {
-                    let base_point: [f64; 2] =
-                        [point[0].parse_finite_float()?, point[1].parse_finite_float()?];
-                    if !(-90.0..=90.0).contains(&base_point[0]) {
-                        return Err(
-                            point[0].as_external_error(FilterError::BadGeoLat(base_point[0]))
-                        )?;
-                    }
-                    if !(-180.0..=180.0).contains(&base_point[1]) {
-                        return Err(
-                            point[1].as_external_error(FilterError::BadGeoLng(base_point[1]))
-                        )?;
-                    }
+                    let base_point = parse_geo_point(point)?;
                     let radius = radius.parse_finite_float()?;
                     let rtree = match index.geo_rtree(rtxn)? {
                         Some(rtree) => rtree,
@@ -385,7 +754,326 @@ impl<'a> Filter<'a> {
                     }))?
                 }
             }
+            FilterCondition::GeoBoundingBox { top_right_point, bottom_left_point } => {
+                if filterable_fields.contains("_geo") {
+                    let top_right = parse_geo_point(top_right_point)?;
+                    let bottom_left = parse_geo_point(bottom_left_point)?;
+                    if top_right[0] < bottom_left[0] {
+                        return Err(top_right_point[0].as_external_error(
+                            FilterError::BadGeoBoundingBoxTopIsBelowBottom(
+                                top_right[0],
+                                bottom_left[0],
+                            ),
+                        ))?;
+                    }
+
+                    let rtree = match index.geo_rtree(rtxn)? {
+                        Some(rtree) => rtree,
+                        None => return Ok(RoaringBitmap::new()),
+                    };
+
+                    // The z coordinate only depends on the latitude, we use it to only
+                    // retrieve the points between the two latitudes from the rtree.
+                    let envelope = AABB::from_corners(
+                        [-1.0, -1.0, lat_lng_to_xyz(&[bottom_left[0], 0.0])[2]],
+                        [1.0, 1.0, lat_lng_to_xyz(&[top_right[0], 0.0])[2]],
+                    );
+
+                    // When the left longitude is greater than the right one the
+                    // bounding box crosses the antimeridian.
+                    let crosses_antimeridian = bottom_left[1] > top_right[1];
+                    let result = rtree
+                        .locate_in_envelope(&envelope)
+                        .filter(|point| {
+                            let [lat, lng] = point.data.1;
+                            let lat_matches = (bottom_left[0]..=top_right[0]).contains(&lat);
+                            let lng_matches = if crosses_antimeridian {
+                                lng >= bottom_left[1] || lng <= top_right[1]
+                            } else {
+                                (bottom_left[1]..=top_right[1]).contains(&lng)
+                            };
+                            lat_matches && lng_matches
+                        })
+                        .map(|point| point.data.0)
+                        .collect();
+
+                    Ok(result)
+                } else {
+                    Err(top_right_point[0].as_external_error(
+                        FilterError::AttributeNotFilterable {
+                            attribute: "_geo",
+                            filterable_fields: filterable_fields.clone(),
+                        },
+                    ))?
+                }
+            }
+            FilterCondition::GeoPolygon { points } => {
+                if filterable_fields.contains("_geo") {
+                    let polygon = points.iter().map(parse_geo_point).collect::<Result<Vec<_>>>()?;
+
+                    let rtree = match index.geo_rtree(rtxn)? {
+                        Some(rtree) => rtree,
+                        None => return Ok(RoaringBitmap::new()),
+                    };
+
+                    // We first only retrieve the points in the bounding box of
+                    // the polygon and then check if they are in the polygon itself.
+                    let (mut min_lat, mut max_lat) = (f64::INFINITY, f64::NEG_INFINITY);
+                    let (mut min_lng, mut max_lng) = (f64::INFINITY, f64::NEG_INFINITY);
+                    for [lat, lng] in &polygon {
+                        min_lat = min_lat.min(*lat);
+                        max_lat = max_lat.max(*lat);
+                        min_lng = min_lng.min(*lng);
+                        max_lng = max_lng.max(*lng);
+                    }
+                    let envelope = AABB::from_corners(
+                        [-1.0, -1.0, lat_lng_to_xyz(&[min_lat, 0.0])[2]],
+                        [1.0, 1.0, lat_lng_to_xyz(&[max_lat, 0.0])[2]],
+                    );
+
+                    let result = rtree
+                        .locate_in_envelope(&envelope)
+                        .filter(|point| {
+                            let [lat, lng] = point.data.1;
+                            (min_lat..=max_lat).contains(&lat)
+                                && (min_lng..=max_lng).contains(&lng)
+                                && polygon_contains(&polygon, &point.data.1)
+                        })
+                        .map(|point| point.data.0)
+                        .collect();
+
+                    Ok(result)
+                } else {
+                    Err(points[0][0].as_external_error(FilterError::AttributeNotFilterable {
+                        attribute: "_geo",
+                        filterable_fields: filterable_fields.clone(),
+                    }))?
+                }
+            }
+        }
+    }
+}
+
+/// A bare `true` or `false` is a boolean, the quoted
+/// `"true"` and `"false"` are matched as strings.
+fn parse_boolean(token: &Token) -> Option<bool> {
+    if token.is_quoted() {
+        None
+    } else {
+        token.value().parse().ok()
+    }
+}
+
+/// Parses a bare boolean when the field has boolean facet values. The fields indexed
+/// before the boolean facets existed have their booleans stored as strings instead.
+fn parse_field_boolean(
+    rtxn: &heed::RoTxn,
+    index: &Index,
+    field_id: FieldId,
+    token: &Token,
+) -> heed::Result<Option<bool>> {
+    match parse_boolean(token) {
+        Some(boolean) => {
+            let booleans = index.faceted_documents_ids(rtxn, field_id, FacetType::Boolean)?;
+            Ok((!booleans.is_empty()).then_some(boolean))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Parses a number or an RFC 3339 date, the dates are compared as timestamps
+/// as it is how they are stored in the facet number databases.
+fn parse_number_or_date<'t>(token: &'t Token) -> StdResult<f64, FPError<'t>> {
+    match token.parse_finite_float() {
+        Ok(number) => Ok(number),
+        Err(error) => match rfc3339_to_timestamp(token.value()) {
+            Some(timestamp) => Ok(timestamp),
+            // the infinite numbers are not reported as invalid values
+            None if token.value().parse::<f64>().is_ok() => Err(error),
+            None => Err(token.as_external_error(FilterError::NotNumberOrDate(token.value()))),
+        },
+    }
+}
+
+/// Parses the latitude and the longitude of a point and ensures that they are valid coordinates.
+fn parse_geo_point(point: &[Token; 2]) -> Result<[f64; 2]> {
+    let lat_lng = [point[0].parse_finite_float()?, point[1].parse_finite_float()?];
+    if !(-90.0..=90.0).contains(&lat_lng[0]) {
+        return Err(point[0].as_external_error(FilterError::BadGeoLat(lat_lng[0])))?;
+    }
+    if !(-180.0..=180.0).contains(&lat_lng[1]) {
+        return Err(point[1].as_external_error(FilterError::BadGeoLng(lat_lng[1])))?;
+    }
+    Ok(lat_lng)
+}
+
+/// Returns the error raised when filtering on an attribute that isn't filterable.
+fn attribute_not_filterable<'t>(
+    fid: &Token<'t>,
+    filterable_fields: &HashSet<String>,
+) -> FPError<'t> {
+    match fid.value() {
+        attribute @ "_geo" => fid.as_external_error(FilterError::BadGeo(attribute)),
+        attribute if attribute.starts_with("_geoPoint(") => {
+            fid.as_external_error(FilterError::BadGeo("_geoPoint"))
+        }
+        attribute @ "_geoDistance" => fid.as_external_error(FilterError::Reserved(attribute)),
+        attribute => fid.as_external_error(FilterError::AttributeNotFilterable {
+            attribute,
+            filterable_fields: filterable_fields.clone(),
+        }),
+    }
+}
+
+/// Collects all the errors the evaluation of the filter could raise, see [`Filter::validate`].
+fn validate_condition<'t>(
+    condition: &'t FilterCondition,
+    filterable_fields: &HashSet<String>,
+    errors: &mut Vec<FPError<'t>>,
+) {
+    let geo_not_filterable = |token: &'t Token| {
+        token.as_external_error(FilterError::AttributeNotFilterable {
+            attribute: "_geo",
+            filterable_fields: filterable_fields.clone(),
+        })
+    };
+
+    match condition {
+        FilterCondition::Not(filter) => validate_condition(filter, filterable_fields, errors),
+        FilterCondition::Condition { fid, op } => {
+            if !crate::is_faceted(fid.value(), filterable_fields) {
+                errors.push(attribute_not_filterable(fid, filterable_fields));
+            }
+            match op {
+                Condition::GreaterThan(value)
+                | Condition::GreaterThanOrEqual(value)
+                | Condition::LowerThan(value)
+                | Condition::LowerThanOrEqual(value) => {
+                    validate_number_or_date(value, errors);
+                }
+                Condition::Between { from, to } => {
+                    validate_number_or_date(from, errors);
+                    validate_number_or_date(to, errors);
+                }
+                _ => (),
+            }
+        }
+        FilterCondition::In { fid, .. } => {
+            if !crate::is_faceted(fid.value(), filterable_fields) {
+                errors.push(attribute_not_filterable(fid, filterable_fields));
+            }
+        }
+        FilterCondition::Or(subfilters) | FilterCondition::And(subfilters) => {
+            for filter in subfilters {
+                validate_condition(filter, filterable_fields, errors);
+            }
+        }
+        FilterCondition::GeoLowerThan { point, radius } => {
+            if !filterable_fields.contains("_geo") {
+                errors.push(geo_not_filterable(&point[0]));
+            }
+            validate_geo_point(point, errors);
+            if let Err(error) = radius.parse_finite_float() {
+                errors.push(error);
+            }
+        }
+        FilterCondition::GeoBoundingBox { top_right_point, bottom_left_point } => {
+            if !filterable_fields.contains("_geo") {
+                errors.push(geo_not_filterable(&top_right_point[0]));
+            }
+            let top_right = validate_geo_point(top_right_point, errors);
+            let bottom_left = validate_geo_point(bottom_left_point, errors);
+            if let (Some(top_right), Some(bottom_left)) = (top_right, bottom_left) {
+                if top_right[0] < bottom_left[0] {
+                    errors.push(top_right_point[0].as_external_error(
+                        FilterError::BadGeoBoundingBoxTopIsBelowBottom(
+                            top_right[0],
+                            bottom_left[0],
+                        ),
+                    ));
+                }
+            }
+        }
+        FilterCondition::GeoPolygon { points } => {
+            if !filterable_fields.contains("_geo") {
+                errors.push(geo_not_filterable(&points[0][0]));
+            }
+            for point in points {
+                validate_geo_point(point, errors);
+            }
+        }
+    }
+}
+
+/// Collects the error of a value that is neither a finite number nor an RFC 3339 date.
+fn validate_number_or_date<'t>(token: &'t Token, errors: &mut Vec<FPError<'t>>) {
+    if let Err(error) = parse_number_or_date(token) {
+        errors.push(error);
+    }
+}
+
+/// Collects the errors of the coordinates of a point and returns it if they are valid.
+fn validate_geo_point<'t>(
+    point: &'t [Token; 2],
+    errors: &mut Vec<FPError<'t>>,
+) -> Option<[f64; 2]> {
+    let (lat, lng) = match (point[0].parse_finite_float(), point[1].parse_finite_float()) {
+        (Ok(lat), Ok(lng)) => (lat, lng),
+        (lat, lng) => {
+            errors.extend(lat.err());
+            errors.extend(lng.err());
+            return None;
+        }
+    };
+
+    let mut valid = true;
+    if !(-90.0..=90.0).contains(&lat) {
+        errors.push(point[0].as_external_error(FilterError::BadGeoLat(lat)));
+        valid = false;
+    }
+    if !(-180.0..=180.0).contains(&lng) {
+        errors.push(point[1].as_external_error(FilterError::BadGeoLng(lng)));
+        valid = false;
+    }
+    if valid {
+        Some([lat, lng])
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if the point is inside the polygon, the latitudes and longitudes
+/// are handled as the coordinates of a plane, the polygon doesn't need to be closed.
+///
+/// It is a ray casting, an horizontal ray is thrown from the point and
+/// the point is inside the polygon if the ray crosses an odd number of edges.
+fn polygon_contains(polygon: &[[f64; 2]], point: &[f64; 2]) -> bool {
+    let [lat, lng] = *point;
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &current in polygon {
+        let [lat_a, lng_a] = previous;
+        let [lat_b, lng_b] = current;
+        if (lat_a > lat) != (lat_b > lat)
+            && lng < (lng_b - lng_a) * (lat - lat_a) / (lat_b - lat_a) + lng_a
+        {
+            inside = !inside;
+        }
+        previous = current;
+    }
+    inside
+}
+
+/// Returns `true` if the documents ids of the filter evaluated against a universe
+/// are restricted to it, they are the complements of other documents ids.
+fn depends_on_universe(condition: &FilterCondition) -> bool {
+    match condition {
+        FilterCondition::Not(_) => true,
+        FilterCondition::Condition { op: Condition::NotEqual(_), .. } => true,
+        FilterCondition::Or(subfilters) | FilterCondition::And(subfilters) => {
+            subfilters.iter().any(depends_on_universe)
         }
+        _ => false,
     }
 }
 
@@ -395,8 +1083,28 @@ impl<'a> From<FilterCondition<'a>> for Filter<'a> {
     }
 }
 
+impl<'a> From<&Filter<'a>> for OwnedFilterCondition {
+    fn from(f: &Filter<'a>) -> Self {
+        (&f.condition).into()
+    }
+}
+
+/// The owned filters are checked for their depth like the filters parsed from a string.
+impl<'a> TryFrom<&'a OwnedFilterCondition> for Filter<'a> {
+    type Error = Error;
+
+    fn try_from(ofc: &'a OwnedFilterCondition) -> Result<Self> {
+        let condition = FilterCondition::from(ofc);
+        if let Some(token) = condition.token_at_depth(MAX_FILTER_DEPTH) {
+            return Err(token.as_external_error(FilterError::TooDeep).into());
+        }
+        Ok(Self { condition })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
     use std::fmt::Write;
     use std::iter::FromIterator;
 
@@ -405,22 +1113,43 @@ mod tests {
     use maplit::hashset;
     use roaring::RoaringBitmap;
 
+    use super::{EvaluationSettings, MAX_FILTER_DEPTH};
     use crate::index::tests::TempIndex;
-    use crate::Filter;
+    use crate::{
+        Condition, Filter, FilterCondition, FilterValue, Index, OwnedFilterCondition, Token,
+    };
 
-    #[test]
-    fn empty_db() {
+    /// Creates an empty index with the given filterable fields.
+    fn filterable_index(fields: &[&str]) -> TempIndex {
         let index = TempIndex::new();
-        //Set the filterable fields to be the channel.
         index
             .update_settings(|settings| {
-                settings.set_filterable_fields(hashset! { S("PrIcE") });
+                settings
+                    .set_filterable_fields(fields.iter().map(|field| field.to_string()).collect());
             })
             .unwrap();
+        index
+    }
 
-        let rtxn = index.read_txn().unwrap();
+    /// Returns the ids of the documents matching the filter.
+    fn evaluate(rtxn: &heed::RoTxn, index: &Index, filter: &str) -> Vec<u32> {
+        let filter = Filter::from_str(filter).unwrap().unwrap();
+        filter.evaluate(rtxn, index).unwrap().into_iter().collect()
+    }
 
-        let filter = Filter::from_str("PrIcE < 1000").unwrap().unwrap();
+    #[test]
+    fn empty_db() {
+        let index = TempIndex::new();
+        //Set the filterable fields to be the channel.
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("PrIcE") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        let filter = Filter::from_str("PrIcE < 1000").unwrap().unwrap();
         let bitmap = filter.evaluate(&rtxn, &index).unwrap();
         assert!(bitmap.is_empty());
 
@@ -491,6 +1220,157 @@ mod tests {
         assert_eq!(condition, expected);
     }
 
+    #[test]
+    fn params() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("price"), S("brand") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 10, "brand": "nike" },
+                { "id": 1, "price": 200, "brand": "adidas" },
+                { "id": 2, "price": 300, "brand": "O'Neil \"AND\" 1 = 1" },
+                { "id": 3, "price": 400, "brand": "puma" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        let evaluate = |filter: &str, params: &[FilterValue]| -> Vec<u32> {
+            let filter = Filter::from_str_with_params(filter, params).unwrap().unwrap();
+            filter.evaluate(&rtxn, &index).unwrap().into_iter().collect()
+        };
+
+        assert_eq!(
+            evaluate("price > ? AND brand IN ?", &[100.into(), vec!["nike", "adidas"].into()]),
+            vec![1]
+        );
+        assert_eq!(evaluate("brand = ?", &["O'Neil \"AND\" 1 = 1".into()]), vec![2]);
+        assert_eq!(evaluate("brand = ?", &["nike OR brand = puma".into()]), Vec::<u32>::new());
+        assert_eq!(evaluate("price ? TO ?", &[200.into(), 300.5.into()]), vec![1, 2]);
+        assert_eq!(evaluate("brand NOT IN ?", &[vec!["nike", "puma"].into()]), vec![1, 2]);
+
+        let error =
+            Filter::from_str_with_params("price > ? AND brand = ?", &[100.into()]).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Expected 2 parameters to bind to the `?` placeholders of the filter but received 1."
+        ));
+
+        let error = Filter::from_str_with_params("price > ?", &["cheap".into()])
+            .unwrap()
+            .unwrap()
+            .evaluate(&rtxn, &index)
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Invalid value `cheap`, expected a number or an RFC 3339 date like `2023-01-01T00:00:00Z`."
+            ),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn owned_filter() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("price"), S("brand") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 10, "brand": "nike" },
+                { "id": 1, "price": 200, "brand": "adidas" },
+                { "id": 2, "price": 300, "brand": "Bernese \"Mountain\"" },
+            ]))
+            .unwrap();
+
+        let owned = {
+            let expression =
+                String::from("price > 100 AND brand IN [adidas, \"Bernese \\\"Mountain\\\"\"]");
+            let filter = Filter::from_str(&expression).unwrap().unwrap();
+            OwnedFilterCondition::from(&filter)
+        };
+
+        // the owned filter outlives the expression and can be sent to another thread
+        let json =
+            std::thread::spawn(move || serde_json::to_string(&owned).unwrap()).join().unwrap();
+        let owned: OwnedFilterCondition = serde_json::from_str(&json).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::try_from(&owned).unwrap();
+        let docids: Vec<u32> = filter.evaluate(&rtxn, &index).unwrap().into_iter().collect();
+        assert_eq!(docids, vec![1, 2]);
+
+        // the owned filters are checked for their depth when they are converted back
+        let mut owned = owned;
+        for _ in 0..MAX_FILTER_DEPTH {
+            owned = OwnedFilterCondition::And(vec![owned]);
+        }
+        let error = Filter::try_from(&owned).unwrap_err();
+        assert!(error.to_string().starts_with("Too many filter conditions"));
+    }
+
+    #[test]
+    fn and_selectivity() {
+        let index = filterable_index(&["price", "brand"]);
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 10, "brand": "nike" },
+                { "id": 1, "price": 20, "brand": "nike" },
+                { "id": 2, "price": 30, "brand": "adidas" },
+                { "id": 3, "price": 40, "brand": "puma" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let settings = EvaluationSettings::new(&rtxn, &index).unwrap();
+
+        let estimate = |filter: &str| -> u64 {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            Filter::estimate_cardinality(&rtxn, &index, &settings, &filter.condition).unwrap().count
+        };
+
+        assert_eq!(estimate("brand = nike"), 2);
+        assert_eq!(estimate("brand = NIKE"), 2);
+        assert_eq!(estimate("price = 30"), 1);
+        assert_eq!(estimate("brand IN [nike, puma]"), 3);
+        assert_eq!(estimate("price > 15"), 4);
+        assert_eq!(estimate("brand != nike"), 4);
+        assert_eq!(estimate("NOT brand = nike"), 4);
+        assert_eq!(estimate("brand = nike OR brand = adidas"), 3);
+        assert_eq!(estimate("price > 15 AND brand = adidas"), 1);
+        assert_eq!(estimate("colour = red"), 0);
+
+        assert_eq!(
+            evaluate(&rtxn, &index, "NOT brand = nike AND price > 15 AND brand != puma"),
+            vec![2]
+        );
+        assert_eq!(
+            evaluate(&rtxn, &index, "brand != nike AND (brand = puma OR NOT price < 35)"),
+            vec![3]
+        );
+        assert_eq!(
+            evaluate(&rtxn, &index, "NOT (brand = nike AND NOT price = 10) AND price < 35"),
+            vec![0, 2]
+        );
+        assert!(evaluate(&rtxn, &index, "brand = reebok AND NOT price = 10").is_empty());
+
+        // the errors are returned even when another sub-filter matches no document
+        let filter = Filter::from_str("price > 1000 AND colour = red").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error.to_string().starts_with("Attribute `colour` is not filterable."));
+    }
+
     #[test]
     fn not_filterable() {
         let index = TempIndex::new();
@@ -531,6 +1411,66 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn validate() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("price"), S("_geo") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        let filter =
+            Filter::from_str("price > 10 AND price 1 TO 2022-01-01T00:00:00Z").unwrap().unwrap();
+        assert!(filter.validate(&rtxn, &index).unwrap().is_empty());
+
+        let filter = Filter::from_str(
+            "price > NaN AND (brand = nike OR color IN [red]) AND _geoRadius(100, 200, 10) AND _geoBoundingBox([1, 2], [3, 4])",
+        )
+        .unwrap()
+        .unwrap();
+        let errors = filter.validate(&rtxn, &index).unwrap();
+        let errors: Vec<_> =
+            errors.iter().map(|error| (*error.context().fragment(), error.to_string())).collect();
+        assert_eq!(errors.len(), 6, "{:#?}", errors);
+        assert_eq!(errors[0].0, "NaN");
+        assert!(errors[0].1.starts_with("Non finite floats are not supported"));
+        assert_eq!(errors[1].0, "brand");
+        assert!(errors[1].1.starts_with("Attribute `brand` is not filterable."));
+        assert_eq!(errors[2].0, "color");
+        assert!(errors[2].1.starts_with("Attribute `color` is not filterable."));
+        assert_eq!(errors[3].0, "100");
+        assert!(errors[3].1.starts_with("Bad latitude `100`."));
+        assert_eq!(errors[4].0, "200");
+        assert!(errors[4].1.starts_with("Bad longitude `200`."));
+        assert_eq!(errors[5].0, "1");
+        assert!(errors[5].1.starts_with("The top latitude `1` is below the bottom latitude `3`."));
+
+        // the filters that are not parsed from a string are not checked for their depth
+        let mut condition = FilterCondition::Condition {
+            fid: Token::from("price"),
+            op: Condition::Equal(Token::from("1")),
+        };
+        for _ in 0..MAX_FILTER_DEPTH {
+            condition = FilterCondition::And(vec![condition]);
+        }
+        let filter = Filter::from(condition);
+        let errors = filter.validate(&rtxn, &index).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().starts_with("Too many filter conditions"));
+
+        // the `IN` conditions raise the same errors when they are validated and evaluated
+        let filter = Filter::from_str("_geoDistance IN [1, 2]").unwrap().unwrap();
+        let errors = filter.validate(&rtxn, &index).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().starts_with("`_geoDistance` is a reserved keyword"));
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert_eq!(error.to_string(), errors[0].to_string());
+    }
+
     #[test]
     fn escaped_quote_in_filter_value_2380() {
         let index = TempIndex::new();
@@ -579,6 +1519,256 @@ mod tests {
         assert_eq!(documents_ids, vec![2]);
     }
 
+    #[test]
+    fn starts_with_and_contains() {
+        let index = filterable_index(&["sku", "tags"]);
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "sku": "AB-123", "tags": ["Blue", "Light"] },
+                { "id": 1, "sku": "ab-456", "tags": "dark blue" },
+                { "id": 2, "sku": "ABC-789", "tags": ["red"] },
+                { "id": 3, "sku": "XAB-000", "tags": [] },
+                { "id": 4, "sku": 12 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        assert_eq!(evaluate(&rtxn, &index, "sku STARTS WITH \"AB-\""), vec![0, 1]);
+        assert_eq!(evaluate(&rtxn, &index, "sku STARTS WITH ab"), vec![0, 1, 2]);
+        assert_eq!(evaluate(&rtxn, &index, "sku STARTS WITH AB-1"), vec![0]);
+        assert_eq!(evaluate(&rtxn, &index, "sku STARTS WITH 1"), Vec::<u32>::new());
+        assert_eq!(evaluate(&rtxn, &index, "sku NOT STARTS WITH AB"), vec![3, 4]);
+        assert_eq!(evaluate(&rtxn, &index, "tags STARTS WITH bl"), vec![0]);
+
+        assert_eq!(evaluate(&rtxn, &index, "sku CONTAINS AB"), vec![0, 1, 2, 3]);
+        assert_eq!(evaluate(&rtxn, &index, "sku CONTAINS 00"), vec![3]);
+        assert_eq!(evaluate(&rtxn, &index, "tags CONTAINS blue"), vec![0, 1]);
+        assert_eq!(evaluate(&rtxn, &index, "tags CONTAINS 'k b'"), vec![1]);
+        assert_eq!(evaluate(&rtxn, &index, "tags NOT CONTAINS blue"), vec![2, 3, 4]);
+
+        let filter = Filter::from_str("title CONTAINS blue").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error.to_string().starts_with("Attribute `title` is not filterable."));
+        assert!(error.to_string().contains("1:6 title CONTAINS blue"), "{}", error);
+    }
+
+    #[test]
+    fn dates() {
+        let index = filterable_index(&["published_at", "updated_at"]);
+        index
+            .update_settings(|settings| {
+                settings.set_date_fields(hashset! { S("published_at") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                {
+                    "id": 0,
+                    "published_at": "2022-06-15T12:00:00Z",
+                    "updated_at": "2022-06-15T12:00:00Z"
+                },
+                { "id": 1, "published_at": "2023-01-01T00:00:00Z" },
+                { "id": 2, "published_at": "2023-01-01T01:30:00+02:00" },
+                { "id": 3, "published_at": ["2021-01-01T00:00:00Z", "2024-01-01T00:00:00Z"] },
+                { "id": 4, "published_at": "yesterday" },
+                { "id": 5, "published_at": 1672531200 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        assert_eq!(evaluate(&rtxn, &index, "published_at > 2023-01-01T00:00:00Z"), vec![3]);
+        assert_eq!(evaluate(&rtxn, &index, "published_at >= 2023-01-01T00:00:00Z"), vec![1, 3, 5]);
+        assert_eq!(evaluate(&rtxn, &index, "published_at < 2023-01-01T00:00:00Z"), vec![0, 2, 3]);
+        assert_eq!(evaluate(&rtxn, &index, "published_at = 2023-01-01T00:00:00Z"), vec![1, 5]);
+        assert_eq!(
+            evaluate(&rtxn, &index, "published_at = '2023-01-01T01:00:00+01:00'"),
+            vec![1, 5]
+        );
+        assert_eq!(
+            evaluate(
+                &rtxn,
+                &index,
+                "published_at 2022-01-01T00:00:00Z TO 2022-12-31T23:59:59.999Z"
+            ),
+            vec![0, 2]
+        );
+        assert_eq!(evaluate(&rtxn, &index, "published_at = yesterday"), vec![4]);
+
+        // the dates of the fields which are not date fields are only strings
+        assert!(evaluate(&rtxn, &index, "updated_at < 2023-01-01T00:00:00Z").is_empty());
+        assert_eq!(evaluate(&rtxn, &index, "updated_at = '2022-06-15T12:00:00Z'"), vec![0]);
+
+        let filter = Filter::from_str("published_at > 2023-13-01T00:00:00Z").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Invalid value `2023-13-01T00:00:00Z`, expected a number or an RFC 3339 date"
+            ),
+            "{}",
+            error
+        );
+        drop(rtxn);
+
+        // the documents are reindexed when the date fields change
+        index
+            .update_settings(|settings| {
+                settings.set_date_fields(hashset! { S("published_at"), S("updated_at") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("updated_at < 2023-01-01T00:00:00Z").unwrap().unwrap();
+        let docids: Vec<u32> = filter.evaluate(&rtxn, &index).unwrap().into_iter().collect();
+        assert_eq!(docids, vec![0]);
+    }
+
+    #[test]
+    fn case_sensitive_facets() {
+        let index = filterable_index(&["code", "name"]);
+        index
+            .update_settings(|settings| {
+                settings.set_case_sensitive_facets(hashset! { S("code") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "code": "aB1", "name": "aB1" },
+                { "id": 1, "code": "ab1", "name": "ab1" },
+                { "id": 2, "code": " AB1 ", "name": " AB1 " },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        assert_eq!(evaluate(&rtxn, &index, "code = aB1"), vec![0]);
+        assert_eq!(evaluate(&rtxn, &index, "code = ab1"), vec![1]);
+        assert_eq!(evaluate(&rtxn, &index, "code = AB1"), Vec::<u32>::new());
+        assert_eq!(evaluate(&rtxn, &index, "code = ' AB1 '"), vec![2]);
+        assert_eq!(evaluate(&rtxn, &index, "code IN [aB1, ab1]"), vec![0, 1]);
+        assert_eq!(evaluate(&rtxn, &index, "code STARTS WITH a"), vec![0, 1]);
+        assert_eq!(evaluate(&rtxn, &index, "code CONTAINS B"), vec![0, 2]);
+        assert_eq!(evaluate(&rtxn, &index, "name = AB1"), vec![0, 1, 2]);
+
+        let distribution = crate::FacetDistribution::new(&rtxn, &index)
+            .facets(vec!["code", "name"])
+            .execute()
+            .unwrap();
+        assert_eq!(distribution["code"].len(), 3);
+        assert_eq!(distribution["name"].len(), 1);
+        assert_eq!(distribution["name"].values().sum::<u64>(), 3);
+    }
+
+    #[test]
+    fn booleans() {
+        let index = filterable_index(&["available"]);
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "available": true },
+                { "id": 1, "available": false },
+                { "id": 2, "available": [true, false] },
+                { "id": 3, "available": "true" },
+                { "id": 4 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        assert_eq!(evaluate(&rtxn, &index, "available = true"), vec![0, 2]);
+        assert_eq!(evaluate(&rtxn, &index, "available = false"), vec![1, 2]);
+        assert_eq!(evaluate(&rtxn, &index, "available != true"), vec![1, 3, 4]);
+        assert_eq!(evaluate(&rtxn, &index, "available IN [true]"), vec![0, 2]);
+        // a quoted boolean is a string
+        assert_eq!(evaluate(&rtxn, &index, "available = 'true'"), vec![3]);
+        assert_eq!(evaluate(&rtxn, &index, "available IN [\"true\", false]"), vec![1, 2, 3]);
+
+        let fid = index.fields_ids_map(&rtxn).unwrap().id("available").unwrap();
+        let docids: Vec<u32> =
+            index.bool_faceted_documents_ids(&rtxn, fid, true).unwrap().into_iter().collect();
+        assert_eq!(docids, vec![0, 2]);
+
+        let mut distribution = crate::FacetDistribution::new(&rtxn, &index);
+        distribution.facets(vec!["available"]);
+        // the booleans are summed with the strings of the same name
+        let values = distribution.execute().unwrap();
+        assert_eq!(values["available"].len(), 2);
+        assert_eq!(values["available"]["true"], 3);
+        assert_eq!(values["available"]["false"], 2);
+        let booleans = distribution.execute_booleans().unwrap();
+        assert_eq!(booleans["available"][&true], 2);
+        assert_eq!(booleans["available"][&false], 2);
+
+        // the documents are removed from the boolean database when they are deleted
+        drop(rtxn);
+        index.delete_document("2");
+        let rtxn = index.read_txn().unwrap();
+        let docids: Vec<u32> =
+            index.bool_faceted_documents_ids(&rtxn, fid, false).unwrap().into_iter().collect();
+        assert_eq!(docids, vec![1]);
+    }
+
+    #[test]
+    fn booleans_stored_as_strings() {
+        let index = filterable_index(&["available"]);
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "available": "true" },
+                { "id": 1, "available": "false" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // the field has no boolean values, a bare boolean is matched as a string
+        assert_eq!(evaluate(&rtxn, &index, "available = true"), vec![0]);
+        assert_eq!(evaluate(&rtxn, &index, "available != true"), vec![1]);
+    }
+
+    #[test]
+    fn null_and_empty() {
+        let index = filterable_index(&["tags"]);
+
+        index
+            .add_documents(documents!([
+                { "id": 0 },
+                { "id": 1, "tags": null },
+                { "id": 2, "tags": "" },
+                { "id": 3, "tags": [] },
+                { "id": 4, "tags": {} },
+                { "id": 5, "tags": "blue" },
+                { "id": 6, "tags": [null] },
+                { "id": 7, "tags": 0 },
+                { "id": 8, "tags": { "color": "blue" } },
+                { "id": 9, "tags": [{ "color": "red" }, { "size": 1 }] },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        assert_eq!(evaluate(&rtxn, &index, "tags IS NULL"), vec![1]);
+        assert_eq!(evaluate(&rtxn, &index, "tags IS NOT NULL"), vec![0, 2, 3, 4, 5, 6, 7, 8, 9]);
+        // the non-empty objects and arrays of objects are not empty once flattened
+        assert_eq!(evaluate(&rtxn, &index, "tags IS EMPTY"), vec![2, 3, 4]);
+        assert_eq!(evaluate(&rtxn, &index, "tags IS NOT EMPTY"), vec![0, 1, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            evaluate(&rtxn, &index, "tags EXISTS AND tags IS NOT NULL AND tags IS NOT EMPTY"),
+            vec![5, 6, 7, 8, 9]
+        );
+
+        // the documents are removed from the databases when they are updated
+        index.add_documents(documents!([{ "id": 1, "tags": "red" }, { "id": 2 }])).unwrap();
+        drop(rtxn);
+        let rtxn = index.read_txn().unwrap();
+        assert!(evaluate(&rtxn, &index, "tags IS NULL").is_empty());
+        assert_eq!(evaluate(&rtxn, &index, "tags IS EMPTY"), vec![3, 4]);
+    }
+
     #[test]
     fn zero_radius() {
         let index = TempIndex::new();
@@ -675,6 +1865,192 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn geo_bounding_box() {
+        let index = filterable_index(&["_geo"]);
+
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "Paris", "_geo": { "lat": 48.8566, "lng": 2.3522 } },
+                { "id": 2, "name": "Lyon", "_geo": { "lat": 45.764, "lng": 4.8357 } },
+                { "id": 3, "name": "Madrid", "_geo": { "lat": 40.4168, "lng": -3.7038 } },
+                { "id": 4, "name": "Suva", "_geo": { "lat": -18.1416, "lng": 178.4419 } },
+                { "id": 5, "name": "Apia", "_geo": { "lat": -13.8507, "lng": -171.7514 } },
+                { "id": 6, "name": "Sydney", "_geo": { "lat": -33.8688, "lng": 151.2093 } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // France
+        assert_eq!(
+            evaluate(&rtxn, &index, "_geoBoundingBox([51.1, 9.6], [42.3, -5.2])"),
+            vec![0, 1]
+        );
+        assert_eq!(evaluate(&rtxn, &index, "_geoBoundingBox([51.1, 9.6], [42.3, 3])"), vec![1]);
+        // the whole western europe
+        assert_eq!(evaluate(&rtxn, &index, "_geoBoundingBox([60, 10], [35, -10])"), vec![0, 1, 2]);
+        // the points on the edges are included
+        assert_eq!(
+            evaluate(&rtxn, &index, "_geoBoundingBox([48.8566, 2.3522], [45.764, 2.3522])"),
+            vec![0]
+        );
+        // crossing the antimeridian
+        assert_eq!(evaluate(&rtxn, &index, "_geoBoundingBox([-10, -170], [-20, 170])"), vec![3, 4]);
+        assert_eq!(
+            evaluate(&rtxn, &index, "_geoBoundingBox([-10, -170], [-40, 150])"),
+            vec![3, 4, 5]
+        );
+        // not crossing the antimeridian
+        assert_eq!(evaluate(&rtxn, &index, "_geoBoundingBox([-10, 180], [-40, 150])"), vec![3, 5]);
+        // the whole world
+        assert_eq!(
+            evaluate(&rtxn, &index, "_geoBoundingBox([90, 180], [-90, -180])"),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            evaluate(&rtxn, &index, "NOT _geoBoundingBox([60, 10], [35, -10])"),
+            vec![3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn geo_bounding_box_error() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_searchable_fields(vec![S("_geo"), S("price")]); // to keep the fields order
+                settings.set_filterable_fields(hashset! { S("_geo"), S("price") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // top right have a bad latitude
+        let filter =
+            Filter::from_str("_geoBoundingBox([-90.0000001, 150], [30, 10])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Bad latitude `-90.0000001`. Latitude must be contained between -90 and 90 degrees."
+            ),
+            "{}",
+            error.to_string()
+        );
+
+        // bottom left have a bad longitude
+        let filter =
+            Filter::from_str("_geoBoundingBox([30, 10], [-30, 180.000001])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Bad longitude `180.000001`. Longitude must be contained between -180 and 180 degrees."
+            ),
+            "{}",
+            error.to_string()
+        );
+
+        // the top is below the bottom
+        let filter = Filter::from_str("_geoBoundingBox([-10, 10], [10, -10])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("The top latitude `-10` is below the bottom latitude `10`."),
+            "{}",
+            error.to_string()
+        );
+
+        // _geo is not filterable
+        let filter = Filter::from_str("_geoBoundingBox([10, 10], [-10, -10])").unwrap().unwrap();
+        let index = TempIndex::new();
+        let rtxn = index.read_txn().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(
+            error.to_string().starts_with("Attribute `_geo` is not filterable."),
+            "{}",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn geo_polygon() {
+        let index = filterable_index(&["_geo"]);
+
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "Paris", "_geo": { "lat": 48.8566, "lng": 2.3522 } },
+                { "id": 2, "name": "Lyon", "_geo": { "lat": 45.764, "lng": 4.8357 } },
+                { "id": 3, "name": "Madrid", "_geo": { "lat": 40.4168, "lng": -3.7038 } },
+                { "id": 4, "name": "Bordeaux", "_geo": { "lat": 44.8378, "lng": -0.5792 } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // a triangle around Paris, Lyon and Bordeaux
+        assert_eq!(
+            evaluate(&rtxn, &index, "_geoPolygon([52, 0], [44, 10], [43, -3])"),
+            vec![0, 1, 3]
+        );
+        // Lyon is in the bounding box of the triangle but not in the triangle
+        assert_eq!(evaluate(&rtxn, &index, "_geoPolygon([50, 2], [50, 6], [44, -1])"), vec![0, 3]);
+        // a concave shape avoiding Lyon
+        assert_eq!(
+            evaluate(
+                &rtxn,
+                &index,
+                "_geoPolygon([50, -5], [50, 6], [47, 6], [47, 3], [40, 3], [40, -5])"
+            ),
+            vec![0, 2, 3]
+        );
+        assert_eq!(
+            evaluate(&rtxn, &index, "NOT _geoPolygon([52, 0], [44, 10], [43, -3])"),
+            vec![2]
+        );
+        assert_eq!(
+            evaluate(&rtxn, &index, "_geoPolygon([10, 10], [10, 20], [0, 20])"),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn geo_polygon_error() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        let filter =
+            Filter::from_str("_geoPolygon([10, 10], [10, 20], [-91, 20])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Bad latitude `-91`. Latitude must be contained between -90 and 90 degrees."
+            ),
+            "{}",
+            error.to_string()
+        );
+        assert!(error.to_string().contains("34:37 _geoPolygon"), "{}", error.to_string());
+
+        let filter =
+            Filter::from_str("_geoPolygon([10, 10], [10, 181], [0, 20])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Bad longitude `181`. Longitude must be contained between -180 and 180 degrees."
+            ),
+            "{}",
+            error.to_string()
+        );
+    }
+
     #[test]
     fn filter_depth() {
         // generates a big (2 MiB) filter with too much of ORs.
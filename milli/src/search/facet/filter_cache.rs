@@ -0,0 +1,235 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use roaring::RoaringBitmap;
+
+use crate::OwnedFilterCondition;
+
+/// A least recently used cache of the documents ids matching the filters and sub-filters.
+///
+/// The entries are keyed by the path and the generation of the index they were computed on,
+/// an update of the index makes all of its entries unreachable and they are evicted as the
+/// new ones are inserted. The cache can be shared between the searches and the threads.
+///
+/// The cache must not be used with write transactions that could be aborted.
+pub struct FilterCache {
+    capacity: usize,
+    inner: Mutex<FilterCacheInner>,
+}
+
+#[derive(Default)]
+struct FilterCacheInner {
+    /// The entries by index path, index generation and filter.
+    entries: HashMap<Arc<Path>, HashMap<u64, HashMap<Arc<OwnedFilterCondition>, FilterCacheEntry>>>,
+    /// The keys of the entries ordered by their last use, the least recently used first.
+    recency: BTreeMap<u64, FilterCacheKey>,
+    /// Incremented on every access, it orders the entries by their last use.
+    clock: u64,
+}
+
+struct FilterCacheKey {
+    index_path: Arc<Path>,
+    generation: u64,
+    filter: Arc<OwnedFilterCondition>,
+}
+
+struct FilterCacheEntry {
+    docids: RoaringBitmap,
+    last_used: u64,
+}
+
+impl FilterCacheInner {
+    fn get_mut(
+        &mut self,
+        index_path: &Path,
+        generation: u64,
+        filter: &OwnedFilterCondition,
+    ) -> Option<&mut FilterCacheEntry> {
+        self.entries.get_mut(index_path)?.get_mut(&generation)?.get_mut(filter)
+    }
+
+    /// Marks the entry last used at `last_used` as being used at `clock`.
+    fn touch(&mut self, last_used: u64, clock: u64) {
+        if let Some(key) = self.recency.remove(&last_used) {
+            self.recency.insert(clock, key);
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let last_used = match self.recency.keys().next() {
+            Some(&last_used) => last_used,
+            None => return,
+        };
+        let FilterCacheKey { index_path, generation, filter } =
+            self.recency.remove(&last_used).unwrap();
+
+        if let Some(generations) = self.entries.get_mut(&index_path) {
+            if let Some(filters) = generations.get_mut(&generation) {
+                filters.remove(&filter);
+                if filters.is_empty() {
+                    generations.remove(&generation);
+                }
+            }
+            if generations.is_empty() {
+                self.entries.remove(&index_path);
+            }
+        }
+    }
+}
+
+impl FilterCache {
+    /// Creates a cache that keeps at most `capacity` filters results.
+    pub fn new(capacity: usize) -> FilterCache {
+        FilterCache { capacity, inner: Mutex::new(FilterCacheInner::default()) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().recency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.entries.clear();
+        inner.recency.clear();
+    }
+
+    pub(crate) fn get(
+        &self,
+        index_path: &Path,
+        generation: u64,
+        filter: &OwnedFilterCondition,
+    ) -> Option<RoaringBitmap> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let entry = inner.get_mut(index_path, generation, filter)?;
+        let last_used = std::mem::replace(&mut entry.last_used, clock);
+        let docids = entry.docids.clone();
+        inner.touch(last_used, clock);
+        Some(docids)
+    }
+
+    pub(crate) fn insert(
+        &self,
+        index_path: &Path,
+        generation: u64,
+        filter: OwnedFilterCondition,
+        docids: RoaringBitmap,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        if let Some(entry) = inner.get_mut(index_path, generation, &filter) {
+            let last_used = std::mem::replace(&mut entry.last_used, clock);
+            entry.docids = docids;
+            inner.touch(last_used, clock);
+            return;
+        }
+
+        if inner.recency.len() >= self.capacity {
+            inner.evict_least_recently_used();
+        }
+
+        let index_path: Arc<Path> = match inner.entries.get_key_value(index_path) {
+            Some((path, _)) => path.clone(),
+            None => Arc::from(index_path),
+        };
+        let filter = Arc::new(filter);
+        inner
+            .entries
+            .entry(index_path.clone())
+            .or_default()
+            .entry(generation)
+            .or_default()
+            .insert(filter.clone(), FilterCacheEntry { docids, last_used: clock });
+        inner.recency.insert(clock, FilterCacheKey { index_path, generation, filter });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+    use crate::Filter;
+
+    #[test]
+    fn least_recently_used_eviction() {
+        let cache = FilterCache::new(2);
+        let path = Path::new("index");
+        let filter = |s: &str| OwnedFilterCondition::from(&Filter::from_str(s).unwrap().unwrap());
+        let docids = |ids: &[u32]| ids.iter().copied().collect::<RoaringBitmap>();
+
+        cache.insert(path, 0, filter("a = 1"), docids(&[1]));
+        cache.insert(path, 0, filter("b = 2"), docids(&[2]));
+        assert_eq!(cache.get(path, 0, &filter("a = 1")), Some(docids(&[1])));
+        assert_eq!(cache.get(path, 1, &filter("a = 1")), None);
+        assert_eq!(cache.get(Path::new("other"), 0, &filter("a = 1")), None);
+
+        // `b = 2` is the least recently used entry
+        cache.insert(path, 0, filter("c = 3"), docids(&[3]));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(path, 0, &filter("b = 2")), None);
+        assert_eq!(cache.get(path, 0, &filter("a = 1")), Some(docids(&[1])));
+        assert_eq!(cache.get(path, 0, &filter("c = 3")), Some(docids(&[3])));
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn invalidated_by_updates() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("tenant"), S("visible") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "tenant": "a", "visible": true },
+                { "id": 1, "tenant": "a", "visible": false },
+                { "id": 2, "tenant": "b", "visible": true },
+            ]))
+            .unwrap();
+
+        let cache = FilterCache::new(10);
+        let filter = Filter::from_str("tenant = a AND NOT visible = false").unwrap().unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let docids = filter.evaluate_with_cache(&rtxn, &index, &cache).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0]);
+        // the `NOT` is evaluated against the documents matching `tenant = a` and isn't cached
+        assert_eq!(cache.len(), 3);
+        let docids = filter.evaluate_with_cache(&rtxn, &index, &cache).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(cache.len(), 3);
+        drop(rtxn);
+
+        index.add_documents(documents!([{ "id": 3, "tenant": "a", "visible": true }])).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let docids = filter.evaluate_with_cache(&rtxn, &index, &cache).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0, 3]);
+        assert_eq!(cache.len(), 6);
+    }
+}
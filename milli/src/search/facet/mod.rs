@@ -3,16 +3,26 @@ pub use facet_sort_descending::descending_facet_sort;
 use heed::types::{ByteSlice, DecodeIgnore};
 use heed::{BytesDecode, RoTxn};
 
-pub use self::facet_distribution::{FacetDistribution, DEFAULT_VALUES_PER_FACET};
+pub use self::facet_buckets::{FacetBucket, FacetBuckets, FacetRange, MAX_FACET_BUCKETS};
+pub use self::facet_distribution::{FacetDistribution, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::facet_stats::{FacetStats, NumericFacetStats};
 pub use self::filter::Filter;
+pub use self::filter_cache::FilterCache;
+pub use self::search::{
+    FacetValueHit, SearchForFacetValues, DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET,
+};
 use crate::heed_codec::facet::{FacetGroupKeyCodec, FacetGroupValueCodec};
 use crate::heed_codec::ByteSliceRefCodec;
+mod facet_buckets;
 mod facet_distribution;
 mod facet_distribution_iter;
 mod facet_range_search;
 mod facet_sort_ascending;
 mod facet_sort_descending;
+mod facet_stats;
 mod filter;
+mod filter_cache;
+mod search;
 
 /// Get the first facet value in the facet database
 pub(crate) fn get_first_facet_value<'t, BoundCodec>(
@@ -0,0 +1,264 @@
+use std::fmt;
+
+use heed::types::ByteSlice;
+use levenshtein_automata::Distance;
+use roaring::RoaringBitmap;
+
+use crate::error::UserError;
+use crate::heed_codec::facet::{FacetGroupKeyCodec, FacetGroupValueCodec};
+use crate::heed_codec::StrRefCodec;
+use crate::search::build_dfa;
+use crate::{FieldId, Index, Result};
+
+/// The default number of facet values returned by a facet search.
+pub const DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET: usize = 100;
+
+/// Searches inside the string values of a facet, tolerating typos and
+/// considering the query as the prefix of the facet values.
+pub struct SearchForFacetValues<'a> {
+    facet: String,
+    query: Option<String>,
+    candidates: Option<RoaringBitmap>,
+    max_values: usize,
+    authorize_typos: bool,
+    rtxn: &'a heed::RoTxn<'a>,
+    index: &'a Index,
+}
+
+impl<'a> SearchForFacetValues<'a> {
+    pub fn new(
+        rtxn: &'a heed::RoTxn,
+        index: &'a Index,
+        facet: impl Into<String>,
+    ) -> SearchForFacetValues<'a> {
+        SearchForFacetValues {
+            facet: facet.into(),
+            query: None,
+            candidates: None,
+            max_values: DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET,
+            authorize_typos: true,
+            rtxn,
+            index,
+        }
+    }
+
+    pub fn query(&mut self, query: impl Into<String>) -> &mut Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    /// Only the facet values of these documents will be returned
+    /// and counted, all the documents are considered by default.
+    pub fn candidates(&mut self, candidates: RoaringBitmap) -> &mut Self {
+        self.candidates = Some(candidates);
+        self
+    }
+
+    pub fn max_values(&mut self, max: usize) -> &mut Self {
+        self.max_values = max;
+        self
+    }
+
+    pub fn authorize_typos(&mut self, value: bool) -> &mut Self {
+        self.authorize_typos = value;
+        self
+    }
+
+    /// Returns the number of typos allowed for the given normalized query,
+    /// following the typo tolerance settings of the index.
+    fn max_typos(&self, query: &str) -> Result<u8> {
+        if !self.authorize_typos || !self.index.authorize_typos(self.rtxn)? {
+            return Ok(0);
+        }
+
+        let count = query.chars().count();
+        let one_typo = self.index.min_word_len_one_typo(self.rtxn)? as usize;
+        let two_typos = self.index.min_word_len_two_typos(self.rtxn)? as usize;
+        Ok(if count < one_typo {
+            0
+        } else if count < two_typos {
+            1
+        } else {
+            2
+        })
+    }
+
+    /// Returns the matching facet values in lexicographic order
+    /// along with the number of candidates containing them.
+    pub fn execute(&self) -> Result<Vec<FacetValueHit>> {
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+        if !crate::is_faceted(&self.facet, &filterable_fields) {
+            return Err(UserError::InvalidFacetSearchFacetName {
+                field: self.facet.clone(),
+                valid_fields: filterable_fields.into_iter().collect(),
+            }
+            .into());
+        }
+
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let field_id = match fields_ids_map.id(&self.facet) {
+            Some(field_id) => field_id,
+            None => return Ok(Vec::new()),
+        };
+
+        // The facet values are stored trimmed and lowercased, the query must be too,
+        // unless the facet is case sensitive and its values are stored as is.
+        let is_case_sensitive =
+            self.index.case_sensitive_facets_ids(self.rtxn)?.contains(&field_id);
+        let query = match self.query.as_deref() {
+            Some(query) if is_case_sensitive => Some(query.to_string()),
+            Some(query) => Some(query.trim().to_lowercase()),
+            None => None,
+        };
+        let query = query.as_deref().filter(|query| !query.is_empty());
+
+        let mut prefix = Vec::new();
+        prefix.extend_from_slice(&field_id.to_be_bytes());
+        prefix.push(0); // read values from level 0 only
+
+        let dfa = match query {
+            Some(query) => match self.max_typos(query)? {
+                // Without typos we can directly jump to the values starting with the query.
+                0 => {
+                    prefix.extend_from_slice(query.as_bytes());
+                    None
+                }
+                typos => Some(build_dfa(query, typos, true)),
+            },
+            None => None,
+        };
+
+        let iter = self
+            .index
+            .facet_id_string_docids
+            .remap_types::<ByteSlice, ByteSlice>()
+            .prefix_iter(self.rtxn, &prefix)?
+            .remap_types::<FacetGroupKeyCodec<StrRefCodec>, FacetGroupValueCodec>();
+
+        let mut hits = Vec::new();
+        for result in iter {
+            if hits.len() >= self.max_values {
+                break;
+            }
+
+            let (key, value) = result?;
+
+            if let Some(dfa) = &dfa {
+                if !matches!(dfa.eval(key.left_bound), Distance::Exact(_)) {
+                    continue;
+                }
+            }
+
+            let docids = match &self.candidates {
+                Some(candidates) => value.bitmap & candidates,
+                None => value.bitmap,
+            };
+
+            if let Some(any_docid) = docids.min() {
+                let value = self.original_value(field_id, any_docid, key.left_bound)?;
+                hits.push(FacetValueHit { value, count: docids.len() });
+            }
+        }
+
+        Ok(hits)
+    }
+
+    fn original_value(&self, field_id: FieldId, docid: u32, normalized: &str) -> Result<String> {
+        let key: (FieldId, _, &str) = (field_id, docid, normalized);
+        let original = self.index.field_id_docid_facet_strings.get(self.rtxn, &key)?;
+        Ok(original.unwrap_or(normalized).to_owned())
+    }
+}
+
+impl fmt::Debug for SearchForFacetValues<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let SearchForFacetValues {
+            facet,
+            query,
+            candidates,
+            max_values,
+            authorize_typos,
+            rtxn: _,
+            index: _,
+        } = self;
+
+        f.debug_struct("SearchForFacetValues")
+            .field("facet", facet)
+            .field("query", query)
+            .field("candidates", candidates)
+            .field("max_values", max_values)
+            .field("authorize_typos", authorize_typos)
+            .finish()
+    }
+}
+
+/// A facet value matching a [`SearchForFacetValues`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacetValueHit {
+    /// The original value of the facet, as it appears in one of the documents.
+    pub value: String,
+    /// The number of candidates containing this facet value.
+    pub count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::SearchForFacetValues;
+    use crate::index::tests::TempIndex;
+
+    fn hits(search: &SearchForFacetValues) -> Vec<(String, u64)> {
+        search.execute().unwrap().into_iter().map(|hit| (hit.value, hit.count)).collect()
+    }
+
+    #[test]
+    fn search_for_facet_values() {
+        let mut index = TempIndex::new();
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("brand") }))
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "brand": "Samsung" },
+                { "brand": "samsung " },
+                { "brand": "Sony" },
+                { "brand": "Sennheiser" },
+                { "brand": "Apple" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let mut search = SearchForFacetValues::new(&txn, &index, "brand");
+        assert_eq!(hits(&search).len(), 4);
+
+        search.query("S");
+        assert_eq!(hits(&search), vec![(S("Samsung"), 2), (S("Sennheiser"), 1), (S("Sony"), 1)]);
+
+        search.query("samsng");
+        assert_eq!(hits(&search), vec![(S("Samsung"), 2)]);
+
+        search.authorize_typos(false);
+        assert!(hits(&search).is_empty());
+
+        search.authorize_typos(true).candidates([1, 2].iter().copied().collect());
+        assert_eq!(hits(&search), vec![(S("samsung "), 1)]);
+
+        search.query("").max_values(1);
+        assert_eq!(hits(&search), vec![(S("samsung "), 1)]);
+
+        search.max_values(0);
+        assert!(hits(&search).is_empty());
+
+        let error = SearchForFacetValues::new(&txn, &index, "color").execute().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute `color` is not filterable and thus, cannot be searched on. Available filterable attributes are: `brand`."
+        );
+    }
+}
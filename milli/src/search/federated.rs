@@ -0,0 +1,126 @@
+use rayon::prelude::*;
+use roaring::RoaringBitmap;
+
+use super::{MatchingWords, Search, SearchResult};
+use crate::score_details::ScoreDetails;
+use crate::{DocumentId, Result};
+
+/// Executes several searches, possibly on different indexes, in parallel and
+/// merges their results into a single list of hits ordered by ranking score.
+///
+/// The ranking scores are computed from the [`ScoreDetails`] of each hit which
+/// are normalized between `0.0` and `1.0` whatever the index the hit comes from.
+pub struct FederatedSearch<'a> {
+    searches: Vec<Search<'a>>,
+    offset: usize,
+    limit: usize,
+}
+
+impl<'a> FederatedSearch<'a> {
+    pub fn new() -> FederatedSearch<'a> {
+        FederatedSearch { searches: Vec::new(), offset: 0, limit: 20 }
+    }
+
+    /// Adds a search to execute, the hits it returns will be identified
+    /// by the position of the search in the order they are added.
+    ///
+    /// The offset and the limit of the search are ignored and
+    /// replaced by the ones of the federated search.
+    pub fn search(&mut self, search: Search<'a>) -> &mut FederatedSearch<'a> {
+        self.searches.push(search);
+        self
+    }
+
+    pub fn offset(&mut self, offset: usize) -> &mut FederatedSearch<'a> {
+        self.offset = offset;
+        self
+    }
+
+    pub fn limit(&mut self, limit: usize) -> &mut FederatedSearch<'a> {
+        self.limit = limit;
+        self
+    }
+
+    pub fn execute(&mut self) -> Result<FederatedSearchResult> {
+        // Every search must return enough hits to fill the requested page on its own.
+        let limit = self.offset + self.limit;
+        for search in &mut self.searches {
+            search.offset(0).limit(limit);
+        }
+
+        let results: Vec<SearchResult> =
+            self.searches.par_iter().map(Search::execute).collect::<Result<_>>()?;
+
+        let mut matching_words = Vec::with_capacity(results.len());
+        let mut candidates = Vec::with_capacity(results.len());
+        let mut hits_per_search = Vec::with_capacity(results.len());
+        for (search_index, result) in results.into_iter().enumerate() {
+            let SearchResult {
+                matching_words: mw,
+                candidates: c,
+                documents_ids,
+                document_scores,
+                geo_distances: _,
+            } = result;
+            matching_words.push(mw);
+            candidates.push(c);
+
+            let hits = documents_ids.into_iter().zip(document_scores).map(
+                move |(document_id, score_details)| FederatedHit {
+                    search_index,
+                    document_id,
+                    score: ScoreDetails::global_score(&score_details),
+                    score_details,
+                },
+            );
+            hits_per_search.push(hits.peekable());
+        }
+
+        // We merge the sorted lists of hits by always picking the best head, the hits
+        // with the same score are returned in the order their searches have been added.
+        let mut hits = Vec::with_capacity(self.limit);
+        while hits.len() < limit {
+            let best = hits_per_search
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, hits)| hits.peek().map(|hit| (i, hit.score)))
+                .reduce(|best, current| if current.1 > best.1 { current } else { best });
+
+            match best {
+                Some((i, _)) => hits.extend(hits_per_search[i].next()),
+                None => break,
+            }
+        }
+
+        let hits = hits.into_iter().skip(self.offset).collect();
+
+        Ok(FederatedSearchResult { hits, matching_words, candidates })
+    }
+}
+
+impl Default for FederatedSearch<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A document returned by a [`FederatedSearch`].
+#[derive(Debug, Clone)]
+pub struct FederatedHit {
+    /// The position of the search that returned this document.
+    pub search_index: usize,
+    pub document_id: DocumentId,
+    /// The ranking score of the document, between `0.0` and `1.0`.
+    pub score: f64,
+    pub score_details: Vec<ScoreDetails>,
+}
+
+#[derive(Default)]
+pub struct FederatedSearchResult {
+    /// The merged hits, from the most relevant to the least relevant.
+    pub hits: Vec<FederatedHit>,
+    /// The matching words of each search, in the order the searches have been added.
+    pub matching_words: Vec<MatchingWords>,
+    /// The candidates of each search, in the order the searches have been added.
+    pub candidates: Vec<RoaringBitmap>,
+}
@@ -17,6 +17,8 @@ type IsPrefix = bool;
 #[derive(Default)]
 pub struct MatchingWords {
     inner: Vec<(Vec<Rc<MatchingWord>>, Vec<PrimitiveWordId>)>,
+    /// The only fields, and their nested fields, in which the words are matched.
+    fields: Option<Vec<String>>,
 }
 
 impl fmt::Debug for MatchingWords {
@@ -36,7 +38,17 @@ impl MatchingWords {
         // in order to highlight the longuest part of the matched word.
         matching_words.sort_unstable_by_key(|(mw, _)| Reverse((mw.len(), mw[0].word.len())));
 
-        Self { inner: matching_words }
+        Self { inner: matching_words, fields: None }
+    }
+
+    /// Only matches the words in the given fields and in their nested fields.
+    pub fn restrict_to_fields(&mut self, fields: Vec<String>) {
+        self.fields = Some(fields);
+    }
+
+    /// Returns `true` if the words can be matched in the given field.
+    pub fn matches_field(&self, field: &str) -> bool {
+        self.fields.as_ref().map_or(true, |fields| crate::is_faceted(field, fields))
     }
 
     /// Returns an iterator over terms that match or partially match the given token.
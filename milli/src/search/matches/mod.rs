@@ -46,6 +46,14 @@ impl<'a, A> MatcherBuilder<'a, A> {
         self
     }
 
+    /// Builds a matcher for the text of the given field, no word is matched
+    /// if the search was restricted to other fields.
+    pub fn build_for_field<'t, 'm>(&'m self, field: &str, text: &'t str) -> Matcher<'t, 'm, A> {
+        let mut matcher = self.build(text);
+        matcher.searched = self.matching_words.matches_field(field);
+        matcher
+    }
+
     pub fn build<'t, 'm>(&'m self, text: &'t str) -> Matcher<'t, 'm, A> {
         let crop_marker = match &self.crop_marker {
             Some(marker) => marker.as_str(),
@@ -67,6 +75,7 @@ impl<'a, A> MatcherBuilder<'a, A> {
             crop_marker,
             highlight_prefix,
             highlight_suffix,
+            searched: true,
             matches: None,
         }
     }
@@ -110,6 +119,8 @@ pub struct Matcher<'t, 'm, A> {
     crop_marker: &'m str,
     highlight_prefix: &'m str,
     highlight_suffix: &'m str,
+    /// Whether the words can be matched in this text.
+    searched: bool,
     matches: Option<(Vec<Token<'t>>, Vec<Match>)>,
 }
 
@@ -171,6 +182,11 @@ impl<'t, A: AsRef<[u8]>> Matcher<'t, '_, A> {
         let tokens: Vec<_> = self.tokenizer.tokenize(self.text).collect();
         let mut matches = Vec::new();
 
+        if !self.searched {
+            self.matches = Some((tokens, matches));
+            return self;
+        }
+
         let mut words_positions = tokens
             .iter()
             .scan((0, 0), |(token_position, word_position), token| {
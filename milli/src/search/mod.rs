@@ -15,16 +15,24 @@ use log::debug;
 use once_cell::sync::Lazy;
 use roaring::bitmap::RoaringBitmap;
 
-pub use self::facet::{FacetDistribution, Filter, DEFAULT_VALUES_PER_FACET};
+pub use self::facet::{
+    FacetBucket, FacetBuckets, FacetDistribution, FacetRange, FacetStats, FacetValueHit, Filter,
+    FilterCache, NumericFacetStats, OrderBy, SearchForFacetValues, DEFAULT_VALUES_PER_FACET,
+};
+pub use self::federated::{FederatedHit, FederatedSearch, FederatedSearchResult};
 use self::fst_utils::{Complement, Intersection, StartsWith, Union};
 pub use self::matches::{
     FormatOptions, MatchBounds, Matcher, MatcherBuilder, MatchingWord, MatchingWords,
 };
 use self::query_tree::QueryTreeBuilder;
 use crate::error::UserError;
+use crate::score_details::ScoreDetails;
 use crate::search::criteria::r#final::{Final, FinalResult};
 use crate::search::criteria::InitialCandidates;
-use crate::{AscDesc, Criterion, DocumentId, Index, Member, Result};
+use crate::{
+    distance_between_two_points, lat_lng_to_xyz, AscDesc, Criterion, DocumentId, FieldId, Index,
+    Member, Result,
+};
 
 // Building these factories is not free.
 static LEVDIST0: Lazy<LevBuilder> = Lazy::new(|| LevBuilder::new(0, true));
@@ -34,6 +42,7 @@ static LEVDIST2: Lazy<LevBuilder> = Lazy::new(|| LevBuilder::new(2, true));
 mod criteria;
 mod distinct;
 pub mod facet;
+mod federated;
 mod fst_utils;
 mod matches;
 mod query_tree;
@@ -42,9 +51,11 @@ pub struct Search<'a> {
     query: Option<String>,
     // this should be linked to the String in the query
     filter: Option<Filter<'a>>,
+    filter_cache: Option<&'a FilterCache>,
     offset: usize,
     limit: usize,
     sort_criteria: Option<Vec<AscDesc>>,
+    searchable_attributes: Option<Vec<String>>,
     terms_matching_strategy: TermsMatchingStrategy,
     authorize_typos: bool,
     words_limit: usize,
@@ -59,9 +70,11 @@ impl<'a> Search<'a> {
         Search {
             query: None,
             filter: None,
+            filter_cache: None,
             offset: 0,
             limit: 20,
             sort_criteria: None,
+            searchable_attributes: None,
             terms_matching_strategy: TermsMatchingStrategy::default(),
             authorize_typos: true,
             exhaustive_number_hits: false,
@@ -92,6 +105,13 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Only matches the words of the query in these searchable attributes,
+    /// and in their nested attributes, without having to reindex the documents.
+    pub fn searchable_attributes(&mut self, searchable: &[&str]) -> &mut Search<'a> {
+        self.searchable_attributes = Some(searchable.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
     pub fn terms_matching_strategy(&mut self, value: TermsMatchingStrategy) -> &mut Search<'a> {
         self.terms_matching_strategy = value;
         self
@@ -112,6 +132,13 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Reuses the documents ids of the filters already evaluated by the searches
+    /// sharing the same cache on the same version of the index.
+    pub fn filter_cache(&mut self, cache: &'a FilterCache) -> &mut Search<'a> {
+        self.filter_cache = Some(cache);
+        self
+    }
+
     /// Force the search to exhastivelly compute the number of candidates,
     /// this will increase the search time but allows finite pagination.
     pub fn exhaustive_number_hits(&mut self, exhaustive_number_hits: bool) -> &mut Search<'a> {
@@ -133,10 +160,38 @@ impl<'a> Search<'a> {
         Ok(self.authorize_typos && index_authorizes_typos)
     }
 
+    /// Returns the ids of the searchable fields restricted to the given attributes,
+    /// the attributes must be searchable.
+    fn restricted_searchable_fields_ids(&self, attributes: &[String]) -> Result<Vec<FieldId>> {
+        if let Some(searchable_fields) = self.index.searchable_fields(self.rtxn)? {
+            for attribute in attributes {
+                if !crate::is_faceted(attribute, &searchable_fields) {
+                    return Err(UserError::InvalidSearchableAttribute {
+                        field: attribute.to_string(),
+                        valid_fields: searchable_fields.into_iter().map(String::from).collect(),
+                    })?;
+                }
+            }
+        }
+
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let searchable_fields_ids = match self.index.searchable_fields_ids(self.rtxn)? {
+            Some(searchable_fields_ids) => searchable_fields_ids,
+            None => fields_ids_map.ids().collect(),
+        };
+
+        Ok(searchable_fields_ids
+            .into_iter()
+            .filter(|&id| {
+                fields_ids_map.name(id).map_or(false, |f| crate::is_faceted(f, attributes))
+            })
+            .collect())
+    }
+
     pub fn execute(&self) -> Result<SearchResult> {
         // We create the query tree by spliting the query into tokens.
         let before = Instant::now();
-        let (query_tree, primitive_query, matching_words) = match self.query.as_ref() {
+        let (query, excluded_query_tree) = match self.query.as_ref() {
             Some(query) => {
                 let mut builder = QueryTreeBuilder::new(self.rtxn, self.index)?;
                 builder.terms_matching_strategy(self.terms_matching_strategy);
@@ -154,20 +209,34 @@ impl<'a> Search<'a> {
 
                 let tokenizer = tokbuilder.build();
                 let tokens = tokenizer.tokenize(query);
-                builder
-                    .build(tokens)?
-                    .map_or((None, None, None), |(qt, pq, mw)| (Some(qt), Some(pq), Some(mw)))
+                builder.build(tokens)?
             }
-            None => (None, None, None),
+            None => (None, None),
+        };
+        let (query_tree, primitive_query, matching_words) =
+            query.map_or((None, None, None), |(qt, pq, mw)| (Some(qt), Some(pq), Some(mw)));
+
+        // The words of the query are only matched, and highlighted, in the restricted attributes.
+        let mut matching_words = matching_words.unwrap_or_default();
+        let restricted_fields_ids = match &self.searchable_attributes {
+            Some(attributes) => {
+                matching_words.restrict_to_fields(attributes.clone());
+                Some(self.restricted_searchable_fields_ids(attributes)?)
+            }
+            None => None,
         };
 
         debug!("query tree: {:?} took {:.02?}", query_tree, before.elapsed());
+        debug!("excluded query tree: {:?}", excluded_query_tree);
 
         // We create the original candidates with the facet conditions results.
         let before = Instant::now();
-        let filtered_candidates = match &self.filter {
-            Some(condition) => Some(condition.evaluate(self.rtxn, self.index)?),
-            None => None,
+        let filtered_candidates = match (&self.filter, self.filter_cache) {
+            (Some(condition), Some(cache)) => {
+                Some(condition.evaluate_with_cache(self.rtxn, self.index, cache)?)
+            }
+            (Some(condition), None) => Some(condition.evaluate(self.rtxn, self.index)?),
+            (None, _) => None,
         };
 
         debug!("facet candidates: {:?} took {:.02?}", filtered_candidates, before.elapsed());
@@ -203,7 +272,28 @@ impl<'a> Search<'a> {
             return Err(UserError::SortRankingRuleMissing.into());
         }
 
-        let criteria_builder = criteria::CriteriaBuilder::new(self.rtxn, self.index)?;
+        let mut criteria_builder = criteria::CriteriaBuilder::new(self.rtxn, self.index)?;
+        if let Some(fields_ids) = restricted_fields_ids {
+            criteria_builder.restrict_searchable_fields(fields_ids);
+        }
+
+        // We remove the documents containing the excluded words and phrases from the candidates.
+        let filtered_candidates = match excluded_query_tree {
+            Some(excluded_query_tree) => {
+                let mut wdcache = WordDerivationsCache::new();
+                let excluded_candidates = criteria::resolve_query_tree(
+                    &criteria_builder,
+                    &excluded_query_tree,
+                    &mut wdcache,
+                )?;
+                let candidates = match filtered_candidates {
+                    Some(candidates) => candidates,
+                    None => self.index.documents_ids(self.rtxn)?,
+                };
+                Some(candidates - excluded_candidates)
+            }
+            None => filtered_candidates,
+        };
 
         match self.index.distinct_field(self.rtxn)? {
             None => {
@@ -216,7 +306,7 @@ impl<'a> Search<'a> {
                     None,
                     self.criterion_implementation_strategy,
                 )?;
-                self.perform_sort(NoopDistinct, matching_words.unwrap_or_default(), criteria)
+                self.perform_sort(NoopDistinct, matching_words, criteria)
             }
             Some(name) => {
                 let field_ids_map = self.index.fields_ids_map(self.rtxn)?;
@@ -233,7 +323,7 @@ impl<'a> Search<'a> {
                             Some(distinct.clone()),
                             self.criterion_implementation_strategy,
                         )?;
-                        self.perform_sort(distinct, matching_words.unwrap_or_default(), criteria)
+                        self.perform_sort(distinct, matching_words, criteria)
                     }
                     None => Ok(SearchResult::default()),
                 }
@@ -251,8 +341,9 @@ impl<'a> Search<'a> {
         let mut initial_candidates = InitialCandidates::Estimated(RoaringBitmap::new());
         let mut excluded_candidates = self.index.soft_deleted_documents_ids(self.rtxn)?;
         let mut documents_ids = Vec::new();
+        let mut document_scores = Vec::new();
 
-        while let Some(FinalResult { candidates, initial_candidates: ic, .. }) =
+        while let Some(FinalResult { candidates, initial_candidates: ic, score_details, .. }) =
             criteria.next(&excluded_candidates)?
         {
             debug!("Number of candidates found {}", candidates.len());
@@ -269,6 +360,7 @@ impl<'a> Search<'a> {
 
             for candidate in candidates.by_ref().take(self.limit - documents_ids.len()) {
                 documents_ids.push(candidate?);
+                document_scores.push(score_details.clone());
             }
 
             excluded_candidates |= candidates.into_excluded();
@@ -279,13 +371,65 @@ impl<'a> Search<'a> {
         }
 
         initial_candidates.map_inplace(|c| c - excluded_candidates);
+        let geo_distances = self.geo_distances(&documents_ids, &document_scores)?;
 
         Ok(SearchResult {
             matching_words,
             candidates: initial_candidates.into_inner(),
             documents_ids,
+            document_scores,
+            geo_distances,
         })
     }
+
+    /// Returns the distance in meters between each document and the point they have been sorted by,
+    /// or the center of the `_geoRadius` they have been filtered by when they haven't been sorted by geo.
+    fn geo_distances(
+        &self,
+        documents_ids: &[DocumentId],
+        document_scores: &[Vec<ScoreDetails>],
+    ) -> Result<Vec<Option<f64>>> {
+        let mut distances: Vec<_> = document_scores
+            .iter()
+            .map(|score_details| {
+                score_details.iter().find_map(|details| match details {
+                    ScoreDetails::GeoSort(geo_sort) => geo_sort.distance(),
+                    _ => None,
+                })
+            })
+            .collect();
+
+        let point = match self.filter.as_ref().and_then(Filter::geo_radius_point) {
+            Some(point) => point,
+            None => return Ok(distances),
+        };
+
+        // the positions of the documents that have not been sorted by geo
+        let mut missing: HashMap<_, _> = documents_ids
+            .iter()
+            .zip(&distances)
+            .enumerate()
+            .filter(|(_, (_, distance))| distance.is_none())
+            .map(|(i, (docid, _))| (*docid, i))
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(distances);
+        }
+
+        if let Some(rtree) = self.index.geo_rtree(self.rtxn)? {
+            for geo_point in rtree.nearest_neighbor_iter(&lat_lng_to_xyz(&point)) {
+                if let Some(i) = missing.remove(&geo_point.data.0) {
+                    distances[i] = Some(distance_between_two_points(&point, &geo_point.data.1));
+                    if missing.is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(distances)
+    }
 }
 
 impl fmt::Debug for Search<'_> {
@@ -293,9 +437,11 @@ impl fmt::Debug for Search<'_> {
         let Search {
             query,
             filter,
+            filter_cache: _,
             offset,
             limit,
             sort_criteria,
+            searchable_attributes,
             terms_matching_strategy,
             authorize_typos,
             words_limit,
@@ -310,6 +456,7 @@ impl fmt::Debug for Search<'_> {
             .field("offset", offset)
             .field("limit", limit)
             .field("sort_criteria", sort_criteria)
+            .field("searchable_attributes", searchable_attributes)
             .field("terms_matching_strategy", terms_matching_strategy)
             .field("authorize_typos", authorize_typos)
             .field("exhaustive_number_hits", exhaustive_number_hits)
@@ -323,8 +470,20 @@ impl fmt::Debug for Search<'_> {
 pub struct SearchResult {
     pub matching_words: MatchingWords,
     pub candidates: RoaringBitmap,
-    // TODO those documents ids should be associated with their criteria scores.
     pub documents_ids: Vec<DocumentId>,
+    /// The details of the buckets each document has been ranked in,
+    /// in the same order as the `documents_ids`.
+    pub document_scores: Vec<Vec<ScoreDetails>>,
+    /// The distance in meters between each document and the geo point of the
+    /// `_geoPoint` sort or the `_geoRadius` filter, in the same order as the `documents_ids`.
+    pub geo_distances: Vec<Option<f64>>,
+}
+
+impl SearchResult {
+    /// Returns the relevancy score, between `0.0` and `1.0`, of each returned document.
+    pub fn ranking_scores(&self) -> impl Iterator<Item = f64> + '_ {
+        self.document_scores.iter().map(ScoreDetails::global_score)
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -443,6 +602,9 @@ pub fn build_dfa(word: &str, typos: u8, is_prefix: bool) -> DFA {
 
 #[cfg(test)]
 mod test {
+    use big_s::S;
+    use maplit::hashset;
+
     use super::*;
     use crate::index::tests::TempIndex;
 
@@ -471,6 +633,190 @@ mod test {
         assert!(!search.is_typo_authorized().unwrap());
     }
 
+    #[test]
+    fn test_geo_distances() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geo") });
+                settings.set_sortable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "Paris", "_geo": { "lat": 48.8566, "lng": 2.3522 } },
+                { "id": 2, "name": "Lyon", "_geo": { "lat": 45.764, "lng": 4.8357 } },
+                { "id": 3, "name": "Madrid", "_geo": { "lat": 40.4168, "lng": -3.7038 } },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let kilometers = |result: &SearchResult| -> Vec<Option<u64>> {
+            result.geo_distances.iter().map(|d| d.map(|d| (d / 1000.0) as u64)).collect()
+        };
+
+        // sorted by geo
+        let mut search = Search::new(&txn, &index);
+        search.sort_criteria(vec![AscDesc::Asc(Member::Geo([48.8566, 2.3522]))]);
+        let result = search.execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0, 1, 2]);
+        assert_eq!(kilometers(&result), vec![Some(0), Some(391), Some(1052)]);
+
+        // filtered by geo
+        let mut search = Search::new(&txn, &index);
+        search.filter(Filter::from_str("_geoRadius(45.764, 4.8357, 500000)").unwrap().unwrap());
+        let result = search.execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+        assert_eq!(kilometers(&result), vec![Some(391), Some(0)]);
+
+        // a document may not match the _geoRadius of an OR
+        let mut search = Search::new(&txn, &index);
+        let filter = "_geoRadius(45.764, 4.8357, 500000) OR _geoRadius(40.4168, -3.7038, 1000)";
+        search.filter(Filter::from_str(filter).unwrap().unwrap());
+        let result = search.execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0, 1, 2]);
+        assert_eq!(kilometers(&result), vec![None, None, None]);
+
+        // the sort takes precedence over the filter
+        search.sort_criteria(vec![AscDesc::Desc(Member::Geo([40.4168, -3.7038]))]);
+        let result = search.execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+        assert_eq!(kilometers(&result), vec![Some(1052), Some(912)]);
+
+        // neither sorted nor filtered by geo
+        let result = Search::new(&txn, &index).execute().unwrap();
+        assert_eq!(kilometers(&result), vec![None, None, None]);
+    }
+
+    #[test]
+    fn test_phrase_slop() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "text": "the quick brown fox" },
+                { "id": 1, "text": "the quick fox" },
+                { "id": 2, "text": "fox of the quick" },
+                { "id": 3, "text": "quick is the rabbit that is not a fox" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let search = |query: &str| {
+            let mut documents_ids =
+                Search::new(&txn, &index).query(query).execute().unwrap().documents_ids;
+            documents_ids.sort_unstable();
+            documents_ids
+        };
+
+        assert_eq!(search("\"quick fox\""), vec![1]);
+        assert_eq!(search("\"quick fox\"~0"), vec![1]);
+        assert_eq!(search("\"quick fox\"~1"), vec![0, 1]);
+        // the words of the phrase can be swapped
+        assert_eq!(search("\"quick fox\"~3"), vec![0, 1, 2]);
+        // the slop is bounded by the maximum proximity
+        assert_eq!(search("\"quick fox\"~255"), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_excluded_terms() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "text": "refurbished laptop" },
+                { "id": 1, "text": "brand new laptop" },
+                { "id": 2, "text": "laptop with a hard drive" },
+                { "id": 3, "text": "laptop sold by e-mail" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let search = |query: &str| {
+            let mut search = Search::new(&txn, &index);
+            search.query(query).terms_matching_strategy(TermsMatchingStrategy::All);
+            let mut result = search.execute().unwrap();
+            result.documents_ids.sort_unstable();
+            result
+        };
+
+        let result = search("laptop -refurbished");
+        assert_eq!(result.documents_ids, vec![1, 2, 3]);
+        // the excluded words are not highlighted
+        let builder = MatcherBuilder::from_matching_words(result.matching_words);
+        let format_options = FormatOptions { highlight: true, crop: None };
+        let mut matcher = builder.build("refurbished laptop");
+        assert_eq!(matcher.format(format_options), "refurbished <em>laptop</em>");
+
+        // the excluded words don't allow typos
+        assert_eq!(search("laptop -refurbishd").documents_ids, vec![0, 1, 2, 3]);
+        assert_eq!(search("laptop -\"hard drive\"").documents_ids, vec![0, 1, 3]);
+        // a minus glued to a word is a simple separator
+        assert_eq!(search("laptop e-mail").documents_ids, vec![3]);
+        // the excluded words alone behave like a placeholder search
+        assert_eq!(search("-refurbished -drive").documents_ids, vec![1, 3]);
+
+        // the excluded words beyond the words limit are still excluded
+        let mut search = Search::new(&txn, &index);
+        search.query("laptop with a hard drive -refurbished").words_limit(1);
+        let mut result = search.execute().unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_searchable_attributes() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_searchable_fields(vec![S("title"), S("author")]);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "title": "The Hobbit", "author": { "name": "Tolkien" } },
+                { "id": 1, "title": "Tolkien, a biography", "author": { "name": "Carpenter" } },
+                { "id": 2, "title": "Carpenter's tools", "author": { "name": "Smith" } },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let search = |query: &str, attributes: &[&str]| {
+            let mut search = Search::new(&txn, &index);
+            search.query(query).searchable_attributes(attributes);
+            search.execute()
+        };
+
+        let mut result = search("tolkien", &["title"]).unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![1]);
+        let mut result = search("tolkien", &["author"]).unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0]);
+        let mut result = search("carpenter", &["title", "author.name"]).unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![1, 2]);
+
+        // the words are only highlighted in the searched attributes
+        let result = search("tolkien", &["title"]).unwrap();
+        let builder = MatcherBuilder::from_matching_words(result.matching_words);
+        let format_options = FormatOptions { highlight: true, crop: None };
+        let mut matcher = builder.build_for_field("title", "Tolkien, a biography");
+        assert_eq!(matcher.format(format_options), "<em>Tolkien</em>, a biography");
+        let mut matcher = builder.build_for_field("author.name", "Tolkien");
+        assert_eq!(matcher.format(format_options), "Tolkien");
+
+        let error = search("tolkien", &["id"]).unwrap_err();
+        assert!(matches!(
+            error,
+            crate::Error::UserError(UserError::InvalidSearchableAttribute { .. })
+        ));
+    }
+
     #[test]
     fn test_one_typos_tolerance() {
         let fst = fst::Set::from_iter(["zealand"].iter()).unwrap().map_data(Cow::Owned).unwrap();
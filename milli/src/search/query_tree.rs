@@ -11,19 +11,23 @@ use charabia::{SeparatorKind, TokenKind};
 use roaring::RoaringBitmap;
 use slice_group_by::GroupBy;
 
+use crate::proximity::MAX_DISTANCE;
 use crate::search::matches::matching_words::{MatchingWord, PrimitiveWordId};
 use crate::search::TermsMatchingStrategy;
 use crate::{CboRoaringBitmapLenCodec, Index, MatchingWords, Result};
 
 type IsOptionalWord = bool;
 type IsPrefix = bool;
+/// The number of words that can be inserted between two consecutive words of a phrase.
+pub type Slop = u8;
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Operation {
     And(Vec<Operation>),
     // series of consecutive non prefix and exact words
     // `None` means a stop word.
-    Phrase(Vec<Option<String>>),
+    // The slop is the number of words allowed between two consecutive words of the phrase.
+    Phrase(Vec<Option<String>>, Slop),
     Or(IsOptionalWord, Vec<Operation>),
     Query(Query),
 }
@@ -36,9 +40,12 @@ impl fmt::Debug for Operation {
                     writeln!(f, "{:1$}AND", "", depth * 2)?;
                     children.iter().try_for_each(|c| pprint_tree(f, c, depth + 1))
                 }
-                Operation::Phrase(children) => {
+                Operation::Phrase(children, 0) => {
                     writeln!(f, "{:2$}PHRASE {:?}", "", children, depth * 2)
                 }
+                Operation::Phrase(children, slop) => {
+                    writeln!(f, "{:3$}PHRASE~{} {:?}", "", slop, children, depth * 2)
+                }
                 Operation::Or(true, children) => {
                     writeln!(f, "{:1$}OR(WORD)", "", depth * 2)?;
                     children.iter().try_for_each(|c| pprint_tree(f, c, depth + 1))
@@ -79,15 +86,15 @@ impl Operation {
         }
     }
 
-    fn phrase(mut words: Vec<Option<String>>) -> Self {
+    fn phrase(mut words: Vec<Option<String>>, slop: Slop) -> Self {
         if words.len() == 1 {
             if let Some(word) = words.pop().unwrap() {
                 Self::Query(Query { prefix: false, kind: QueryKind::exact(word) })
             } else {
-                Self::Phrase(words)
+                Self::Phrase(words, slop)
             }
         } else {
-            Self::Phrase(words)
+            Self::Phrase(words, slop)
         }
     }
 
@@ -180,6 +187,9 @@ pub struct QueryTreeBuilder<'a> {
     authorize_typos: bool,
     words_limit: Option<usize>,
     exact_words: Option<fst::Set<Cow<'a, [u8]>>>,
+    /// The synonyms and the expansions of the synonym rules, read once per search.
+    /// It is empty when the synonyms are already indexed along with the words they expand.
+    synonyms: HashMap<Vec<String>, Vec<Vec<String>>>,
 }
 
 impl<'a> Context for QueryTreeBuilder<'a> {
@@ -188,7 +198,8 @@ impl<'a> Context for QueryTreeBuilder<'a> {
     }
 
     fn synonyms<S: AsRef<str>>(&self, words: &[S]) -> heed::Result<Option<Vec<Vec<String>>>> {
-        self.index.words_synonyms(self.rtxn, words)
+        let words: Vec<_> = words.iter().map(|s| s.as_ref().to_owned()).collect();
+        Ok(self.synonyms.get(&words).cloned())
     }
 
     fn word_documents_count(&self, word: &str) -> heed::Result<Option<u64>> {
@@ -223,6 +234,12 @@ impl<'a> QueryTreeBuilder<'a> {
     /// Create a `QueryTreeBuilder` from a heed ReadOnly transaction `rtxn`
     /// and an Index `index`.
     pub fn new(rtxn: &'a heed::RoTxn<'a>, index: &'a Index) -> Result<Self> {
+        let synonyms = if index.index_time_synonyms(rtxn)? {
+            HashMap::new()
+        } else {
+            index.all_synonyms(rtxn)?
+        };
+
         Ok(Self {
             rtxn,
             index,
@@ -230,6 +247,7 @@ impl<'a> QueryTreeBuilder<'a> {
             authorize_typos: true,
             words_limit: None,
             exact_words: index.exact_words(rtxn)?,
+            synonyms,
         })
     }
 
@@ -268,11 +286,16 @@ impl<'a> QueryTreeBuilder<'a> {
     /// - if `authorize_typos` is set to `false` the query tree will be generated
     ///   forcing all query words to match documents without any typo
     ///   (the criterion `typo` will be ignored)
+    ///
+    /// The words and phrases prefixed by a `-` are not part of the query tree,
+    /// they are returned in a second query tree matching the documents to exclude.
+    #[allow(clippy::type_complexity)]
     pub fn build<A: AsRef<[u8]>>(
         &self,
         query: NormalizedTokenIter<A>,
-    ) -> Result<Option<(Operation, PrimitiveQuery, MatchingWords)>> {
-        let primitive_query = create_primitive_query(query, self.words_limit);
+    ) -> Result<(Option<(Operation, PrimitiveQuery, MatchingWords)>, Option<Operation>)> {
+        let (primitive_query, excluded_query) = create_primitive_query(query, self.words_limit);
+        let excluded_query_tree = create_excluded_query_tree(excluded_query);
         if !primitive_query.is_empty() {
             let qt = create_query_tree(
                 self,
@@ -282,9 +305,9 @@ impl<'a> QueryTreeBuilder<'a> {
             )?;
             let matching_words =
                 create_matching_words(self, self.authorize_typos, &primitive_query)?;
-            Ok(Some((qt, primitive_query, matching_words)))
+            Ok((Some((qt, primitive_query, matching_words)), excluded_query_tree))
         } else {
-            Ok(None)
+            Ok((None, excluded_query_tree))
         }
     }
 }
@@ -349,13 +372,33 @@ fn synonyms(ctx: &impl Context, word: &[&str]) -> heed::Result<Option<Vec<Operat
                         kind: QueryKind::exact(synonym[0].clone()),
                     })
                 } else {
-                    Operation::Phrase(synonym.into_iter().map(Some).collect())
+                    Operation::Phrase(synonym.into_iter().map(Some).collect(), 0)
                 }
             })
             .collect()
     }))
 }
 
+/// Creates the query tree matching the documents that contain any of the excluded parts,
+/// the words are matched without typos and are never considered as prefixes.
+fn create_excluded_query_tree(excluded_query: PrimitiveQuery) -> Option<Operation> {
+    let ops: Vec<_> = excluded_query
+        .into_iter()
+        .map(|part| match part {
+            PrimitiveQueryPart::Word(word, _) => {
+                Operation::Query(Query { prefix: false, kind: QueryKind::exact(word) })
+            }
+            PrimitiveQueryPart::Phrase(words, slop) => Operation::phrase(words, slop),
+        })
+        .collect();
+
+    if ops.is_empty() {
+        None
+    } else {
+        Some(Operation::or(false, ops))
+    }
+}
+
 /// Main function that creates the final query tree from the primitive query.
 fn create_query_tree(
     ctx: &impl Context,
@@ -377,10 +420,10 @@ fn create_query_tree(
             PrimitiveQueryPart::Word(word, prefix) => {
                 let mut children = synonyms(ctx, &[&word])?.unwrap_or_default();
                 if let Some((left, right)) = split_best_frequency(ctx, &word)? {
-                    children.push(Operation::Phrase(vec![
-                        Some(left.to_string()),
-                        Some(right.to_string()),
-                    ]));
+                    children.push(Operation::Phrase(
+                        vec![Some(left.to_string()), Some(right.to_string())],
+                        0,
+                    ));
                 }
                 let (word_len_one_typo, word_len_two_typo) = ctx.min_word_len_for_typo()?;
                 let exact_words = ctx.exact_words();
@@ -393,7 +436,7 @@ fn create_query_tree(
                 Ok(Operation::or(false, children))
             }
             // create a CONSECUTIVE operation wrapping all word in the phrase
-            PrimitiveQueryPart::Phrase(words) => Ok(Operation::phrase(words)),
+            PrimitiveQueryPart::Phrase(words, slop) => Ok(Operation::phrase(words, slop)),
         }
     }
 
@@ -627,7 +670,7 @@ fn create_matching_words(
                 }
             }
             // create a CONSECUTIVE matchings words wrapping all word in the phrase
-            PrimitiveQueryPart::Phrase(words) => {
+            PrimitiveQueryPart::Phrase(words, _) => {
                 let ids: Vec<_> =
                     (0..words.len()).into_iter().map(|i| id + i as PrimitiveWordId).collect();
                 // Require that all words of the phrase have a corresponding MatchingWord
@@ -754,13 +797,13 @@ pub type PrimitiveQuery = Vec<PrimitiveQueryPart>;
 
 #[derive(Debug, Clone)]
 pub enum PrimitiveQueryPart {
-    Phrase(Vec<Option<String>>),
+    Phrase(Vec<Option<String>>, Slop),
     Word(String, IsPrefix),
 }
 
 impl PrimitiveQueryPart {
     fn is_phrase(&self) -> bool {
-        matches!(self, Self::Phrase(_))
+        matches!(self, Self::Phrase(..))
     }
 
     fn is_prefix(&self) -> bool {
@@ -769,7 +812,7 @@ impl PrimitiveQueryPart {
 
     fn len(&self) -> usize {
         match self {
-            Self::Phrase(words) => words.len(),
+            Self::Phrase(words, _) => words.len(),
             Self::Word(_, _) => 1,
         }
     }
@@ -777,28 +820,83 @@ impl PrimitiveQueryPart {
 
 /// Create primitive query from tokenized query string,
 /// the primitive query is an intermediate state to build the query tree.
+///
+/// A closing quote directly followed by a tilde and a number, e.g. `"quick fox"~3`,
+/// gives a slop to the phrase: up to this number of words can be found between
+/// two consecutive words of the phrase, in any order.
+///
+/// The words and phrases prefixed by a `-`, e.g. `laptop -refurbished` or `-"hard drive"`,
+/// are returned apart, in the second primitive query, as the documents containing them
+/// must be excluded. A `-` glued to the previous word, e.g. `e-mail`, is a simple separator.
+/// The `words_limit` only applies to the first primitive query, all the excluded parts are kept.
 fn create_primitive_query<A>(
     query: NormalizedTokenIter<A>,
     words_limit: Option<usize>,
-) -> PrimitiveQuery
+) -> (PrimitiveQuery, PrimitiveQuery)
 where
     A: AsRef<[u8]>,
 {
     let mut primitive_query = Vec::new();
+    let mut excluded_query = Vec::new();
     let mut phrase = Vec::new();
     let mut quoted = false;
+    // whether the current phrase must be excluded.
+    let mut excluded_phrase = false;
+    // whether the previous token is a separator ending with a whitespace, or there is none.
+    let mut after_whitespace = true;
+    // whether the next word must be excluded.
+    let mut excluded_word = false;
+    // whether the previous token closed a phrase, and ended right after the closing quote.
+    let mut phrase_closed = false;
+    // whether the next token is expected to be the slop of the last phrase.
+    let mut slop_pending = false;
 
     let parts_limit = words_limit.unwrap_or(usize::MAX);
 
     let mut peekable = query.peekable();
     while let Some(token) = peekable.next() {
-        // early return if word limit is exceeded
-        if primitive_query.len() >= parts_limit {
-            return primitive_query;
-        }
+        let (was_phrase_closed, was_slop_pending) =
+            (mem::take(&mut phrase_closed), mem::take(&mut slop_pending));
+        let was_after_whitespace = mem::take(&mut after_whitespace);
+        let is_excluded_word = mem::take(&mut excluded_word);
 
         match token.kind {
             TokenKind::Word | TokenKind::StopWord => {
+                // if the word directly follows the tilde of a phrase it is its slop,
+                // the tilde is either a separator or the first character of the word.
+                let slop = if was_slop_pending {
+                    token.lemma().parse::<Slop>().ok()
+                } else if was_phrase_closed {
+                    token.lemma().strip_prefix('~').and_then(|s| s.parse::<Slop>().ok())
+                } else {
+                    None
+                };
+                let last_phrase = if excluded_phrase {
+                    excluded_query.last_mut()
+                } else {
+                    primitive_query.last_mut()
+                };
+                if let (Some(slop), Some(PrimitiveQueryPart::Phrase(_, phrase_slop))) =
+                    (slop, last_phrase)
+                {
+                    *phrase_slop = slop;
+                    continue;
+                }
+
+                // the excluded words are never prefixes, the stop words are ignored.
+                if is_excluded_word && !quoted {
+                    if let TokenKind::Word = token.kind {
+                        excluded_query
+                            .push(PrimitiveQueryPart::Word(token.lemma().to_string(), false));
+                    }
+                    continue;
+                }
+
+                // once the word limit is reached, we only keep reading the excluded parts.
+                if primitive_query.len() >= parts_limit && !(quoted && excluded_phrase) {
+                    continue;
+                }
+
                 // 1. if the word is quoted we push it in a phrase-buffer waiting for the ending quote,
                 // 2. if the word is not the last token of the query and is not a stop_word we push it as a non-prefix word,
                 // 3. if the word is the last token of the query we push it as a prefix word.
@@ -819,20 +917,48 @@ where
                 }
             }
             TokenKind::Separator(separator_kind) => {
-                let quote_count = token.lemma().chars().filter(|&s| s == '"').count();
+                let lemma = token.lemma();
+                let quote_count = lemma.chars().filter(|&s| s == '"').count();
+                let was_quoted = quoted;
                 // swap quoted state if we encounter a double quote
                 if quote_count % 2 != 0 {
                     quoted = !quoted;
                 }
                 // if there is a quote or a hard separator we close the phrase.
+                let mut phrase_pushed = false;
                 if quote_count > 0 || separator_kind == SeparatorKind::Hard {
                     let phrase = mem::take(&mut phrase);
 
                     // if the phrase only contains stop words, we don't keep it in the query.
                     if phrase.iter().any(|w| w.is_some()) {
-                        primitive_query.push(PrimitiveQueryPart::Phrase(phrase));
+                        if excluded_phrase {
+                            excluded_query.push(PrimitiveQueryPart::Phrase(phrase, 0));
+                            phrase_pushed = true;
+                        } else if primitive_query.len() < parts_limit {
+                            primitive_query.push(PrimitiveQueryPart::Phrase(phrase, 0));
+                            phrase_pushed = true;
+                        }
                     }
                 }
+
+                // a phrase is excluded when its opening quote directly follows a minus.
+                if !was_quoted && quoted {
+                    let before_quote = &lemma[..lemma.rfind('"').unwrap()];
+                    excluded_phrase = ends_with_minus(before_quote, was_after_whitespace);
+                } else if !quoted {
+                    excluded_word = ends_with_minus(lemma, was_after_whitespace);
+                }
+                after_whitespace = lemma.ends_with(char::is_whitespace);
+
+                // the tilde of the slop can be in the same separator as the closing quote,
+                // or be the separator that follows it.
+                if phrase_pushed && was_quoted && !quoted {
+                    let after_quote = &lemma[lemma.rfind('"').unwrap() + 1..];
+                    phrase_closed = after_quote.is_empty();
+                    slop_pending = after_quote == "~";
+                } else if was_phrase_closed {
+                    slop_pending = lemma == "~";
+                }
             }
             _ => (),
         }
@@ -840,10 +966,24 @@ where
 
     // If a quote is never closed, we consider all of the end of the query as a phrase.
     if phrase.iter().any(|w| w.is_some()) {
-        primitive_query.push(PrimitiveQueryPart::Phrase(mem::take(&mut phrase)));
+        if excluded_phrase {
+            excluded_query.push(PrimitiveQueryPart::Phrase(mem::take(&mut phrase), 0));
+        } else if primitive_query.len() < parts_limit {
+            primitive_query.push(PrimitiveQueryPart::Phrase(mem::take(&mut phrase), 0));
+        }
     }
 
-    primitive_query
+    (primitive_query, excluded_query)
+}
+
+/// Returns `true` if the separator ends with a minus that is not glued to the previous word,
+/// `after_whitespace` tells if the separator is the first token or follows a whitespace.
+fn ends_with_minus(separator: &str, after_whitespace: bool) -> bool {
+    match separator.strip_suffix('-') {
+        Some("") => after_whitespace,
+        Some(separator) => separator.ends_with(char::is_whitespace),
+        None => false,
+    }
 }
 
 /// Returns the maximum number of typos that this Operation allows.
@@ -854,7 +994,7 @@ pub fn maximum_typo(operation: &Operation) -> usize {
         And(ops) => ops.iter().map(maximum_typo).sum::<usize>(),
         Query(q) => q.kind.typo() as usize,
         // no typo allowed in phrases
-        Phrase(_) => 0,
+        Phrase(..) => 0,
     }
 }
 
@@ -866,7 +1006,9 @@ pub fn maximum_proximity(operation: &Operation) -> usize {
         And(ops) => {
             ops.iter().map(maximum_proximity).sum::<usize>() + ops.len().saturating_sub(1) * 7
         }
-        Query(_) | Phrase(_) => 0,
+        // the words of a phrase are separated by at most `MAX_DISTANCE - 2` other words
+        Phrase(_, slop) => (*slop as usize).min(MAX_DISTANCE as usize - 2),
+        Query(_) => 0,
     }
 }
 
@@ -898,7 +1040,7 @@ mod test {
             words_limit: Option<usize>,
             query: NormalizedTokenIter<A>,
         ) -> Result<Option<(Operation, PrimitiveQuery)>> {
-            let primitive_query = create_primitive_query(query, words_limit);
+            let (primitive_query, _) = create_primitive_query(query, words_limit);
             if !primitive_query.is_empty() {
                 let qt = create_query_tree(
                     self,
@@ -1232,6 +1374,67 @@ mod test {
         "###);
     }
 
+    #[test]
+    fn phrase_with_slop() {
+        let query = "\"hey friends\"~3 \"wooop wooop\"";
+        let tokens = query.tokenize();
+
+        let (query_tree, _) = TestContext::default()
+            .build(TermsMatchingStrategy::All, true, None, tokens)
+            .unwrap()
+            .unwrap();
+
+        insta::assert_debug_snapshot!(query_tree, @r###"
+        AND
+          PHRASE~3 [Some("hey"), Some("friends")]
+          PHRASE [Some("wooop"), Some("wooop")]
+        "###);
+    }
+
+    #[test]
+    fn excluded_words_and_phrases() {
+        let query = "laptop -refurbished e-mail -\"hard drive\"~2";
+        let tokens = query.tokenize();
+
+        let (primitive_query, excluded_query) = create_primitive_query(tokens, None);
+
+        insta::assert_debug_snapshot!(primitive_query, @r###"
+        [
+            Word(
+                "laptop",
+                false,
+            ),
+            Word(
+                "e",
+                false,
+            ),
+            Word(
+                "mail",
+                false,
+            ),
+        ]
+        "###);
+        insta::assert_debug_snapshot!(excluded_query, @r###"
+        [
+            Word(
+                "refurbished",
+                false,
+            ),
+            Phrase(
+                [
+                    Some(
+                        "hard",
+                    ),
+                    Some(
+                        "drive",
+                    ),
+                ],
+                2,
+            ),
+        ]
+        "###);
+    }
+
     #[test]
     fn optional_word() {
         let query = "hey my friend ";
@@ -1346,6 +1549,42 @@ mod test {
         "###);
     }
 
+    #[test]
+    fn words_limit_keeps_excluded_parts() {
+        let query = "laptop with a hard drive -refurbished -\"dead pixels\"";
+        let tokens = query.tokenize();
+
+        let (primitive_query, excluded_query) = create_primitive_query(tokens, Some(1));
+
+        insta::assert_debug_snapshot!(primitive_query, @r###"
+        [
+            Word(
+                "laptop",
+                false,
+            ),
+        ]
+        "###);
+        insta::assert_debug_snapshot!(excluded_query, @r###"
+        [
+            Word(
+                "refurbished",
+                false,
+            ),
+            Phrase(
+                [
+                    Some(
+                        "dead",
+                    ),
+                    Some(
+                        "pixels",
+                    ),
+                ],
+                0,
+            ),
+        ]
+        "###);
+    }
+
     #[test]
     fn test_min_word_len_typo() {
         let exact_words = fst::Set::from_iter([b""]).unwrap().map_data(Cow::Owned).unwrap();
@@ -293,6 +293,27 @@ pub fn snap_facet_id_exists_docids(index: &Index) -> String {
     });
     snap
 }
+pub fn snap_facet_id_is_null_docids(index: &Index) -> String {
+    let snap = make_db_snap_from_iter!(index, facet_id_is_null_docids, |(facet_id, docids)| {
+        &format!("{facet_id:<3} {}", display_bitmap(&docids))
+    });
+    snap
+}
+pub fn snap_facet_id_is_empty_docids(index: &Index) -> String {
+    let snap = make_db_snap_from_iter!(index, facet_id_is_empty_docids, |(facet_id, docids)| {
+        &format!("{facet_id:<3} {}", display_bitmap(&docids))
+    });
+    snap
+}
+pub fn snap_facet_id_bool_docids(index: &Index) -> String {
+    let snap = make_db_snap_from_iter!(index, facet_id_bool_docids, |(
+        (facet_id, boolean),
+        docids,
+    )| {
+        &format!("{facet_id:<3} {boolean:<6} {}", display_bitmap(&docids))
+    });
+    snap
+}
 pub fn snap_facet_id_string_docids(index: &Index) -> String {
     let snap = make_db_snap_from_iter!(index, facet_id_string_docids, |(
         FacetGroupKey { field_id, level, left_bound },
@@ -501,6 +522,15 @@ macro_rules! full_snap_of_db {
     ($index:ident, facet_id_exists_docids) => {{
         $crate::snapshot_tests::snap_facet_id_exists_docids(&$index)
     }};
+    ($index:ident, facet_id_is_null_docids) => {{
+        $crate::snapshot_tests::snap_facet_id_is_null_docids(&$index)
+    }};
+    ($index:ident, facet_id_is_empty_docids) => {{
+        $crate::snapshot_tests::snap_facet_id_is_empty_docids(&$index)
+    }};
+    ($index:ident, facet_id_bool_docids) => {{
+        $crate::snapshot_tests::snap_facet_id_bool_docids(&$index)
+    }};
     ($index:ident, documents_ids) => {{
         $crate::snapshot_tests::snap_documents_ids(&$index)
     }};
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A synonym rule as defined by the user, the expressions can contain multiple words.
+///
+/// Only the expressions of at most three words are recognized in the queries,
+/// the longer ones can only be used as the expansions of shorter ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SynonymRule {
+    /// All the expressions are synonyms of each other, e.g. `nyc <=> new york city`.
+    Mutual(Vec<String>),
+    /// The `from` expression is expanded into the `to` expressions but
+    /// they are not expanded into it, e.g. `phone => smartphone`.
+    OneWay { from: String, to: Vec<String> },
+}
+
+/// The normalized expansions of the synonym rules, indexed by the words they expand.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Synonyms {
+    expansions: HashMap<Vec<String>, Vec<Vec<String>>>,
+}
+
+impl Synonyms {
+    /// Builds the expansions of the rules, the expressions are split
+    /// into normalized words by the given function.
+    pub fn from_rules<F>(rules: &[SynonymRule], mut normalize: F) -> Synonyms
+    where
+        F: FnMut(&str) -> Vec<String>,
+    {
+        let mut synonyms = Synonyms::default();
+
+        for rule in rules {
+            match rule {
+                SynonymRule::Mutual(expressions) => {
+                    let expressions: Vec<_> = expressions.iter().map(|e| normalize(e)).collect();
+                    for (i, words) in expressions.iter().enumerate() {
+                        let others = expressions
+                            .iter()
+                            .enumerate()
+                            .filter(|(j, _)| i != *j)
+                            .map(|(_, other)| other.clone());
+                        synonyms.insert(words.clone(), others);
+                    }
+                }
+                SynonymRule::OneWay { from, to } => {
+                    let words = normalize(from);
+                    let expansions: Vec<_> = to.iter().map(|e| normalize(e)).collect();
+                    synonyms.insert(words, expansions);
+                }
+            }
+        }
+
+        // Make sure that we don't have duplicate expansions.
+        synonyms.expansions.values_mut().for_each(|expansions| {
+            expansions.sort_unstable();
+            expansions.dedup();
+        });
+
+        synonyms
+    }
+
+    fn insert(&mut self, words: Vec<String>, expansions: impl IntoIterator<Item = Vec<String>>) {
+        // The expressions only made of stop words or separators are ignored.
+        if words.is_empty() {
+            return;
+        }
+
+        let expansions: Vec<_> =
+            expansions.into_iter().filter(|e| !e.is_empty() && *e != words).collect();
+        self.expansions.entry(words).or_default().extend(expansions);
+    }
+
+    /// Returns the expansions of the given normalized words.
+    pub fn get<S: AsRef<str>>(&self, words: &[S]) -> Option<&[Vec<String>]> {
+        let words: Vec<_> = words.iter().map(|s| s.as_ref().to_owned()).collect();
+        self.expansions.get(&words).filter(|e| !e.is_empty()).map(Vec::as_slice)
+    }
+
+    /// Returns the normalized words and their expansions.
+    pub fn iter(&self) -> impl Iterator<Item = (&[String], &[Vec<String>])> {
+        self.expansions.iter().map(|(words, expansions)| (words.as_slice(), expansions.as_slice()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.expansions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expansions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_lowercase).collect()
+    }
+
+    #[test]
+    fn mutual_and_one_way_rules() {
+        let rules = vec![
+            SynonymRule::Mutual(vec!["NYC".into(), "new york city".into(), "big apple".into()]),
+            SynonymRule::OneWay { from: "phone".into(), to: vec!["smartphone".into()] },
+            SynonymRule::OneWay { from: "phone".into(), to: vec!["mobile".into(), "".into()] },
+        ];
+        let synonyms = Synonyms::from_rules(&rules, normalize);
+
+        assert_eq!(
+            synonyms.get(&["nyc"]).unwrap(),
+            [normalize("big apple"), normalize("new york city")]
+        );
+        assert_eq!(
+            synonyms.get(&["new", "york", "city"]).unwrap(),
+            [normalize("big apple"), normalize("nyc")]
+        );
+        assert_eq!(
+            synonyms.get(&["phone"]).unwrap(),
+            [normalize("mobile"), normalize("smartphone")]
+        );
+        assert_eq!(synonyms.get(&["smartphone"]), None);
+        assert_eq!(synonyms.get(&["new", "york"]), None);
+    }
+}
@@ -241,9 +241,12 @@ impl<'t, 'u, 'i> DeleteDocuments<'t, 'u, 'i> {
             word_prefix_position_docids,
             facet_id_f64_docids: _,
             facet_id_string_docids: _,
+            facet_id_bool_docids,
             field_id_docid_facet_f64s: _,
             field_id_docid_facet_strings: _,
             facet_id_exists_docids,
+            facet_id_is_null_docids,
+            facet_id_is_empty_docids,
             documents,
         } = self.index;
 
@@ -499,13 +502,45 @@ impl<'t, 'u, 'i> DeleteDocuments<'t, 'u, 'i> {
             .execute(self.wtxn)?;
         }
 
+        // We only remove the documents ids of the boolean facets.
+        for field_id in self.index.faceted_fields_ids(self.wtxn)? {
+            let mut docids =
+                self.index.faceted_documents_ids(self.wtxn, field_id, FacetType::Boolean)?;
+            docids -= &self.to_delete_docids;
+            self.index.put_faceted_documents_ids(
+                self.wtxn,
+                field_id,
+                FacetType::Boolean,
+                &docids,
+            )?;
+        }
+        remove_docids_from_facet_id_docids(
+            self.wtxn,
+            facet_id_bool_docids,
+            &self.to_delete_docids,
+        )?;
+
         // We delete the documents ids that are under the facet field id values.
-        remove_docids_from_facet_id_exists_docids(
+        remove_docids_from_facet_id_docids(
             self.wtxn,
             facet_id_exists_docids,
             &self.to_delete_docids,
         )?;
 
+        // We delete the documents ids that are under the facet field id null values.
+        remove_docids_from_facet_id_docids(
+            self.wtxn,
+            facet_id_is_null_docids,
+            &self.to_delete_docids,
+        )?;
+
+        // We delete the documents ids that are under the facet field id empty values.
+        remove_docids_from_facet_id_docids(
+            self.wtxn,
+            facet_id_is_empty_docids,
+            &self.to_delete_docids,
+        )?;
+
         self.index.put_soft_deleted_documents_ids(self.wtxn, &RoaringBitmap::new())?;
 
         Ok(DetailedDocumentDeletionResult {
@@ -588,6 +623,8 @@ fn remove_docids_from_field_id_docid_facet_value(
         FacetType::Number => {
             index.field_id_docid_facet_f64s.remap_types::<ByteSlice, DecodeIgnore>()
         }
+        // The boolean facets are not stored by document, there is no facet value to remove.
+        FacetType::Boolean => return Ok(HashSet::new()),
     };
     let mut all_affected_facet_values = HashSet::default();
     let mut iter = db
@@ -608,7 +645,7 @@ fn remove_docids_from_field_id_docid_facet_value(
     Ok(all_affected_facet_values)
 }
 
-fn remove_docids_from_facet_id_exists_docids<'a, C>(
+fn remove_docids_from_facet_id_docids<'a, C>(
     wtxn: &'a mut heed::RwTxn,
     db: &heed::Database<C, CboRoaringBitmapCodec>,
     to_remove: &RoaringBitmap,
@@ -6,7 +6,10 @@ use heed::types::ByteSlice;
 use heed::{BytesEncode, Error, RoTxn, RwTxn};
 use roaring::RoaringBitmap;
 
-use super::{FACET_GROUP_SIZE, FACET_MIN_LEVEL_SIZE};
+use super::{
+    facet_levels_database, insert_boolean_facets, recompute_boolean_faceted_documents_ids,
+    FACET_GROUP_SIZE, FACET_MIN_LEVEL_SIZE,
+};
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{
     FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
@@ -71,12 +74,13 @@ impl<'i> FacetsUpdateBulk<'i> {
     pub fn execute(self, wtxn: &mut heed::RwTxn) -> Result<()> {
         let Self { index, field_ids, group_size, min_level_size, facet_type, new_data } = self;
 
-        let db = match facet_type {
-            FacetType::String => index
-                .facet_id_string_docids
-                .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
-            FacetType::Number => {
-                index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>()
+        let db = match facet_levels_database(index, facet_type) {
+            Some(db) => db,
+            None => {
+                if let Some(new_data) = new_data {
+                    insert_boolean_facets(index, wtxn, new_data)?;
+                }
+                return recompute_boolean_faceted_documents_ids(index, wtxn, &field_ids);
             }
         };
 
@@ -5,7 +5,10 @@ use log::debug;
 use roaring::RoaringBitmap;
 use time::OffsetDateTime;
 
-use super::{FACET_GROUP_SIZE, FACET_MAX_GROUP_SIZE, FACET_MIN_LEVEL_SIZE};
+use super::{
+    delete_boolean_facets, facet_levels_database, FACET_GROUP_SIZE, FACET_MAX_GROUP_SIZE,
+    FACET_MIN_LEVEL_SIZE,
+};
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec};
 use crate::heed_codec::ByteSliceRefCodec;
@@ -18,7 +21,7 @@ use crate::{FieldId, Index, Result};
 /// a bulk delete method or an incremental delete method.
 pub struct FacetsDelete<'i, 'b> {
     index: &'i Index,
-    database: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
+    database: Option<heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>>,
     facet_type: FacetType,
     affected_facet_values: HashMap<FieldId, HashSet<Vec<u8>>>,
     docids_to_delete: &'b RoaringBitmap,
@@ -33,14 +36,7 @@ impl<'i, 'b> FacetsDelete<'i, 'b> {
         affected_facet_values: HashMap<FieldId, HashSet<Vec<u8>>>,
         docids_to_delete: &'b RoaringBitmap,
     ) -> Self {
-        let database = match facet_type {
-            FacetType::String => index
-                .facet_id_string_docids
-                .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
-            FacetType::Number => {
-                index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>()
-            }
-        };
+        let database = facet_levels_database(index, facet_type);
         Self {
             index,
             database,
@@ -57,6 +53,14 @@ impl<'i, 'b> FacetsDelete<'i, 'b> {
         debug!("Computing and writing the facet values levels docids into LMDB on disk...");
         self.index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
 
+        let database = match self.database {
+            Some(database) => database,
+            None => {
+                let field_ids: Vec<_> = self.affected_facet_values.keys().copied().collect();
+                return delete_boolean_facets(self.index, wtxn, &field_ids, self.docids_to_delete);
+            }
+        };
+
         for (field_id, affected_facet_values) in self.affected_facet_values {
             // This is an incorrect condition, since we assume that the length of the database is equal
             // to the number of facet values for the given field_id. It means that in some cases, we might
@@ -65,22 +69,22 @@ impl<'i, 'b> FacetsDelete<'i, 'b> {
             // each field id. This would almost never happen. Still, to be overly cautious, I have added a
             // 2x penalty to the incremental indexer. That is, instead of assuming a 70x worst-case performance
             // penalty to the incremental indexer, we assume a 150x worst-case performance penalty instead.
-            if affected_facet_values.len() >= (self.database.len(wtxn)? / 150) {
+            if affected_facet_values.len() >= (database.len(wtxn)? / 150) {
                 // Bulk delete
                 let mut modified = false;
 
                 for facet_value in affected_facet_values {
                     let key =
                         FacetGroupKey { field_id, level: 0, left_bound: facet_value.as_slice() };
-                    let mut old = self.database.get(wtxn, &key)?.unwrap();
+                    let mut old = database.get(wtxn, &key)?.unwrap();
                     let previous_len = old.bitmap.len();
                     old.bitmap -= self.docids_to_delete;
                     if old.bitmap.is_empty() {
                         modified = true;
-                        self.database.delete(wtxn, &key)?;
+                        database.delete(wtxn, &key)?;
                     } else if old.bitmap.len() != previous_len {
                         modified = true;
-                        self.database.put(wtxn, &key, &old)?;
+                        database.put(wtxn, &key, &old)?;
                     }
                 }
                 if modified {
@@ -94,7 +98,7 @@ impl<'i, 'b> FacetsDelete<'i, 'b> {
             } else {
                 // Incremental
                 let inc = FacetsUpdateIncrementalInner {
-                    db: self.database,
+                    db: database,
                     group_size: self.group_size,
                     min_level_size: self.min_level_size,
                     max_group_size: self.max_group_size,
@@ -11,6 +11,7 @@ use crate::heed_codec::facet::{
 };
 use crate::heed_codec::ByteSliceRefCodec;
 use crate::search::facet::get_highest_level;
+use crate::update::facet::{facet_levels_database, insert_boolean_facets};
 use crate::update::index_documents::valid_lmdb_key;
 use crate::{CboRoaringBitmapCodec, FieldId, Index, Result};
 
@@ -32,7 +33,8 @@ enum DeletionResult {
 /// is also updated to contain the new set of faceted documents.
 pub struct FacetsUpdateIncremental<'i> {
     index: &'i Index,
-    inner: FacetsUpdateIncrementalInner,
+    // None for the boolean facets
+    inner: Option<FacetsUpdateIncrementalInner>,
     facet_type: FacetType,
     new_data: grenad::Reader<File>,
 }
@@ -48,25 +50,19 @@ impl<'i> FacetsUpdateIncremental<'i> {
     ) -> Self {
         FacetsUpdateIncremental {
             index,
-            inner: FacetsUpdateIncrementalInner {
-                db: match facet_type {
-                    FacetType::String => index
-                        .facet_id_string_docids
-                        .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
-                    FacetType::Number => index
-                        .facet_id_f64_docids
-                        .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
-                },
-                group_size,
-                max_group_size,
-                min_level_size,
-            },
+            inner: facet_levels_database(index, facet_type).map(|db| {
+                FacetsUpdateIncrementalInner { db, group_size, max_group_size, min_level_size }
+            }),
             facet_type,
             new_data,
         }
     }
 
     pub fn execute(self, wtxn: &'i mut RwTxn) -> crate::Result<()> {
+        let inner = match self.inner {
+            Some(inner) => inner,
+            None => return insert_boolean_facets(self.index, wtxn, self.new_data),
+        };
         let mut new_faceted_docids = HashMap::<FieldId, RoaringBitmap>::default();
 
         let mut cursor = self.new_data.into_cursor()?;
@@ -77,7 +73,7 @@ impl<'i> FacetsUpdateIncremental<'i> {
             let key = FacetGroupKeyCodec::<ByteSliceRefCodec>::bytes_decode(key)
                 .ok_or(heed::Error::Encoding)?;
             let docids = CboRoaringBitmapCodec::bytes_decode(value).ok_or(heed::Error::Encoding)?;
-            self.inner.insert(wtxn, key.field_id, key.left_bound, &docids)?;
+            inner.insert(wtxn, key.field_id, key.left_bound, &docids)?;
             *new_faceted_docids.entry(key.field_id).or_default() |= docids;
         }
 
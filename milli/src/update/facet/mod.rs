@@ -76,17 +76,21 @@ pub const FACET_MAX_GROUP_SIZE: u8 = 8;
 pub const FACET_GROUP_SIZE: u8 = 4;
 pub const FACET_MIN_LEVEL_SIZE: u8 = 5;
 
+use std::collections::HashMap;
 use std::fs::File;
 
+use heed::BytesDecode;
 use log::debug;
+use roaring::RoaringBitmap;
 use time::OffsetDateTime;
 
 use self::incremental::FacetsUpdateIncremental;
 use super::FacetsUpdateBulk;
 use crate::facet::FacetType;
-use crate::heed_codec::facet::{FacetGroupKeyCodec, FacetGroupValueCodec};
+use crate::heed_codec::facet::{FacetGroupKeyCodec, FacetGroupValueCodec, FieldIdBoolCodec};
 use crate::heed_codec::ByteSliceRefCodec;
-use crate::{Index, Result};
+use crate::update::index_documents::valid_lmdb_key;
+use crate::{CboRoaringBitmapCodec, FieldId, Index, Result};
 
 pub mod bulk;
 pub mod delete;
@@ -98,7 +102,7 @@ pub mod incremental;
 /// a bulk update method or an incremental update method.
 pub struct FacetsUpdate<'i> {
     index: &'i Index,
-    database: heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>,
+    database: Option<heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>>,
     facet_type: FacetType,
     new_data: grenad::Reader<File>,
     group_size: u8,
@@ -107,14 +111,7 @@ pub struct FacetsUpdate<'i> {
 }
 impl<'i> FacetsUpdate<'i> {
     pub fn new(index: &'i Index, facet_type: FacetType, new_data: grenad::Reader<File>) -> Self {
-        let database = match facet_type {
-            FacetType::String => index
-                .facet_id_string_docids
-                .remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
-            FacetType::Number => {
-                index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>()
-            }
-        };
+        let database = facet_levels_database(index, facet_type);
         Self {
             index,
             database,
@@ -133,8 +130,13 @@ impl<'i> FacetsUpdate<'i> {
         debug!("Computing and writing the facet values levels docids into LMDB on disk...");
         self.index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
 
+        let database = match self.database {
+            Some(database) => database,
+            None => return insert_boolean_facets(self.index, wtxn, self.new_data),
+        };
+
         // See self::comparison_bench::benchmark_facet_indexing
-        if self.new_data.len() >= (self.database.len(wtxn)? as u64 / 50) {
+        if self.new_data.len() >= (database.len(wtxn)? as u64 / 50) {
             let field_ids =
                 self.index.faceted_fields_ids(wtxn)?.iter().copied().collect::<Vec<_>>();
             let bulk_update = FacetsUpdateBulk::new(
@@ -161,6 +163,96 @@ impl<'i> FacetsUpdate<'i> {
     }
 }
 
+/// Returns the database in which the facet values of the given type are stored in levels,
+/// or `None` for the boolean facets.
+///
+/// The boolean facets are not stored in levels, there are only two values per field and their
+/// documents ids are directly stored in the `facet_id_bool_docids` database.
+pub(crate) fn facet_levels_database(
+    index: &Index,
+    facet_type: FacetType,
+) -> Option<heed::Database<FacetGroupKeyCodec<ByteSliceRefCodec>, FacetGroupValueCodec>> {
+    match facet_type {
+        FacetType::String => Some(
+            index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
+        ),
+        FacetType::Number => Some(
+            index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<ByteSliceRefCodec>>(),
+        ),
+        FacetType::Boolean => None,
+    }
+}
+
+/// Merges the `(field_id, bool)` keys and docids of `new_data` into the `facet_id_bool_docids`
+/// database and adds the new documents to the boolean `faceted_documents_ids` of their field.
+pub(crate) fn insert_boolean_facets(
+    index: &Index,
+    wtxn: &mut heed::RwTxn,
+    new_data: grenad::Reader<File>,
+) -> Result<()> {
+    let mut new_faceted_docids = HashMap::<FieldId, RoaringBitmap>::new();
+    let mut cursor = new_data.into_cursor()?;
+    while let Some((key, value)) = cursor.move_on_next()? {
+        if !valid_lmdb_key(key) {
+            continue;
+        }
+        let (field_id, boolean) =
+            FieldIdBoolCodec::bytes_decode(key).ok_or(heed::Error::Encoding)?;
+        let docids = CboRoaringBitmapCodec::bytes_decode(value).ok_or(heed::Error::Encoding)?;
+
+        let mut all_docids = index.bool_faceted_documents_ids(wtxn, field_id, boolean)?;
+        all_docids |= &docids;
+        index.facet_id_bool_docids.put(wtxn, &(field_id, boolean), &all_docids)?;
+
+        *new_faceted_docids.entry(field_id).or_default() |= docids;
+    }
+
+    for (field_id, new_docids) in new_faceted_docids {
+        let mut docids = index.faceted_documents_ids(wtxn, field_id, FacetType::Boolean)?;
+        docids |= new_docids;
+        index.put_faceted_documents_ids(wtxn, field_id, FacetType::Boolean, &docids)?;
+    }
+    Ok(())
+}
+
+/// Removes the `to_delete` documents from the `facet_id_bool_docids` entries of the given fields
+/// and recomputes their boolean `faceted_documents_ids`.
+pub(crate) fn delete_boolean_facets(
+    index: &Index,
+    wtxn: &mut heed::RwTxn,
+    field_ids: &[FieldId],
+    to_delete: &RoaringBitmap,
+) -> Result<()> {
+    for &field_id in field_ids {
+        for boolean in [false, true] {
+            let mut docids = index.bool_faceted_documents_ids(wtxn, field_id, boolean)?;
+            let previous_len = docids.len();
+            docids -= to_delete;
+            if docids.is_empty() {
+                index.facet_id_bool_docids.delete(wtxn, &(field_id, boolean))?;
+            } else if docids.len() != previous_len {
+                index.facet_id_bool_docids.put(wtxn, &(field_id, boolean), &docids)?;
+            }
+        }
+    }
+    recompute_boolean_faceted_documents_ids(index, wtxn, field_ids)
+}
+
+/// Recomputes the boolean `faceted_documents_ids` of the given fields from the content
+/// of the `facet_id_bool_docids` database.
+pub(crate) fn recompute_boolean_faceted_documents_ids(
+    index: &Index,
+    wtxn: &mut heed::RwTxn,
+    field_ids: &[FieldId],
+) -> Result<()> {
+    for &field_id in field_ids {
+        let docids = index.bool_faceted_documents_ids(wtxn, field_id, false)?
+            | index.bool_faceted_documents_ids(wtxn, field_id, true)?;
+        index.put_faceted_documents_ids(wtxn, field_id, FacetType::Boolean, &docids)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 pub(crate) mod test_helpers {
     use std::cell::Cell;
@@ -457,13 +549,19 @@ pub(crate) mod test_helpers {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::iter::FromIterator;
+
     use big_s::S;
     use maplit::hashset;
+    use roaring::RoaringBitmap;
 
+    use super::delete::FacetsDelete;
     use crate::db_snap;
     use crate::documents::documents_batch_reader_from_objects;
+    use crate::facet::FacetType;
     use crate::index::tests::TempIndex;
-    use crate::update::DeletionStrategy;
+    use crate::update::{DeletionStrategy, FacetsUpdateBulk};
 
     #[test]
     fn replace_all_identical_soft_deletion_then_hard_deletion() {
@@ -548,6 +646,43 @@ mod tests {
         db_snap!(index, number_faceted_documents_ids, "replaced_2_hard", @"60b19824f136affe6b240a7200779028");
         db_snap!(index, soft_deleted_documents_ids, "replaced_2_hard", @"[]");
     }
+
+    #[test]
+    fn boolean_facets_are_not_stored_in_levels() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("available") });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "available": true },
+                { "id": 1, "available": false },
+                { "id": 2, "available": true },
+            ]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let field_id = index.fields_ids_map(&wtxn).unwrap().id("available").unwrap();
+
+        FacetsUpdateBulk::new_not_updating_level_0(&index, vec![field_id], FacetType::Boolean)
+            .execute(&mut wtxn)
+            .unwrap();
+        let faceted = index.faceted_documents_ids(&wtxn, field_id, FacetType::Boolean).unwrap();
+        assert_eq!(faceted, RoaringBitmap::from_iter([0, 1, 2]));
+
+        let to_delete = RoaringBitmap::from_iter([0]);
+        let affected_facet_values = HashMap::from_iter([(field_id, HashSet::new())]);
+        FacetsDelete::new(&index, FacetType::Boolean, affected_facet_values, &to_delete)
+            .execute(&mut wtxn)
+            .unwrap();
+        let trues = index.bool_faceted_documents_ids(&wtxn, field_id, true).unwrap();
+        assert_eq!(trues, RoaringBitmap::from_iter([2]));
+        let faceted = index.faceted_documents_ids(&wtxn, field_id, FacetType::Boolean).unwrap();
+        assert_eq!(faceted, RoaringBitmap::from_iter([1, 2]));
+    }
 }
 
 #[allow(unused)]
@@ -370,31 +370,49 @@ pub fn extract_finite_float_from_value(value: Value) -> StdResult<f64, Value> {
     }
 }
 
+/// Validates the `_geo` field of a document, it can either be a single point
+/// `{ "lat": 12, "lng": 42 }` or an array of points.
 pub fn validate_geo_from_json(id: &DocumentId, bytes: &[u8]) -> Result<StdResult<(), GeoError>> {
     use GeoError::*;
     let debug_id = || {
         serde_json::from_slice(id.value().as_bytes()).unwrap_or_else(|_| Value::from(id.debug()))
     };
     match serde_json::from_slice(bytes).map_err(InternalError::SerdeJson)? {
-        Value::Object(mut object) => match (object.remove("lat"), object.remove("lng")) {
-            (Some(lat), Some(lng)) => {
-                match (extract_finite_float_from_value(lat), extract_finite_float_from_value(lng)) {
-                    (Ok(_), Ok(_)) if !object.is_empty() => Ok(Err(UnexpectedExtraFields {
-                        document_id: debug_id(),
-                        value: object.into(),
-                    })),
-                    (Ok(_), Ok(_)) => Ok(Ok(())),
-                    (Err(value), Ok(_)) => Ok(Err(BadLatitude { document_id: debug_id(), value })),
-                    (Ok(_), Err(value)) => Ok(Err(BadLongitude { document_id: debug_id(), value })),
-                    (Err(lat), Err(lng)) => {
-                        Ok(Err(BadLatitudeAndLongitude { document_id: debug_id(), lat, lng }))
-                    }
+        Value::Object(object) => Ok(validate_geo_point(object, debug_id)),
+        Value::Array(points) => {
+            for point in points {
+                let result = match point {
+                    Value::Object(object) => validate_geo_point(object, debug_id),
+                    value => Err(NotAnObject { document_id: debug_id(), value }),
+                };
+                if result.is_err() {
+                    return Ok(result);
                 }
             }
-            (None, Some(_)) => Ok(Err(MissingLatitude { document_id: debug_id() })),
-            (Some(_), None) => Ok(Err(MissingLongitude { document_id: debug_id() })),
-            (None, None) => Ok(Err(MissingLatitudeAndLongitude { document_id: debug_id() })),
-        },
+            Ok(Ok(()))
+        }
         value => Ok(Err(NotAnObject { document_id: debug_id(), value })),
     }
 }
+
+fn validate_geo_point(mut object: Object, debug_id: impl Fn() -> Value) -> StdResult<(), GeoError> {
+    use GeoError::*;
+    match (object.remove("lat"), object.remove("lng")) {
+        (Some(lat), Some(lng)) => {
+            match (extract_finite_float_from_value(lat), extract_finite_float_from_value(lng)) {
+                (Ok(_), Ok(_)) if !object.is_empty() => {
+                    Err(UnexpectedExtraFields { document_id: debug_id(), value: object.into() })
+                }
+                (Ok(_), Ok(_)) => Ok(()),
+                (Err(value), Ok(_)) => Err(BadLatitude { document_id: debug_id(), value }),
+                (Ok(_), Err(value)) => Err(BadLongitude { document_id: debug_id(), value }),
+                (Err(lat), Err(lng)) => {
+                    Err(BadLatitudeAndLongitude { document_id: debug_id(), lat, lng })
+                }
+            }
+        }
+        (None, Some(_)) => Err(MissingLatitude { document_id: debug_id() }),
+        (Some(_), None) => Err(MissingLongitude { document_id: debug_id() }),
+        (None, None) => Err(MissingLatitudeAndLongitude { document_id: debug_id() }),
+    }
+}
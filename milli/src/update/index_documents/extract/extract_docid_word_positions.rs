@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
 use std::fs::File;
 use std::{io, mem, str};
@@ -7,23 +7,34 @@ use charabia::{SeparatorKind, Token, TokenKind, TokenizerBuilder};
 use roaring::RoaringBitmap;
 use serde_json::Value;
 
-use super::helpers::{concat_u32s_array, create_sorter, sorter_into_reader, GrenadParameters};
+use super::helpers::{
+    concat_u32s_array, create_sorter, sorter_into_reader, GrenadParameters, MergeFn,
+};
 use crate::error::{InternalError, SerializationError};
 use crate::{
     absolute_from_relative_position, FieldId, Result, MAX_POSITION_PER_ATTRIBUTE, MAX_WORD_LENGTH,
 };
 
+/// The maximum number of words of the expressions expanded into their synonyms,
+/// the same as the longest ngrams looked up in the synonyms at search time.
+const MAX_SYNONYM_WORDS: usize = 3;
+
 /// Extracts the word and positions where this word appear and
 /// prefixes it by the document id.
 ///
 /// Returns the generated internal documents ids and a grenad reader
 /// with the list of extracted words from the given chunk of documents.
+///
+/// The synonyms of the words are extracted at the position of the first word they expand.
+/// All the words of the multi-word synonyms share this position, extracting them at
+/// consecutive positions would make them overlap with the words following the expression.
 #[logging_timer::time]
 pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
     indexer: GrenadParameters,
     searchable_fields: &Option<HashSet<FieldId>>,
     stop_words: Option<&fst::Set<&[u8]>>,
+    synonyms: &HashMap<Vec<String>, Vec<Vec<String>>>,
     max_positions_per_attributes: Option<u32>,
 ) -> Result<(RoaringBitmap, grenad::Reader<File>)> {
     let max_positions_per_attributes = max_positions_per_attributes
@@ -42,6 +53,8 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
 
     let mut key_buffer = Vec::new();
     let mut field_buffer = String::new();
+    // The last words of the field, the expressions expanded into their synonyms end with them.
+    let mut last_words = VecDeque::with_capacity(MAX_SYNONYM_WORDS);
     let mut builder = TokenizerBuilder::new();
     if let Some(stop_words) = stop_words {
         builder.stop_words(stop_words);
@@ -69,18 +82,46 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
                     let tokens = process_tokens(tokenizer.tokenize(field))
                         .take_while(|(p, _)| (*p as u32) < max_positions_per_attributes);
 
+                    last_words.clear();
                     for (index, token) in tokens {
                         let token = token.lemma().trim();
-                        if !token.is_empty() && token.len() <= MAX_WORD_LENGTH {
-                            key_buffer.truncate(mem::size_of::<u32>());
-                            key_buffer.extend_from_slice(token.as_bytes());
-
-                            let position: u16 = index
-                                .try_into()
-                                .map_err(|_| SerializationError::InvalidNumberSerialization)?;
-                            let position = absolute_from_relative_position(field_id, position);
-                            docid_word_positions_sorter
-                                .insert(&key_buffer, position.to_ne_bytes())?;
+                        if token.is_empty() || token.len() > MAX_WORD_LENGTH {
+                            continue;
+                        }
+
+                        insert_word_position(
+                            &mut docid_word_positions_sorter,
+                            &mut key_buffer,
+                            field_id,
+                            index,
+                            token,
+                        )?;
+
+                        if synonyms.is_empty() {
+                            continue;
+                        }
+
+                        if last_words.len() == MAX_SYNONYM_WORDS {
+                            last_words.pop_front();
+                        }
+                        last_words.push_back((index, token.to_string()));
+
+                        for start in 0..last_words.len() {
+                            let words: Vec<_> =
+                                last_words.iter().skip(start).map(|(_, w)| w.clone()).collect();
+                            if let Some(expansions) = synonyms.get(&words) {
+                                let (start_index, _) = last_words[start];
+                                let synonyms_words = expansions.iter().flatten();
+                                for word in synonyms_words.filter(|w| w.len() <= MAX_WORD_LENGTH) {
+                                    insert_word_position(
+                                        &mut docid_word_positions_sorter,
+                                        &mut key_buffer,
+                                        field_id,
+                                        start_index,
+                                        word,
+                                    )?;
+                                }
+                            }
                         }
                     }
                 }
@@ -91,6 +132,25 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
     sorter_into_reader(docid_word_positions_sorter, indexer).map(|reader| (documents_ids, reader))
 }
 
+/// Inserts the word at the given relative position of the field,
+/// the key buffer must start with the document id.
+fn insert_word_position(
+    sorter: &mut grenad::Sorter<MergeFn>,
+    key_buffer: &mut Vec<u8>,
+    field_id: FieldId,
+    index: usize,
+    word: &str,
+) -> Result<()> {
+    key_buffer.truncate(mem::size_of::<u32>());
+    key_buffer.extend_from_slice(word.as_bytes());
+
+    let position: u16 =
+        index.try_into().map_err(|_| SerializationError::InvalidNumberSerialization)?;
+    let position = absolute_from_relative_position(field_id, position);
+    sorter.insert(key_buffer.as_slice(), position.to_ne_bytes())?;
+    Ok(())
+}
+
 /// Transform a JSON value into a string that can be indexed.
 fn json_to_string<'a>(value: &'a Value, buffer: &'a mut String) -> Option<&'a str> {
     fn inner(value: &Value, output: &mut String) -> bool {
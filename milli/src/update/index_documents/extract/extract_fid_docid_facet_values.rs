@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::fs::File;
 use std::io;
@@ -11,20 +11,45 @@ use serde_json::Value;
 
 use super::helpers::{create_sorter, keep_first, sorter_into_reader, GrenadParameters};
 use crate::error::InternalError;
+use crate::facet::rfc3339_to_timestamp;
 use crate::facet::value_encoding::f64_into_bytes;
+use crate::heed_codec::facet::FieldIdBoolCodec;
 use crate::update::index_documents::{create_writer, writer_into_reader};
-use crate::{CboRoaringBitmapCodec, DocumentId, FieldId, Result, BEU32, MAX_FACET_VALUE_LENGTH};
+use crate::{
+    CboRoaringBitmapCodec, DocumentId, FieldId, FieldsIdsMap, Result, BEU32, MAX_FACET_VALUE_LENGTH,
+};
+
+/// The extracted facet values stored in grenad files by type.
+pub struct ExtractedFacetValues {
+    pub docid_fid_facet_numbers_chunk: grenad::Reader<File>,
+    pub docid_fid_facet_strings_chunk: grenad::Reader<File>,
+    pub fid_facet_exists_docids_chunk: grenad::Reader<File>,
+    pub fid_facet_is_null_docids_chunk: grenad::Reader<File>,
+    pub fid_facet_is_empty_docids_chunk: grenad::Reader<File>,
+    pub fid_facet_bool_docids_chunk: grenad::Reader<File>,
+}
 
 /// Extracts the facet values of each faceted field of each document.
 ///
 /// Returns the generated grenad reader containing the docid the fid and the orginal value as key
 /// and the normalized value as value extracted from the given chunk of documents.
+/// Also returns the documents ids in which each field exists, is null or is empty
+/// and the documents ids of the boolean values of each field.
+///
+/// The flattening replaces the objects and the arrays of objects by an empty array along with
+/// a field for each one of their keys, such a field is only empty when none of these exist.
+///
+/// The RFC 3339 strings of the `date_fields` are extracted as timestamps numbers
+/// and the strings of the `case_sensitive_facets` are kept as is instead of being normalized.
 #[logging_timer::time]
 pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
     indexer: GrenadParameters,
+    fields_ids_map: &FieldsIdsMap,
     faceted_fields: &HashSet<FieldId>,
-) -> Result<(grenad::Reader<File>, grenad::Reader<File>, grenad::Reader<File>)> {
+    date_fields: &HashSet<FieldId>,
+    case_sensitive_facets: &HashSet<FieldId>,
+) -> Result<ExtractedFacetValues> {
     let max_memory = indexer.max_memory_by_thread();
 
     let mut fid_docid_facet_numbers_sorter = create_sorter(
@@ -46,6 +71,10 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
     );
 
     let mut facet_exists_docids = BTreeMap::<FieldId, RoaringBitmap>::new();
+    let mut facet_is_null_docids = BTreeMap::<FieldId, RoaringBitmap>::new();
+    let mut facet_is_empty_docids = BTreeMap::<FieldId, RoaringBitmap>::new();
+    let mut facet_bool_docids = BTreeMap::<(FieldId, bool), RoaringBitmap>::new();
+    let nested_fields = nested_fields_ids(fields_ids_map, faceted_fields);
 
     let mut key_buffer = Vec::new();
     let mut cursor = obkv_documents.into_cursor()?;
@@ -72,7 +101,26 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                 let value =
                     serde_json::from_slice(field_bytes).map_err(InternalError::SerdeJson)?;
 
-                let (numbers, strings) = extract_facet_values(&value);
+                if value.is_null() {
+                    facet_is_null_docids.entry(field_id).or_default().insert(document);
+                } else if is_empty_value(&value) {
+                    let has_nested_fields = nested_fields.get(&field_id).map_or(false, |nested| {
+                        obkv.iter().any(|(nested_id, _)| nested.contains(&nested_id))
+                    });
+                    if !has_nested_fields {
+                        facet_is_empty_docids.entry(field_id).or_default().insert(document);
+                    }
+                }
+
+                let is_date = date_fields.contains(&field_id);
+                let is_case_sensitive = case_sensitive_facets.contains(&field_id);
+                let (numbers, strings, bools) =
+                    extract_facet_values(&value, is_date, is_case_sensitive);
+
+                // insert facet booleans in the map
+                for boolean in bools {
+                    facet_bool_docids.entry((field_id, boolean)).or_default().insert(document);
+                }
 
                 // insert facet numbers in sorter
                 for number in numbers {
@@ -101,47 +149,112 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
         }
     }
 
-    let mut facet_exists_docids_writer = create_writer(
+    Ok(ExtractedFacetValues {
+        docid_fid_facet_numbers_chunk: sorter_into_reader(fid_docid_facet_numbers_sorter, indexer)?,
+        docid_fid_facet_strings_chunk: sorter_into_reader(fid_docid_facet_strings_sorter, indexer)?,
+        fid_facet_exists_docids_chunk: fid_docids_into_reader(facet_exists_docids, indexer)?,
+        fid_facet_is_null_docids_chunk: fid_docids_into_reader(facet_is_null_docids, indexer)?,
+        fid_facet_is_empty_docids_chunk: fid_docids_into_reader(facet_is_empty_docids, indexer)?,
+        fid_facet_bool_docids_chunk: fid_bool_docids_into_reader(facet_bool_docids, indexer)?,
+    })
+}
+
+/// Writes the documents ids associated with each field id into a grenad file.
+fn fid_docids_into_reader(
+    fid_docids: BTreeMap<FieldId, RoaringBitmap>,
+    indexer: GrenadParameters,
+) -> Result<grenad::Reader<File>> {
+    let mut writer = create_writer(
         indexer.chunk_compression_type,
         indexer.chunk_compression_level,
         tempfile::tempfile()?,
     );
-    for (fid, bitmap) in facet_exists_docids.into_iter() {
+    for (fid, bitmap) in fid_docids.into_iter() {
         let bitmap_bytes = CboRoaringBitmapCodec::bytes_encode(&bitmap).unwrap();
-        facet_exists_docids_writer.insert(fid.to_be_bytes(), &bitmap_bytes)?;
+        writer.insert(fid.to_be_bytes(), &bitmap_bytes)?;
     }
-    let facet_exists_docids_reader = writer_into_reader(facet_exists_docids_writer)?;
+    writer_into_reader(writer)
+}
 
-    Ok((
-        sorter_into_reader(fid_docid_facet_numbers_sorter, indexer)?,
-        sorter_into_reader(fid_docid_facet_strings_sorter, indexer)?,
-        facet_exists_docids_reader,
-    ))
+/// Writes the documents ids associated with each field id and boolean into a grenad file.
+fn fid_bool_docids_into_reader(
+    fid_bool_docids: BTreeMap<(FieldId, bool), RoaringBitmap>,
+    indexer: GrenadParameters,
+) -> Result<grenad::Reader<File>> {
+    let mut writer = create_writer(
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        tempfile::tempfile()?,
+    );
+    for (key, bitmap) in fid_bool_docids.into_iter() {
+        let key_bytes = FieldIdBoolCodec::bytes_encode(&key).unwrap();
+        let bitmap_bytes = CboRoaringBitmapCodec::bytes_encode(&bitmap).unwrap();
+        writer.insert(key_bytes, &bitmap_bytes)?;
+    }
+    writer_into_reader(writer)
+}
+
+/// Returns the ids of the fields nested under each one of the faceted fields,
+/// i.e. the fields whose name starts with the name of the faceted field and a dot.
+fn nested_fields_ids(
+    fields_ids_map: &FieldsIdsMap,
+    faceted_fields: &HashSet<FieldId>,
+) -> HashMap<FieldId, HashSet<FieldId>> {
+    let mut nested_fields = HashMap::<FieldId, HashSet<FieldId>>::new();
+    for &field_id in faceted_fields {
+        if let Some(name) = fields_ids_map.name(field_id) {
+            let prefix = format!("{}.", name);
+            let nested = fields_ids_map
+                .iter()
+                .filter(|(_, nested_name)| nested_name.starts_with(&prefix))
+                .map(|(nested_id, _)| nested_id);
+            nested_fields.entry(field_id).or_default().extend(nested);
+        }
+    }
+    nested_fields
 }
 
-fn extract_facet_values(value: &Value) -> (Vec<f64>, Vec<(String, String)>) {
+/// Returns `true` if the value is an empty string, an empty array or an empty object.
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        Value::Null | Value::Bool(_) | Value::Number(_) => false,
+    }
+}
+
+fn extract_facet_values(
+    value: &Value,
+    is_date: bool,
+    is_case_sensitive: bool,
+) -> (Vec<f64>, Vec<(String, String)>, Vec<bool>) {
     fn inner_extract_facet_values(
         value: &Value,
         can_recurse: bool,
         output_numbers: &mut Vec<f64>,
-        output_strings: &mut Vec<(String, String)>,
+        output_strings: &mut Vec<String>,
+        output_bools: &mut Vec<bool>,
     ) {
         match value {
             Value::Null => (),
-            Value::Bool(b) => output_strings.push((b.to_string(), b.to_string())),
+            Value::Bool(b) => output_bools.push(*b),
             Value::Number(number) => {
                 if let Some(float) = number.as_f64() {
                     output_numbers.push(float);
                 }
             }
-            Value::String(original) => {
-                let normalized = original.trim().to_lowercase();
-                output_strings.push((normalized, original.clone()));
-            }
+            Value::String(original) => output_strings.push(original.clone()),
             Value::Array(values) => {
                 if can_recurse {
                     for value in values {
-                        inner_extract_facet_values(value, false, output_numbers, output_strings);
+                        inner_extract_facet_values(
+                            value,
+                            false,
+                            output_numbers,
+                            output_strings,
+                            output_bools,
+                        );
                     }
                 }
             }
@@ -150,8 +263,25 @@ fn extract_facet_values(value: &Value) -> (Vec<f64>, Vec<(String, String)>) {
     }
 
     let mut facet_number_values = Vec::new();
-    let mut facet_string_values = Vec::new();
-    inner_extract_facet_values(value, true, &mut facet_number_values, &mut facet_string_values);
+    let mut original_string_values = Vec::new();
+    let mut facet_bool_values = Vec::new();
+    inner_extract_facet_values(
+        value,
+        true,
+        &mut facet_number_values,
+        &mut original_string_values,
+        &mut facet_bool_values,
+    );
+
+    let mut facet_string_values = Vec::with_capacity(original_string_values.len());
+    for original in original_string_values {
+        let timestamp = if is_date { rfc3339_to_timestamp(&original) } else { None };
+        match timestamp {
+            Some(timestamp) => facet_number_values.push(timestamp),
+            None if is_case_sensitive => facet_string_values.push((original.clone(), original)),
+            None => facet_string_values.push((original.trim().to_lowercase(), original)),
+        }
+    }
 
-    (facet_number_values, facet_string_values)
+    (facet_number_values, facet_string_values, facet_bool_values)
 }
@@ -11,7 +11,8 @@ use crate::{FieldId, InternalError, Result};
 
 /// Extracts the geographical coordinates contained in each document under the `_geo` field.
 ///
-/// Returns the generated grenad reader containing the docid as key associated to the (latitude, longitude)
+/// Returns the generated grenad reader containing the docid as key associated to the
+/// (latitude, longitude) of all the points of the document, one after the other.
 #[logging_timer::time]
 pub fn extract_geo_points<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
@@ -25,6 +26,7 @@ pub fn extract_geo_points<R: io::Read + io::Seek>(
         tempfile::tempfile()?,
     );
 
+    let mut bytes = Vec::new();
     let mut cursor = obkv_documents.into_cursor()?;
     while let Some((docid_bytes, value)) = cursor.move_on_next()? {
         let obkv = obkv::KvReader::new(value);
@@ -40,20 +42,35 @@ pub fn extract_geo_points<R: io::Read + io::Seek>(
         let lng = obkv.get(lng_fid);
 
         if let Some((lat, lng)) = lat.zip(lng) {
-            // then we extract the values
-            let lat = extract_finite_float_from_value(
-                serde_json::from_slice(lat).map_err(InternalError::SerdeJson)?,
-            )
-            .map_err(|lat| GeoError::BadLatitude { document_id: document_id(), value: lat })?;
+            // the fields are flattened, when a document has several points
+            // all the latitudes and all the longitudes are stored in two arrays.
+            let lats = into_values(serde_json::from_slice(lat).map_err(InternalError::SerdeJson)?);
+            let lngs = into_values(serde_json::from_slice(lng).map_err(InternalError::SerdeJson)?);
+            if lats.len() < lngs.len() {
+                return Err(GeoError::MissingLatitude { document_id: document_id() })?;
+            } else if lats.len() > lngs.len() {
+                return Err(GeoError::MissingLongitude { document_id: document_id() })?;
+            }
 
-            let lng = extract_finite_float_from_value(
-                serde_json::from_slice(lng).map_err(InternalError::SerdeJson)?,
-            )
-            .map_err(|lng| GeoError::BadLongitude { document_id: document_id(), value: lng })?;
+            bytes.clear();
+            for (lat, lng) in lats.into_iter().zip(lngs) {
+                // then we extract the values
+                let lat = extract_finite_float_from_value(lat).map_err(|lat| {
+                    GeoError::BadLatitude { document_id: document_id(), value: lat }
+                })?;
 
-            #[allow(clippy::drop_non_drop)]
-            let bytes: [u8; 16] = concat_arrays![lat.to_ne_bytes(), lng.to_ne_bytes()];
-            writer.insert(docid_bytes, bytes)?;
+                let lng = extract_finite_float_from_value(lng).map_err(|lng| {
+                    GeoError::BadLongitude { document_id: document_id(), value: lng }
+                })?;
+
+                #[allow(clippy::drop_non_drop)]
+                let point: [u8; 16] = concat_arrays![lat.to_ne_bytes(), lng.to_ne_bytes()];
+                bytes.extend_from_slice(&point);
+            }
+
+            if !bytes.is_empty() {
+                writer.insert(docid_bytes, &bytes)?;
+            }
         } else if lat.is_none() && lng.is_some() {
             return Err(GeoError::MissingLatitude { document_id: document_id() })?;
         } else if lat.is_some() && lng.is_none() {
@@ -63,3 +80,11 @@ pub fn extract_geo_points<R: io::Read + io::Seek>(
 
     writer_into_reader(writer)
 }
+
+/// Returns the values of an array or the value itself if it is not an array.
+fn into_values(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    }
+}
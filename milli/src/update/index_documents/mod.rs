@@ -4,7 +4,7 @@ mod helpers;
 mod transform;
 mod typed_chunk;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{Cursor, Read, Seek};
 use std::iter::FromIterator;
 use std::num::NonZeroU32;
@@ -258,6 +258,10 @@ where
             self.index.searchable_fields_ids(self.wtxn)?.map(HashSet::from_iter);
         // get filterable fields for facet databases
         let faceted_fields = self.index.faceted_fields_ids(self.wtxn)?;
+        // get the faceted fields which must be parsed as dates
+        let date_fields = self.index.date_fields_ids(self.wtxn)?;
+        // get the faceted fields which must not be normalized
+        let case_sensitive_facets = self.index.case_sensitive_facets_ids(self.wtxn)?;
         // get the fid of the `_geo.lat` and `_geo.lng` fields.
         let geo_fields_ids = match self.index.fields_ids_map(self.wtxn)?.id("_geo") {
             Some(gfid) => {
@@ -280,6 +284,11 @@ where
         };
 
         let stop_words = self.index.stop_words(self.wtxn)?;
+        let synonyms = if self.index.index_time_synonyms(self.wtxn)? {
+            self.index.all_synonyms(self.wtxn)?
+        } else {
+            HashMap::new()
+        };
         let exact_attributes = self.index.exact_attributes_ids(self.wtxn)?;
 
         let pool_params = GrenadParameters {
@@ -310,11 +319,15 @@ where
                     flattened_chunk,
                     pool_params,
                     lmdb_writer_sx.clone(),
+                    fields_ids_map,
                     searchable_fields,
                     faceted_fields,
+                    date_fields,
+                    case_sensitive_facets,
                     primary_key_id,
                     geo_fields_ids,
                     stop_words,
+                    synonyms,
                     max_positions_per_attributes,
                     exact_attributes,
                 )
@@ -965,6 +978,54 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn multiple_geo_points() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset!(S("_geo")));
+                settings.set_sortable_fields(hashset!(S("_geo")));
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+              { "id": 0, "_geo": [{ "lat": 0, "lng": 0 }, { "lat": 50, "lng": 50 }] },
+              { "id": 1, "_geo": { "lat": 10, "lng": 10 } },
+              { "id": 2, "_geo": [{ "lat": 40, "lng": 40 }] },
+              { "id": 3, "_geo": [] },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let rtree = index.geo_rtree(&rtxn).unwrap().unwrap();
+        let mut points: Vec<_> = rtree.iter().map(|point| point.data).collect();
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            points,
+            vec![(0, [0.0, 0.0]), (0, [50.0, 50.0]), (1, [10.0, 10.0]), (2, [40.0, 40.0])]
+        );
+        let geo_faceted_documents_ids = index.geo_faceted_documents_ids(&rtxn).unwrap();
+        assert_eq!(geo_faceted_documents_ids.into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        // the documents are filtered on any of their points
+        let mut search = crate::Search::new(&rtxn, &index);
+        search.filter(crate::Filter::from_str("_geoRadius(50, 50, 1000)").unwrap().unwrap());
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert_eq!(documents_ids, vec![0]);
+
+        // and sorted by their nearest point
+        let mut search = crate::Search::new(&rtxn, &index);
+        search.sort_criteria(vec![crate::AscDesc::Asc(crate::Member::Geo([45.0, 45.0]))]);
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert_eq!(&documents_ids[..3], &[0, 2, 1]);
+
+        search.sort_criteria(vec![crate::AscDesc::Desc(crate::Member::Geo([45.0, 45.0]))]);
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert_eq!(&documents_ids[..3], &[1, 2, 0]);
+    }
+
     #[test]
     fn geo_error() {
         let mut index = TempIndex::new();
@@ -1025,6 +1086,26 @@ mod tests {
             &error.to_string(),
             r#"Could not parse longitude in the document with the id: `0`. Was expecting a finite number but instead got `"hello"`."#
         );
+
+        let error = index
+            .add_documents(documents!([
+              { "id": 0, "_geo": [{ "lat": 12, "lng": 42 }, { "lat": 12 }] }
+            ]))
+            .unwrap_err();
+        assert_eq!(
+            &error.to_string(),
+            r#"Could not find longitude in the document with the id: `0`. Was expecting a `_geo.lng` field."#
+        );
+
+        let error = index
+            .add_documents(documents!([
+              { "id": 0, "_geo": [{ "lat": 12, "lng": 42 }, 12] }
+            ]))
+            .unwrap_err();
+        assert_eq!(
+            &error.to_string(),
+            r#"The `_geo` field in the document with the id: `0` is not an object nor an array of objects. Was expecting objects with the `_geo.lat` and `_geo.lng` fields but instead got `12`."#
+        );
     }
 
     #[test]
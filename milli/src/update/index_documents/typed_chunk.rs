@@ -37,6 +37,9 @@ pub(crate) enum TypedChunk {
     FieldIdFacetStringDocids(grenad::Reader<File>),
     FieldIdFacetNumberDocids(grenad::Reader<File>),
     FieldIdFacetExistsDocids(grenad::Reader<File>),
+    FieldIdFacetIsNullDocids(grenad::Reader<File>),
+    FieldIdFacetIsEmptyDocids(grenad::Reader<File>),
+    FieldIdFacetBoolDocids(grenad::Reader<File>),
     GeoPoints(grenad::Reader<File>),
 }
 
@@ -158,6 +161,33 @@ pub(crate) fn write_typed_chunk_into_index(
             )?;
             is_merged_database = true;
         }
+        TypedChunk::FieldIdFacetIsNullDocids(facet_id_is_null_docids) => {
+            append_entries_into_database(
+                facet_id_is_null_docids,
+                &index.facet_id_is_null_docids,
+                wtxn,
+                index_is_empty,
+                |value, _buffer| Ok(value),
+                merge_cbo_roaring_bitmaps,
+            )?;
+            is_merged_database = true;
+        }
+        TypedChunk::FieldIdFacetIsEmptyDocids(facet_id_is_empty_docids) => {
+            append_entries_into_database(
+                facet_id_is_empty_docids,
+                &index.facet_id_is_empty_docids,
+                wtxn,
+                index_is_empty,
+                |value, _buffer| Ok(value),
+                merge_cbo_roaring_bitmaps,
+            )?;
+            is_merged_database = true;
+        }
+        TypedChunk::FieldIdFacetBoolDocids(facet_id_bool_docids) => {
+            let indexer = FacetsUpdate::new(index, FacetType::Boolean, facet_id_bool_docids);
+            indexer.execute(wtxn)?;
+            is_merged_database = true;
+        }
         TypedChunk::WordPairProximityDocids(word_pair_proximity_docids_iter) => {
             append_entries_into_database(
                 word_pair_proximity_docids_iter,
@@ -198,13 +228,16 @@ pub(crate) fn write_typed_chunk_into_index(
                 // convert the key back to a u32 (4 bytes)
                 let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
 
-                // convert the latitude and longitude back to a f64 (8 bytes)
-                let (lat, tail) = helpers::try_split_array_at::<u8, 8>(value).unwrap();
-                let (lng, _) = helpers::try_split_array_at::<u8, 8>(tail).unwrap();
-                let point = [f64::from_ne_bytes(lat), f64::from_ne_bytes(lng)];
-                let xyz_point = lat_lng_to_xyz(&point);
+                // a document can have several points, one after the other
+                for point in value.chunks_exact(16) {
+                    // convert the latitude and longitude back to a f64 (8 bytes)
+                    let (lat, tail) = helpers::try_split_array_at::<u8, 8>(point).unwrap();
+                    let (lng, _) = helpers::try_split_array_at::<u8, 8>(tail).unwrap();
+                    let point = [f64::from_ne_bytes(lat), f64::from_ne_bytes(lng)];
+                    let xyz_point = lat_lng_to_xyz(&point);
 
-                rtree.insert(GeoPoint::new(xyz_point, (docid, point)));
+                    rtree.insert(GeoPoint::new(xyz_point, (docid, point)));
+                }
                 geo_faceted_docids.insert(docid);
             }
             index.put_geo_rtree(wtxn, &rtree)?;
@@ -14,7 +14,7 @@ use crate::error::UserError;
 use crate::index::{DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
 use crate::update::index_documents::IndexDocumentsMethod;
 use crate::update::{IndexDocuments, UpdateIndexingStep};
-use crate::{FieldsIdsMap, Index, Result};
+use crate::{FieldsIdsMap, Index, Result, SynonymRule, Synonyms};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum Setting<T> {
@@ -110,10 +110,18 @@ pub struct Settings<'a, 't, 'u, 'i> {
     displayed_fields: Setting<Vec<String>>,
     filterable_fields: Setting<HashSet<String>>,
     sortable_fields: Setting<HashSet<String>>,
+    /// Faceted attributes whose RFC 3339 strings are indexed as dates.
+    date_fields: Setting<HashSet<String>>,
+    /// Faceted attributes whose string values are matched exactly, without normalization.
+    case_sensitive_facets: Setting<HashSet<String>>,
     criteria: Setting<Vec<Criterion>>,
     stop_words: Setting<BTreeSet<String>>,
     distinct_field: Setting<String>,
     synonyms: Setting<HashMap<String, Vec<String>>>,
+    /// Mutual and one-way synonyms.
+    synonym_rules: Setting<Vec<SynonymRule>>,
+    /// Expands the synonyms when indexing the documents rather than when searching.
+    index_time_synonyms: Setting<bool>,
     primary_key: Setting<String>,
     authorize_typos: Setting<bool>,
     min_word_len_two_typos: Setting<u8>,
@@ -138,10 +146,14 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
             displayed_fields: Setting::NotSet,
             filterable_fields: Setting::NotSet,
             sortable_fields: Setting::NotSet,
+            date_fields: Setting::NotSet,
+            case_sensitive_facets: Setting::NotSet,
             criteria: Setting::NotSet,
             stop_words: Setting::NotSet,
             distinct_field: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_rules: Setting::NotSet,
+            index_time_synonyms: Setting::NotSet,
             primary_key: Setting::NotSet,
             authorize_typos: Setting::NotSet,
             exact_words: Setting::NotSet,
@@ -186,6 +198,22 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
         self.sortable_fields = Setting::Reset;
     }
 
+    pub fn set_date_fields(&mut self, names: HashSet<String>) {
+        self.date_fields = Setting::Set(names);
+    }
+
+    pub fn reset_date_fields(&mut self) {
+        self.date_fields = Setting::Reset;
+    }
+
+    pub fn set_case_sensitive_facets(&mut self, names: HashSet<String>) {
+        self.case_sensitive_facets = Setting::Set(names);
+    }
+
+    pub fn reset_case_sensitive_facets(&mut self) {
+        self.case_sensitive_facets = Setting::Reset;
+    }
+
     pub fn reset_criteria(&mut self) {
         self.criteria = Setting::Reset;
     }
@@ -219,6 +247,22 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
         self.synonyms = if synonyms.is_empty() { Setting::Reset } else { Setting::Set(synonyms) }
     }
 
+    pub fn reset_synonym_rules(&mut self) {
+        self.synonym_rules = Setting::Reset;
+    }
+
+    pub fn set_synonym_rules(&mut self, rules: Vec<SynonymRule>) {
+        self.synonym_rules = if rules.is_empty() { Setting::Reset } else { Setting::Set(rules) }
+    }
+
+    pub fn reset_index_time_synonyms(&mut self) {
+        self.index_time_synonyms = Setting::Reset;
+    }
+
+    pub fn set_index_time_synonyms(&mut self, value: bool) {
+        self.index_time_synonyms = Setting::Set(value);
+    }
+
     pub fn reset_primary_key(&mut self) {
         self.primary_key = Setting::Reset;
     }
@@ -436,19 +480,6 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
     fn update_synonyms(&mut self) -> Result<bool> {
         match self.synonyms {
             Setting::Set(ref synonyms) => {
-                fn normalize(tokenizer: &Tokenizer<&[u8]>, text: &str) -> Vec<String> {
-                    tokenizer
-                        .tokenize(text)
-                        .filter_map(|token| {
-                            if token.is_word() {
-                                Some(token.lemma().to_string())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                }
-
                 let mut builder = TokenizerBuilder::new();
                 let stop_words = self.index.stop_words(self.wtxn)?;
                 if let Some(ref stop_words) = stop_words {
@@ -459,9 +490,9 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
                 let mut new_synonyms = HashMap::new();
                 for (word, synonyms) in synonyms {
                     // Normalize both the word and associated synonyms.
-                    let normalized_word = normalize(&tokenizer, word);
+                    let normalized_word = normalize_synonym(&tokenizer, word);
                     let normalized_synonyms =
-                        synonyms.iter().map(|synonym| normalize(&tokenizer, synonym));
+                        synonyms.iter().map(|synonym| normalize_synonym(&tokenizer, synonym));
 
                     // Store the normalized synonyms under the normalized word,
                     // merging the possible duplicate words.
@@ -489,6 +520,49 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
         }
     }
 
+    fn update_synonym_rules(&mut self) -> Result<bool> {
+        match self.synonym_rules {
+            Setting::Set(ref rules) => {
+                let mut builder = TokenizerBuilder::new();
+                let stop_words = self.index.stop_words(self.wtxn)?;
+                if let Some(ref stop_words) = stop_words {
+                    builder.stop_words(stop_words);
+                }
+                let tokenizer = builder.build();
+
+                let expansions =
+                    Synonyms::from_rules(rules, |text| normalize_synonym(&tokenizer, text));
+
+                let old_rules = self.index.synonym_rules(self.wtxn)?;
+                let old_expansions = self.index.synonym_expansions(self.wtxn)?;
+
+                if *rules != old_rules || expansions != old_expansions {
+                    self.index.put_synonym_rules(self.wtxn, rules, &expansions)?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Setting::Reset => Ok(self.index.delete_synonym_rules(self.wtxn)?),
+            Setting::NotSet => Ok(false),
+        }
+    }
+
+    fn update_index_time_synonyms(&mut self) -> Result<bool> {
+        let old_value = self.index.index_time_synonyms(self.wtxn)?;
+        match self.index_time_synonyms {
+            Setting::Set(value) => {
+                self.index.put_index_time_synonyms(self.wtxn, value)?;
+                Ok(value != old_value)
+            }
+            Setting::Reset => {
+                self.index.delete_index_time_synonyms(self.wtxn)?;
+                Ok(old_value)
+            }
+            Setting::NotSet => Ok(false),
+        }
+    }
+
     fn update_exact_attributes(&mut self) -> Result<bool> {
         match self.exact_attributes {
             Setting::Set(ref attrs) => {
@@ -542,6 +616,38 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
         Ok(())
     }
 
+    fn update_date_fields(&mut self) -> Result<bool> {
+        match self.date_fields {
+            Setting::Set(ref fields) => {
+                let old_fields = self.index.date_fields(self.wtxn)?;
+                if fields != &old_fields {
+                    self.index.put_date_fields(self.wtxn, fields)?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Setting::Reset => Ok(self.index.delete_date_fields(self.wtxn)?),
+            Setting::NotSet => Ok(false),
+        }
+    }
+
+    fn update_case_sensitive_facets(&mut self) -> Result<bool> {
+        match self.case_sensitive_facets {
+            Setting::Set(ref fields) => {
+                let old_fields = self.index.case_sensitive_facets(self.wtxn)?;
+                if fields != &old_fields {
+                    self.index.put_case_sensitive_facets(self.wtxn, fields)?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Setting::Reset => Ok(self.index.delete_case_sensitive_facets(self.wtxn)?),
+            Setting::NotSet => Ok(false),
+        }
+    }
+
     fn update_criteria(&mut self) -> Result<()> {
         match &self.criteria {
             Setting::Set(criteria) => {
@@ -720,12 +826,22 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
 
         let stop_words_updated = self.update_stop_words()?;
         let synonyms_updated = self.update_synonyms()?;
+        let index_time_synonyms_updated = self.update_index_time_synonyms()?;
+        // The synonym rules are only part of the indexed words when they are expanded at indexing.
+        let synonym_rules_updated =
+            self.update_synonym_rules()? && self.index.index_time_synonyms(self.wtxn)?;
         let searchable_updated = self.update_searchable()?;
         let exact_attributes_updated = self.update_exact_attributes()?;
+        let date_fields_updated = self.update_date_fields()?;
+        let case_sensitive_facets_updated = self.update_case_sensitive_facets()?;
 
         if stop_words_updated
             || faceted_updated
+            || date_fields_updated
+            || case_sensitive_facets_updated
             || synonyms_updated
+            || index_time_synonyms_updated
+            || synonym_rules_updated
             || searchable_updated
             || exact_attributes_updated
         {
@@ -736,6 +852,14 @@ impl<'a, 't, 'u, 'i> Settings<'a, 't, 'u, 'i> {
     }
 }
 
+/// Splits a synonym expression into its normalized words, the stop words are ignored.
+fn normalize_synonym(tokenizer: &Tokenizer<&[u8]>, text: &str) -> Vec<String> {
+    tokenizer
+        .tokenize(text)
+        .filter_map(|token| if token.is_word() { Some(token.lemma().to_string()) } else { None })
+        .collect::<Vec<_>>()
+}
+
 #[cfg(test)]
 mod tests {
     use big_s::S;
@@ -1249,6 +1373,122 @@ mod tests {
         assert!(result.documents_ids.is_empty());
     }
 
+    #[test]
+    fn set_and_reset_synonym_rules() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "city": "I love New York City" },
+                { "id": 1, "city": "NYC is great" },
+                { "id": 2, "city": "a new smartphone" },
+                { "id": 3, "city": "a new phone" },
+            ]))
+            .unwrap();
+
+        index
+            .update_settings(|settings| {
+                settings.set_synonym_rules(vec![
+                    SynonymRule::Mutual(vec![S("nyc"), S("new york city")]),
+                    SynonymRule::OneWay { from: S("phone"), to: vec![S("smartphone")] },
+                ]);
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.synonym_rules(&rtxn).unwrap().len(), 2);
+        // the legacy synonyms are left untouched
+        assert!(index.synonyms(&rtxn).unwrap().is_empty());
+
+        let mut result = index.search(&rtxn).query("nyc").execute().unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+        let mut result = index.search(&rtxn).query("new york city").execute().unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+        let result = index.search(&rtxn).query("phone").execute().unwrap();
+        assert_eq!(result.documents_ids.len(), 2);
+        // the one-way rule isn't expanded in the other direction
+        let result = index.search(&rtxn).query("smartphone").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![2]);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.reset_synonym_rules();
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.synonym_rules(&rtxn).unwrap().is_empty());
+        let result = index.search(&rtxn).query("nyc").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![1]);
+    }
+
+    #[test]
+    fn index_time_synonyms() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "city": "I love New York City" },
+                { "id": 1, "city": "NYC is great" },
+            ]))
+            .unwrap();
+
+        index
+            .update_settings(|settings| {
+                settings.set_synonym_rules(vec![SynonymRule::Mutual(vec![
+                    S("nyc"),
+                    S("new york city"),
+                ])]);
+                settings.set_index_time_synonyms(true);
+            })
+            .unwrap();
+
+        // the synonyms are indexed at the position of the first word they expand
+        let rtxn = index.read_txn().unwrap();
+        let nyc = index.word_docids.get(&rtxn, "nyc").unwrap().unwrap();
+        assert_eq!(nyc.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+        let york = index.word_docids.get(&rtxn, "york").unwrap().unwrap();
+        assert_eq!(york.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+        let mut result = index.search(&rtxn).query("nyc").execute().unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+        let mut result = index.search(&rtxn).query("new york city").execute().unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+        // the words of the expansion do not overlap with the words following the expression
+        let result = index.search(&rtxn).query("\"york great\"").execute().unwrap();
+        assert!(result.documents_ids.is_empty());
+        let result = index.search(&rtxn).query("\"nyc is great\"").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![1]);
+        drop(rtxn);
+
+        // the new documents are indexed with their synonyms too
+        index.add_documents(documents!([{ "id": 2, "city": "nyc" }])).unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let york = index.word_docids.get(&rtxn, "york").unwrap().unwrap();
+        assert_eq!(york.into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+        drop(rtxn);
+
+        // disabling the option reindexes the documents without their synonyms
+        index
+            .update_settings(|settings| {
+                settings.set_index_time_synonyms(false);
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(!index.index_time_synonyms(&rtxn).unwrap());
+        let york = index.word_docids.get(&rtxn, "york").unwrap().unwrap();
+        assert_eq!(york.into_iter().collect::<Vec<_>>(), vec![0]);
+        // but they are still expanded at search time
+        let mut result = index.search(&rtxn).query("nyc").execute().unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0, 1, 2]);
+    }
+
     #[test]
     fn setting_searchable_recomputes_other_settings() {
         let index = TempIndex::new();
@@ -1489,10 +1729,14 @@ mod tests {
                     displayed_fields,
                     filterable_fields,
                     sortable_fields,
+                    date_fields,
+                    case_sensitive_facets,
                     criteria,
                     stop_words,
                     distinct_field,
                     synonyms,
+                    synonym_rules,
+                    index_time_synonyms,
                     primary_key,
                     authorize_typos,
                     min_word_len_two_typos,
@@ -1506,10 +1750,14 @@ mod tests {
                 assert!(matches!(displayed_fields, Setting::NotSet));
                 assert!(matches!(filterable_fields, Setting::NotSet));
                 assert!(matches!(sortable_fields, Setting::NotSet));
+                assert!(matches!(date_fields, Setting::NotSet));
+                assert!(matches!(case_sensitive_facets, Setting::NotSet));
                 assert!(matches!(criteria, Setting::NotSet));
                 assert!(matches!(stop_words, Setting::NotSet));
                 assert!(matches!(distinct_field, Setting::NotSet));
                 assert!(matches!(synonyms, Setting::NotSet));
+                assert!(matches!(synonym_rules, Setting::NotSet));
+                assert!(matches!(index_time_synonyms, Setting::NotSet));
                 assert!(matches!(primary_key, Setting::NotSet));
                 assert!(matches!(authorize_typos, Setting::NotSet));
                 assert!(matches!(min_word_len_two_typos, Setting::NotSet));
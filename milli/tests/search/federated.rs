@@ -0,0 +1,38 @@
+use milli::{Criterion, FederatedSearch, Search, TermsMatchingStrategy};
+use Criterion::*;
+
+use crate::search::{self, EXTERNAL_DOCUMENTS_IDS};
+
+#[test]
+fn federated_search_merges_hits_by_score() {
+    let criteria = vec![Words, Typo, Proximity, Attribute, Exactness];
+    let first_index = search::setup_search_index_with_criteria(&criteria);
+    let second_index = search::setup_search_index_with_criteria(&criteria[..2]);
+    let first_rtxn = first_index.read_txn().unwrap();
+    let second_rtxn = second_index.read_txn().unwrap();
+
+    let mut first_search = Search::new(&first_rtxn, &first_index);
+    first_search.query(search::TEST_QUERY);
+    first_search.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let mut second_search = Search::new(&second_rtxn, &second_index);
+    second_search.query(search::TEST_QUERY);
+    second_search.terms_matching_strategy(TermsMatchingStrategy::Last);
+
+    let mut federated = FederatedSearch::new();
+    federated.search(first_search).search(second_search).limit(EXTERNAL_DOCUMENTS_IDS.len());
+    let result = federated.execute().unwrap();
+
+    assert_eq!(result.candidates.len(), 2);
+    assert_eq!(result.hits.len(), EXTERNAL_DOCUMENTS_IDS.len());
+    assert!(result.hits.iter().any(|hit| hit.search_index == 0));
+    assert!(result.hits.iter().any(|hit| hit.search_index == 1));
+    assert!(result.hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+    // the pagination is applied on the merged list of hits.
+    federated.offset(3).limit(4);
+    let page = federated.execute().unwrap();
+    let page: Vec<_> = page.hits.iter().map(|hit| (hit.search_index, hit.document_id)).collect();
+    let expected: Vec<_> =
+        result.hits[3..7].iter().map(|hit| (hit.search_index, hit.document_id)).collect();
+    assert_eq!(page, expected);
+}
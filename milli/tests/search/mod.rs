@@ -14,6 +14,7 @@ use slice_group_by::GroupBy;
 
 mod distinct;
 mod facet_distribution;
+mod federated;
 mod filters;
 mod phrase_search;
 mod query_criteria;
@@ -468,3 +468,24 @@ fn criteria_ascdesc() {
         assert_eq!(documents_ids, expected_document_ids);
     }
 }
+
+#[test]
+fn ranking_scores() {
+    let criteria = vec![Words, Typo, Proximity, Attribute, Exactness];
+    let index = search::setup_search_index_with_criteria(&criteria);
+    let rtxn = index.read_txn().unwrap();
+
+    let mut search = Search::new(&rtxn, &index);
+    search.query(search::TEST_QUERY);
+    search.limit(EXTERNAL_DOCUMENTS_IDS.len());
+    search.terms_matching_strategy(ALLOW_OPTIONAL_WORDS);
+
+    let result = search.execute().unwrap();
+    assert_eq!(result.document_scores.len(), result.documents_ids.len());
+    assert!(result.document_scores.iter().all(|details| details.len() == criteria.len()));
+
+    // the documents are returned from the most relevant to the least relevant one.
+    let scores: Vec<_> = result.ranking_scores().collect();
+    assert!(scores.iter().all(|score| (0.0..=1.0).contains(score)), "{scores:?}");
+    assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]), "{scores:?}");
+}